@@ -0,0 +1,115 @@
+//! Frame costs at large terminal sizes. Run with
+//! `cargo bench --profile profiling` to keep symbols for a profiler.
+
+use criterion::{BenchmarkId, Criterion, criterion_group, criterion_main};
+use crossterm::style::Color;
+use rand::SeedableRng;
+use rand::rngs::StdRng;
+use std::hint::black_box;
+use std::io;
+use weathr::animation::fog::FogSystem;
+use weathr::animation::raindrops::RaindropSystem;
+use weathr::animation::snow::SnowSystem;
+use weathr::animation::stars::StarSystem;
+use weathr::render::{StdoutBackend, TerminalRenderer};
+use weathr::weather::{FogIntensity, RainIntensity, SnowIntensity};
+
+const SIZES: [(u16, u16); 3] = [(120, 40), (240, 70), (400, 120)];
+const LARGE: (u16, u16) = (400, 120);
+
+/// A renderer that encodes frames exactly as for a terminal but throws the
+/// bytes away.
+fn sink_renderer((width, height): (u16, u16)) -> TerminalRenderer {
+    let backend = StdoutBackend::with_writer(Box::new(io::sink()));
+    TerminalRenderer::with_backend(width, height, Box::new(backend))
+}
+
+/// Fill the screen so that every cell differs from the previous frame.
+fn fill(renderer: &mut TerminalRenderer, (width, height): (u16, u16), frame: u32) {
+    const COLORS: [Color; 4] = [Color::Blue, Color::White, Color::DarkGrey, Color::Cyan];
+    for y in 0..height {
+        for x in 0..width {
+            let n = x as u32 + y as u32 + frame;
+            let ch = if frame % 2 == 0 { '|' } else { '.' };
+            renderer
+                .render_char(x, y, ch, COLORS[n as usize % COLORS.len()])
+                .unwrap();
+        }
+    }
+}
+
+fn bench_flush(c: &mut Criterion) {
+    let mut group = c.benchmark_group("flush");
+    for size in SIZES {
+        let id = format!("{}x{}", size.0, size.1);
+        group.bench_with_input(BenchmarkId::new("full_redraw", &id), &size, |b, &size| {
+            let mut renderer = sink_renderer(size);
+            let mut frame = 0;
+            b.iter(|| {
+                frame += 1;
+                fill(&mut renderer, size, frame);
+                renderer.flush().unwrap();
+            });
+        });
+        group.bench_with_input(BenchmarkId::new("unchanged", &id), &size, |b, &size| {
+            let mut renderer = sink_renderer(size);
+            fill(&mut renderer, size, 0);
+            b.iter(|| {
+                fill(&mut renderer, size, 0);
+                renderer.flush().unwrap();
+            });
+        });
+    }
+    group.finish();
+}
+
+/// One simulation step plus drawing, for the systems with the most
+/// particles.
+fn bench_particles(c: &mut Criterion) {
+    let (width, height) = LARGE;
+    let mut group = c.benchmark_group(format!("particles_{}x{}", width, height));
+    let mut rng = StdRng::seed_from_u64(1);
+    let mut renderer = TerminalRenderer::headless(width, height);
+
+    let mut rain = RaindropSystem::new(width, height, RainIntensity::Storm, &mut rng);
+    rain.set_wind(45.0, 225.0);
+    group.bench_function("rain_storm", |b| {
+        b.iter(|| {
+            rain.update(width, height, &mut rng);
+            rain.render(&mut renderer).unwrap();
+            black_box(rain.particle_count())
+        })
+    });
+
+    let mut snow = SnowSystem::new(width, height, SnowIntensity::Heavy, &mut rng);
+    group.bench_function("snow_heavy", |b| {
+        b.iter(|| {
+            snow.update(width, height, &mut rng);
+            snow.render(&mut renderer).unwrap();
+            black_box(snow.particle_count())
+        })
+    });
+
+    let mut fog = FogSystem::new(width, height, FogIntensity::Heavy);
+    group.bench_function("fog_heavy", |b| {
+        b.iter(|| {
+            fog.update(width, height, &mut rng);
+            fog.render(&mut renderer).unwrap();
+            black_box(fog.particle_count())
+        })
+    });
+
+    let mut stars = StarSystem::new(width, height, &mut rng);
+    group.bench_function("stars", |b| {
+        b.iter(|| {
+            stars.update(width, height, &mut rng);
+            stars.render(&mut renderer).unwrap();
+            black_box(stars.particle_count())
+        })
+    });
+
+    group.finish();
+}
+
+criterion_group!(benches, bench_flush, bench_particles);
+criterion_main!(benches);
@@ -1,5 +1,4 @@
 use crate::render::TerminalRenderer;
-use crossterm::style::Color;
 use rand::prelude::*;
 use std::io;
 
@@ -51,6 +50,11 @@ impl AirplaneSystem {
         self.planes.push(Airplane { x: 0.0, y, speed });
     }
 
+    /// Planes on screen.
+    pub fn particle_count(&self) -> usize {
+        self.planes.len()
+    }
+
     pub fn render(&self, renderer: &mut TerminalRenderer) -> io::Result<()> {
         let airplane_art = [
             "           _",
@@ -62,6 +66,13 @@ impl AirplaneSystem {
             "          '-'",
         ];
 
+        let palette = renderer.palette();
+        let (body, window, tail) = (
+            palette.airplane,
+            palette.airplane_window,
+            palette.airplane_tail,
+        );
+        let (belly, trim) = (palette.stone, palette.trim);
         for plane in &self.planes {
             let x = plane.x as u16;
             let y = plane.y as u16;
@@ -80,15 +91,15 @@ impl AirplaneSystem {
 
                     if ch != ' ' {
                         let color = match ch {
-                            '"' => Color::Cyan,
+                            '"' => window,
 
-                            '\\' => Color::Blue,
+                            '\\' => tail,
 
-                            '_' => Color::DarkGrey,
+                            '_' => trim,
 
-                            '~' => Color::Grey,
+                            '~' => belly,
 
-                            _ => Color::White,
+                            _ => body,
                         };
                         renderer.render_char(render_x, render_y, ch, color)?;
                     }
@@ -1,5 +1,4 @@
 use crate::render::TerminalRenderer;
-use crossterm::style::Color;
 use rand::prelude::*;
 use std::io;
 
@@ -56,12 +55,18 @@ impl BirdSystem {
         }
     }
 
+    /// Birds on screen.
+    pub fn particle_count(&self) -> usize {
+        self.birds.len()
+    }
+
     pub fn render(&self, renderer: &mut TerminalRenderer) -> io::Result<()> {
+        let color = renderer.palette().bird;
         for bird in &self.birds {
             let x = bird.x as u16;
             let y = bird.y as u16;
             if x < self.terminal_width && y < self.terminal_height {
-                renderer.render_char(x, y, bird.character, Color::Yellow)?;
+                renderer.render_char(x, y, bird.character, color)?;
             }
         }
         Ok(())
@@ -1,9 +1,11 @@
 use crate::render::TerminalRenderer;
+use crate::theme::Palette;
 use crossterm::style::Color;
 use rand::prelude::*;
 use std::io;
 
 const MAX_PARTICLES: usize = 200;
+const DEFAULT_SPAWN_RATE: u32 = 8;
 
 struct SmokeParticle {
     x: f32,
@@ -37,14 +39,14 @@ impl SmokeParticle {
         self.age < self.max_age
     }
 
-    fn get_color(&self) -> Color {
+    fn get_color(&self, palette: &Palette) -> Color {
         let life_ratio = self.age as f32 / self.max_age as f32;
         if life_ratio < 0.3 {
-            Color::White
+            palette.smoke[0]
         } else if life_ratio < 0.6 {
-            Color::Grey
+            palette.smoke[1]
         } else {
-            Color::DarkGrey
+            palette.smoke[2]
         }
     }
 }
@@ -52,7 +54,7 @@ impl SmokeParticle {
 pub struct ChimneySmoke {
     particles: Vec<SmokeParticle>,
     spawn_counter: u32,
-    spawn_rate: u32,
+    spawn_rate: Option<u32>, // None = fire is out
 }
 
 impl ChimneySmoke {
@@ -60,7 +62,29 @@ impl ChimneySmoke {
         Self {
             particles: Vec::with_capacity(MAX_PARTICLES),
             spawn_counter: 0,
-            spawn_rate: 8,
+            spawn_rate: Some(DEFAULT_SPAWN_RATE),
+        }
+    }
+
+    /// Adjust how hard the fire is burning based on outside temperature (°C).
+    /// Colder weather means more smoke; on hot days the chimney goes quiet.
+    pub fn set_temperature(&mut self, celsius: f64) {
+        self.spawn_rate = Self::spawn_rate_for(celsius);
+    }
+
+    fn spawn_rate_for(celsius: f64) -> Option<u32> {
+        if celsius < -10.0 {
+            Some(2)
+        } else if celsius < 0.0 {
+            Some(4)
+        } else if celsius < 10.0 {
+            Some(6)
+        } else if celsius < 18.0 {
+            Some(DEFAULT_SPAWN_RATE)
+        } else if celsius < 25.0 {
+            Some(16)
+        } else {
+            None
         }
     }
 
@@ -71,14 +95,24 @@ impl ChimneySmoke {
 
         self.particles.retain(|p| p.is_alive() && p.y >= 0.0);
 
+        let Some(spawn_rate) = self.spawn_rate else {
+            self.spawn_counter = 0;
+            return;
+        };
+
         self.spawn_counter += 1;
-        if self.spawn_counter >= self.spawn_rate && self.particles.len() < MAX_PARTICLES {
+        if self.spawn_counter >= spawn_rate && self.particles.len() < MAX_PARTICLES {
             self.spawn_counter = 0;
             self.particles
                 .push(SmokeParticle::new(chimney_x, chimney_y, rng));
         }
     }
 
+    /// Smoke puffs in the air.
+    pub fn particle_count(&self) -> usize {
+        self.particles.len()
+    }
+
     pub fn render(&self, renderer: &mut TerminalRenderer) -> io::Result<()> {
         for particle in &self.particles {
             let x = particle.x as i16;
@@ -92,7 +126,8 @@ impl ChimneySmoke {
                     _ => '·',
                 };
 
-                renderer.render_char(x as u16, y as u16, display_char, particle.get_color())?;
+                let color = particle.get_color(renderer.palette());
+                renderer.render_char(x as u16, y as u16, display_char, color)?;
             }
         }
         Ok(())
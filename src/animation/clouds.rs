@@ -1,5 +1,4 @@
 use crate::render::TerminalRenderer;
-use crossterm::style::Color;
 use rand::prelude::*;
 use std::io;
 use std::sync::OnceLock;
@@ -11,32 +10,24 @@ struct Cloud {
     y: f32,
     speed: f32,
     shape: Vec<String>,
-    color: Color,
 }
 
 pub struct CloudSystem {
     clouds: Vec<Cloud>,
     terminal_width: u16,
     terminal_height: u16,
+    /// Fair-weather clouds are drawn light, the rest dark.
+    clear: bool,
 }
 
 impl CloudSystem {
     pub fn set_cloud_color(&mut self, is_clear: bool) {
-        let color = if is_clear {
-            Color::White
-        } else {
-            Color::DarkGrey
-        };
-
-        for cloud in &mut self.clouds {
-            cloud.color = color;
-        }
+        self.clear = is_clear;
     }
 }
 
 impl CloudSystem {
-    pub fn new(terminal_width: u16, terminal_height: u16) -> Self {
-        let mut rng = rand::rng();
+    pub fn new(terminal_width: u16, terminal_height: u16, rng: &mut impl Rng) -> Self {
         // Add a few initial clouds
         let count = std::cmp::max(1, terminal_width / 20);
 
@@ -48,8 +39,7 @@ impl CloudSystem {
                 terminal_width,
                 terminal_height,
                 true,
-                Color::White,
-                &mut rng,
+                rng,
             ));
         }
 
@@ -57,16 +47,11 @@ impl CloudSystem {
             clouds,
             terminal_width,
             terminal_height,
+            clear: true,
         }
     }
 
-    fn create_random_cloud(
-        width: u16,
-        height: u16,
-        random_x: bool,
-        color: Color,
-        rng: &mut impl Rng,
-    ) -> Cloud {
+    fn create_random_cloud(width: u16, height: u16, random_x: bool, rng: &mut impl Rng) -> Cloud {
         let shapes = CLOUD_SHAPES.get_or_init(Self::create_cloud_shapes);
 
         let shape_idx = (rng.random::<u32>() as usize) % shapes.len();
@@ -83,13 +68,7 @@ impl CloudSystem {
 
         let speed = 0.05 + (rng.random::<f32>() * 0.1);
 
-        Cloud {
-            x,
-            y,
-            speed,
-            shape,
-            color,
-        }
+        Cloud { x, y, speed, shape }
     }
 
     fn create_cloud_shapes() -> Vec<Vec<String>> {
@@ -126,7 +105,6 @@ impl CloudSystem {
         terminal_width: u16,
         terminal_height: u16,
         is_clear: bool,
-        cloud_color: Color,
         rng: &mut impl Rng,
     ) {
         self.terminal_width = terminal_width;
@@ -151,13 +129,23 @@ impl CloudSystem {
                 terminal_width,
                 terminal_height,
                 false,
-                cloud_color,
                 rng,
             ));
         }
     }
 
+    /// Clouds in the sky.
+    pub fn particle_count(&self) -> usize {
+        self.clouds.len()
+    }
+
     pub fn render(&self, renderer: &mut TerminalRenderer) -> io::Result<()> {
+        let palette = renderer.palette();
+        let color = if self.clear {
+            palette.cloud
+        } else {
+            palette.cloud_dark
+        };
         for cloud in &self.clouds {
             for (i, line) in cloud.shape.iter().enumerate() {
                 let y = cloud.y as i16 + i as i16;
@@ -168,7 +156,7 @@ impl CloudSystem {
                         std::cmp::max(0, x) as u16,
                         y as u16,
                         line,
-                        cloud.color,
+                        color,
                     )?;
                 }
             }
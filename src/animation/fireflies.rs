@@ -1,4 +1,5 @@
 use crate::render::TerminalRenderer;
+use crate::theme::Palette;
 use crossterm::style::Color;
 use rand::prelude::*;
 use std::io;
@@ -84,23 +85,15 @@ impl Firefly {
         }
     }
 
-    fn get_color(&self) -> Color {
+    fn get_color(&self, palette: &Palette) -> Color {
         if self.brightness > 200 {
-            Color::Yellow
+            palette.fireflies[0]
         } else if self.brightness > 128 {
-            Color::Rgb {
-                r: 200,
-                g: 255,
-                b: 100,
-            }
+            palette.fireflies[1]
         } else if self.brightness > 64 {
-            Color::Rgb {
-                r: 150,
-                g: 200,
-                b: 80,
-            }
+            palette.fireflies[2]
         } else {
-            Color::DarkGrey
+            palette.fireflies[3]
         }
     }
 
@@ -147,6 +140,11 @@ impl FireflySystem {
         }
     }
 
+    /// Fireflies alive.
+    pub fn particle_count(&self) -> usize {
+        self.fireflies.len()
+    }
+
     pub fn render(&self, renderer: &mut TerminalRenderer) -> io::Result<()> {
         for firefly in &self.fireflies {
             if firefly.is_visible() {
@@ -158,12 +156,8 @@ impl FireflySystem {
                     && x < self.terminal_width as i16
                     && y < self.terminal_height as i16
                 {
-                    renderer.render_char(
-                        x as u16,
-                        y as u16,
-                        firefly.get_character(),
-                        firefly.get_color(),
-                    )?;
+                    let color = firefly.get_color(renderer.palette());
+                    renderer.render_char(x as u16, y as u16, firefly.get_character(), color)?;
                 }
             }
         }
@@ -1,6 +1,5 @@
 use crate::render::TerminalRenderer;
 use crate::weather::types::FogIntensity;
-use crossterm::style::Color;
 use rand::prelude::*;
 use std::collections::VecDeque;
 use std::io;
@@ -10,7 +9,8 @@ struct FogWisp {
     y: f32,
     speed_x: f32,
     character: char,
-    color: Color,
+    /// Index into the palette's fog shades.
+    shade: usize,
     lifetime: u32,
     max_lifetime: u32,
 }
@@ -26,23 +26,14 @@ impl FogWisp {
         let chars = ['.', ',', '-', '~'];
         let char_idx = (rng.random::<u32>() as usize) % chars.len();
 
-        let colors = [
-            Color::Grey,
-            Color::DarkGrey,
-            Color::Rgb {
-                r: 120,
-                g: 120,
-                b: 120,
-            },
-        ];
-        let color_idx = (rng.random::<u32>() as usize) % colors.len();
+        let shade = (rng.random::<u32>() as usize) % 3;
 
         Self {
             x,
             y,
             speed_x: (rng.random::<f32>() - 0.5) * 0.15,
             character: chars[char_idx],
-            color: colors[color_idx],
+            shade,
             lifetime: 0,
             max_lifetime: 100 + (rng.random::<u32>() % 200),
         }
@@ -118,14 +109,20 @@ impl FogSystem {
         }
     }
 
+    /// Fog wisps drifting.
+    pub fn particle_count(&self) -> usize {
+        self.wisps.len()
+    }
+
     pub fn render(&self, renderer: &mut TerminalRenderer) -> io::Result<()> {
+        let shades = renderer.palette().fog;
         for wisp in &self.wisps {
             let x = wisp.x as i16;
             let y = wisp.y as i16;
 
             if x >= 0 && x < self.terminal_width as i16 && y >= 0 && y < self.terminal_height as i16
             {
-                renderer.render_char(x as u16, y as u16, wisp.character, wisp.color)?;
+                renderer.render_char(x as u16, y as u16, wisp.character, shades[wisp.shade])?;
             }
         }
         Ok(())
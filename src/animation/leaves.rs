@@ -1,5 +1,4 @@
 use crate::render::TerminalRenderer;
-use crossterm::style::Color;
 use rand::prelude::*;
 use std::io;
 
@@ -11,7 +10,8 @@ struct Leaf {
     sway_phase: f32,
     sway_amplitude: f32,
     rotation: u8,
-    color: Color,
+    /// Index into the palette's leaf colors.
+    shade: usize,
     character: char,
 }
 
@@ -29,39 +29,9 @@ impl Leaf {
         let sway_phase = rng.random::<f32>() * std::f32::consts::PI * 2.0;
         let sway_amplitude = 0.5 + (rng.random::<f32>() * 1.5);
 
-        let colors = [
-            Color::Rgb {
-                r: 255,
-                g: 165,
-                b: 0,
-            }, // Orange
-            Color::Rgb {
-                r: 218,
-                g: 165,
-                b: 32,
-            }, // Golden
-            Color::Rgb {
-                r: 184,
-                g: 134,
-                b: 11,
-            }, // Dark golden
-            Color::Rgb {
-                r: 205,
-                g: 92,
-                b: 92,
-            }, // Indian red
-            Color::Rgb {
-                r: 160,
-                g: 82,
-                b: 45,
-            }, // Sienna brown
-            Color::Rgb {
-                r: 139,
-                g: 69,
-                b: 19,
-            }, // Saddle brown
-        ];
-        let color = colors[(rng.random::<u32>() % colors.len() as u32) as usize];
+        // Orange, golden, dark golden, indian red, sienna and saddle brown
+        // in the default palette
+        let shade = (rng.random::<u32>() % 6) as usize;
 
         let chars = ['*', '+', ',', '.', '~'];
         let character = chars[(rng.random::<u32>() % chars.len() as u32) as usize];
@@ -74,7 +44,7 @@ impl Leaf {
             sway_phase,
             sway_amplitude,
             rotation: 0,
-            color,
+            shade,
             character,
         }
     }
@@ -128,15 +98,14 @@ pub struct FallingLeaves {
 }
 
 impl FallingLeaves {
-    pub fn new(terminal_width: u16, terminal_height: u16) -> Self {
-        let mut rng = rand::rng();
+    pub fn new(terminal_width: u16, terminal_height: u16, rng: &mut impl Rng) -> Self {
         let initial_count = std::cmp::max(5, terminal_width / 10);
 
         let max_capacity = std::cmp::max(10, terminal_width / 8) as usize;
         let mut leaves = Vec::with_capacity(max_capacity);
 
         for _ in 0..initial_count {
-            leaves.push(Leaf::new(terminal_width, false, &mut rng));
+            leaves.push(Leaf::new(terminal_width, false, rng));
         }
 
         Self {
@@ -172,14 +141,25 @@ impl FallingLeaves {
         }
     }
 
+    /// Leaves falling.
+    pub fn particle_count(&self) -> usize {
+        self.leaves.len()
+    }
+
     pub fn render(&self, renderer: &mut TerminalRenderer) -> io::Result<()> {
+        let colors = renderer.palette().leaves;
         for leaf in &self.leaves {
             let x = leaf.x as i16;
             let y = leaf.y as i16;
 
             if x >= 0 && y >= 0 && x < self.terminal_width as i16 && y < self.terminal_height as i16
             {
-                renderer.render_char(x as u16, y as u16, leaf.get_character(), leaf.color)?;
+                renderer.render_char(
+                    x as u16,
+                    y as u16,
+                    leaf.get_character(),
+                    colors[leaf.shade],
+                )?;
             }
         }
         Ok(())
@@ -13,6 +13,7 @@ pub mod sunny;
 pub mod thunderstorm;
 
 use crate::render::TerminalRenderer;
+use crate::theme::Palette;
 use crossterm::style::Color;
 use std::io;
 
@@ -20,7 +21,7 @@ pub trait Animation {
     fn get_frame(&self, frame_number: usize) -> &[String];
     fn frame_count(&self) -> usize;
 
-    fn get_color(&self) -> Color {
+    fn get_color(&self, _palette: &Palette) -> Color {
         Color::Reset
     }
 }
@@ -46,7 +47,7 @@ impl AnimationController {
         y_offset: u16,
     ) -> io::Result<()> {
         let frame = animation.get_frame(self.current_frame);
-        let color = animation.get_color();
+        let color = animation.get_color(renderer.palette());
         renderer.render_centered_colored(frame, y_offset, color)
     }
 
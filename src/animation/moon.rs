@@ -1,5 +1,4 @@
 use crate::render::TerminalRenderer;
-use crossterm::style::Color;
 use std::io;
 
 pub struct MoonSystem {
@@ -106,6 +105,7 @@ impl MoonSystem {
             _ => vec![],
         };
 
+        let color = renderer.palette().moon;
         for (i, line) in art.iter().enumerate() {
             let y = self.y + i as u16;
             for (j, ch) in line.chars().enumerate() {
@@ -117,10 +117,10 @@ impl MoonSystem {
 
                 if ch == '~' {
                     // Opaque Moon Body (hides stars) - Render as space but overwrite what's there
-                    renderer.render_char(x, y, ' ', Color::White)?;
+                    renderer.render_char(x, y, ' ', color)?;
                 } else {
                     // Texture/Outline
-                    renderer.render_char(x, y, ch, Color::White)?;
+                    renderer.render_char(x, y, ch, color)?;
                 }
             }
         }
@@ -1,6 +1,5 @@
 use crate::render::TerminalRenderer;
 use crate::weather::types::RainIntensity;
-use crossterm::style::Color;
 use rand::prelude::*;
 use std::collections::VecDeque;
 use std::io;
@@ -13,7 +12,8 @@ struct Raindrop {
     speed_y: f32,
     speed_x: f32,
     character: char,
-    color: Color,
+    /// Near drops of drizzle and heavy rain take the palette's rain tint.
+    tinted: bool,
     z_index: u8,
 }
 
@@ -36,7 +36,12 @@ pub struct RaindropSystem {
 }
 
 impl RaindropSystem {
-    pub fn new(terminal_width: u16, terminal_height: u16, intensity: RainIntensity) -> Self {
+    pub fn new(
+        terminal_width: u16,
+        terminal_height: u16,
+        intensity: RainIntensity,
+        rng: &mut impl Rng,
+    ) -> Self {
         let drops_capacity = match intensity {
             RainIntensity::Drizzle => (terminal_width / 4) as usize,
             RainIntensity::Light => (terminal_width / 2) as usize,
@@ -53,7 +58,7 @@ impl RaindropSystem {
             intensity,
             wind_x: 0.0,
         };
-        let wind_dir = if rand::random::<bool>() { 1.0 } else { -1.0 };
+        let wind_dir = if rng.random::<bool>() { 1.0 } else { -1.0 };
         system.set_intensity_with_dir(intensity, wind_dir);
         system
     }
@@ -86,33 +91,17 @@ impl RaindropSystem {
             - (self.terminal_width as f32 * 0.5);
         let z_index = if rng.random::<bool>() { 1 } else { 0 };
 
-        let (speed_y, chars, color) = match self.intensity {
-            RainIntensity::Drizzle => (
-                if z_index == 1 { 0.4 } else { 0.2 },
-                vec!['.', ','],
-                if z_index == 1 {
-                    Color::Cyan
-                } else {
-                    Color::DarkGrey
-                },
-            ),
+        let (speed_y, chars, tinted) = match self.intensity {
+            RainIntensity::Drizzle => (if z_index == 1 { 0.4 } else { 0.2 }, vec!['.', ','], true),
             RainIntensity::Light => (
                 if z_index == 1 { 0.7 } else { 0.4 },
                 vec!['|', ':', '.'],
-                if z_index == 1 {
-                    Color::White
-                } else {
-                    Color::DarkGrey
-                },
+                false,
             ),
             RainIntensity::Heavy => (
                 if z_index == 1 { 0.9 } else { 0.6 }, // Slightly faster than Light
                 vec!['|', ':'],                       // Vertical density
-                if z_index == 1 {
-                    Color::Cyan
-                } else {
-                    Color::DarkGrey // Blue-ish background
-                },
+                true,
             ),
             RainIntensity::Storm => (
                 if z_index == 1 { 1.8 } else { 1.2 },
@@ -122,11 +111,7 @@ impl RaindropSystem {
                 } else {
                     vec!['/']
                 },
-                if z_index == 1 {
-                    Color::White
-                } else {
-                    Color::DarkGrey
-                },
+                false,
             ),
         };
 
@@ -138,7 +123,7 @@ impl RaindropSystem {
             speed_y: speed_y + (rng.random::<f32>() * 0.2),
             speed_x: self.wind_x + (rng.random::<f32>() * 0.1 - 0.05),
             character: chars[char_idx],
-            color,
+            tinted,
             z_index,
         });
     }
@@ -210,6 +195,11 @@ impl RaindropSystem {
         });
     }
 
+    /// Drops falling plus splashes on the ground.
+    pub fn particle_count(&self) -> usize {
+        self.drops.len() + self.splashes.len()
+    }
+
     pub fn render(&self, renderer: &mut TerminalRenderer) -> io::Result<()> {
         // Render drops
         for drop in &self.drops {
@@ -231,7 +221,13 @@ impl RaindropSystem {
                 } else {
                     drop.character
                 };
-                renderer.render_char(x as u16, y as u16, ch, drop.color)?;
+                let palette = renderer.palette();
+                let color = match (drop.z_index, drop.tinted) {
+                    (1, true) => palette.rain_tint,
+                    (1, false) => palette.rain_near,
+                    _ => palette.rain_far,
+                };
+                renderer.render_char(x as u16, y as u16, ch, color)?;
             }
         }
 
@@ -244,7 +240,8 @@ impl RaindropSystem {
                     2 => 'O',
                     _ => ' ',
                 };
-                renderer.render_char(splash.x, splash.y, ch, Color::White)?;
+                let color = renderer.palette().rain_near;
+                renderer.render_char(splash.x, splash.y, ch, color)?;
             }
         }
 
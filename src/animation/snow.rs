@@ -1,6 +1,5 @@
 use crate::render::TerminalRenderer;
 use crate::weather::types::SnowIntensity;
-use crossterm::style::Color;
 use rand::prelude::*;
 use std::io;
 
@@ -11,7 +10,7 @@ struct Snowflake {
     speed_x: f32,
     sway_offset: f32,
     character: char,
-    color: Color,
+    near: bool,
 }
 
 pub struct SnowSystem {
@@ -23,7 +22,12 @@ pub struct SnowSystem {
 }
 
 impl SnowSystem {
-    pub fn new(terminal_width: u16, terminal_height: u16, intensity: SnowIntensity) -> Self {
+    pub fn new(
+        terminal_width: u16,
+        terminal_height: u16,
+        intensity: SnowIntensity,
+        rng: &mut impl Rng,
+    ) -> Self {
         let flakes_capacity = match intensity {
             SnowIntensity::Light => (terminal_width / 4) as usize,
             SnowIntensity::Medium => (terminal_width / 2) as usize,
@@ -38,7 +42,7 @@ impl SnowSystem {
             wind_x: 0.0,
         };
         // Initialize with some default wind
-        let wind_dir = if rand::random::<bool>() { 0.2 } else { -0.2 };
+        let wind_dir = if rng.random::<bool>() { 0.2 } else { -0.2 };
         system.set_intensity_with_dir(intensity, wind_dir);
         system
     }
@@ -88,11 +92,7 @@ impl SnowSystem {
             speed_x: self.wind_x + (rng.random::<f32>() * 0.1 - 0.05),
             sway_offset: rng.random::<f32>() * 100.0, // Random phase for sway
             character: chars[char_idx],
-            color: if z_index == 1 {
-                Color::White
-            } else {
-                Color::DarkGrey
-            },
+            near: z_index == 1,
         });
     }
 
@@ -138,14 +138,21 @@ impl SnowSystem {
         });
     }
 
+    /// Flakes falling.
+    pub fn particle_count(&self) -> usize {
+        self.flakes.len()
+    }
+
     pub fn render(&self, renderer: &mut TerminalRenderer) -> io::Result<()> {
+        let (near, far) = (renderer.palette().snow_near, renderer.palette().snow_far);
         for flake in &self.flakes {
             let x = flake.x as i16;
             let y = flake.y as i16;
 
             if x >= 0 && x < self.terminal_width as i16 && y >= 0 && y < self.terminal_height as i16
             {
-                renderer.render_char(x as u16, y as u16, flake.character, flake.color)?;
+                let color = if flake.near { near } else { far };
+                renderer.render_char(x as u16, y as u16, flake.character, color)?;
             }
         }
         Ok(())
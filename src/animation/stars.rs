@@ -1,5 +1,4 @@
 use crate::render::TerminalRenderer;
-use crossterm::style::Color;
 use rand::prelude::*;
 use std::io;
 
@@ -30,8 +29,8 @@ pub struct StarSystem {
 impl StarSystem {
     const MIN_DISTANCE: f32 = 3.0; // Minimum distance between stars
 
-    pub fn new(terminal_width: u16, terminal_height: u16) -> Self {
-        let stars = Self::create_stars(terminal_width, terminal_height, &[]);
+    pub fn new(terminal_width: u16, terminal_height: u16, rng: &mut impl Rng) -> Self {
+        let stars = Self::create_stars(terminal_width, terminal_height, &[], rng);
 
         Self {
             stars,
@@ -41,8 +40,12 @@ impl StarSystem {
         }
     }
 
-    fn create_stars(terminal_width: u16, terminal_height: u16, inital_stars: &[Star]) -> Vec<Star> {
-        let mut rng = rand::rng();
+    fn create_stars(
+        terminal_width: u16,
+        terminal_height: u16,
+        inital_stars: &[Star],
+        rng: &mut impl Rng,
+    ) -> Vec<Star> {
         let count = (terminal_width as usize * terminal_height as usize) / 80; // Density
 
         if count < inital_stars.len() {
@@ -89,7 +92,7 @@ impl StarSystem {
     pub fn update(&mut self, terminal_width: u16, terminal_height: u16, rng: &mut impl Rng) {
         if terminal_width != self.terminal_width || terminal_height != self.terminal_height {
             // Fix stars not resizing
-            self.stars = Self::create_stars(terminal_width, terminal_height, &self.stars);
+            self.stars = Self::create_stars(terminal_width, terminal_height, &self.stars, rng);
 
             self.terminal_width = terminal_width;
             self.terminal_height = terminal_height;
@@ -126,7 +129,13 @@ impl StarSystem {
         }
     }
 
+    /// Stars in the sky.
+    pub fn particle_count(&self) -> usize {
+        self.stars.len()
+    }
+
     pub fn render(&self, renderer: &mut TerminalRenderer) -> io::Result<()> {
+        let (bright, dim) = (renderer.palette().star, renderer.palette().star_dim);
         for star in &self.stars {
             let ch = if star.brightness > 0.8 {
                 '*'
@@ -135,11 +144,7 @@ impl StarSystem {
             } else {
                 '.'
             };
-            let color = if star.brightness > 0.6 {
-                Color::White
-            } else {
-                Color::DarkGrey
-            };
+            let color = if star.brightness > 0.6 { bright } else { dim };
 
             renderer.render_char(star.x, star.y, ch, color)?;
         }
@@ -155,7 +160,7 @@ impl StarSystem {
                 && head_y >= 0
                 && head_y < self.terminal_height as i16
             {
-                renderer.render_char(head_x as u16, head_y as u16, '*', Color::White)?;
+                renderer.render_char(head_x as u16, head_y as u16, '*', bright)?;
             }
 
             for i in 1..star.length {
@@ -168,7 +173,7 @@ impl StarSystem {
                     && trail_y < self.terminal_height as i16
                 {
                     let ch = if i == 1 { '+' } else { '.' };
-                    renderer.render_char(trail_x as u16, trail_y as u16, ch, Color::White)?;
+                    renderer.render_char(trail_x as u16, trail_y as u16, ch, bright)?;
                 }
             }
         }
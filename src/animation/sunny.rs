@@ -1,4 +1,5 @@
 use super::Animation;
+use crate::theme::Palette;
 use crossterm::style::Color;
 
 pub struct SunnyAnimation {
@@ -50,8 +51,8 @@ impl Animation for SunnyAnimation {
         self.frames.len()
     }
 
-    fn get_color(&self) -> Color {
-        Color::Yellow
+    fn get_color(&self, palette: &Palette) -> Color {
+        palette.sun
     }
 }
 
@@ -1,5 +1,4 @@
 use crate::render::TerminalRenderer;
-use crossterm::style::Color;
 use rand::prelude::*;
 use std::collections::VecDeque;
 use std::io;
@@ -28,11 +27,13 @@ pub struct ThunderstormSystem {
     terminal_width: u16,
     terminal_height: u16,
     flash_active: bool,
+    /// Simulation steps until the next strike; `AnimationManager` runs a
+    /// fixed number of steps per second whatever the frame rate.
     next_strike_in: u16,
 }
 
 impl ThunderstormSystem {
-    pub fn new(terminal_width: u16, terminal_height: u16) -> Self {
+    pub fn new(terminal_width: u16, terminal_height: u16, rng: &mut impl Rng) -> Self {
         Self {
             bolts: VecDeque::with_capacity(MAX_BOLTS),
             state: LightningState::Idle,
@@ -40,7 +41,7 @@ impl ThunderstormSystem {
             terminal_width,
             terminal_height,
             flash_active: false,
-            next_strike_in: 60 + (rand::random::<u16>() % 120), // Random start delay
+            next_strike_in: 60 + (rng.random::<u16>() % 120), // Random start delay
         }
     }
 
@@ -155,11 +156,17 @@ impl ThunderstormSystem {
         self.flash_active
     }
 
+    /// Lightning bolts still visible.
+    pub fn particle_count(&self) -> usize {
+        self.bolts.len()
+    }
+
     pub fn render(&self, renderer: &mut TerminalRenderer) -> io::Result<()> {
+        let palette = renderer.palette();
         let color = if self.flash_active {
-            Color::White
+            palette.flash
         } else {
-            Color::Yellow
+            palette.lightning
         };
 
         for bolt in &self.bolts {
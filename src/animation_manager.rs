@@ -6,14 +6,22 @@ use crate::animation::{
 };
 use crate::app_state::AppState;
 use crate::render::TerminalRenderer;
-use crate::scene::WorldScene;
-use crate::scene::house::House;
+use crate::scene::{LayoutTier, SceneAnchors};
 use crate::weather::{FogIntensity, RainIntensity, SnowIntensity, WeatherConditions};
-use crossterm::style::Color;
 use std::io;
-use std::time::{Duration, Instant};
+use std::time::Duration;
 
+/// Simulation steps per second. Every system moves its particles a fixed
+/// amount per step, so this is the speed everything was tuned at.
+pub const SIMULATION_RATE: u32 = 30;
+/// Length of one simulation step.
+pub const SIMULATION_STEP: Duration = Duration::from_nanos(1_000_000_000 / SIMULATION_RATE as u64);
+/// Longer gaps between frames, e.g. after the process was suspended, are
+/// not caught up on.
+const MAX_FRAME_TIME: Duration = Duration::from_secs(2);
 const FRAME_DELAY: Duration = Duration::from_millis(500);
+const SPEED_LEVELS: [f32; 7] = [0.25, 0.5, 0.75, 1.0, 1.5, 2.0, 4.0];
+const NORMAL_SPEED_LEVEL: usize = 3;
 
 pub struct AnimationManager {
     raindrop_system: RaindropSystem,
@@ -25,37 +33,121 @@ pub struct AnimationManager {
     airplane_system: AirplaneSystem,
     star_system: StarSystem,
     moon_system: MoonSystem,
-    chimney_smoke: ChimneySmoke,
+    chimney_smoke: Vec<ChimneySmoke>,
+    outside_temperature: Option<f64>,
     firefly_system: FireflySystem,
     falling_leaves: FallingLeaves,
     sunny_animation: SunnyAnimation,
     animation_controller: AnimationController,
-    last_frame_time: Instant,
+    /// Time the sun has shown its current frame, scaled by the speed.
+    sunny_elapsed: Duration,
     show_leaves: bool,
+    paused: bool,
+    speed_level: usize,
+    /// Simulated time not yet turned into whole steps, in nanoseconds.
+    pending_nanos: f64,
+    steps: u32,
+    /// Particles in each system drawn this frame, for the debug overlay.
+    particle_counts: Vec<(&'static str, usize)>,
 }
 
 impl AnimationManager {
-    pub fn new(term_width: u16, term_height: u16, show_leaves: bool) -> Self {
+    /// Initial clouds, stars and so on are placed using `rng`.
+    pub fn new(
+        term_width: u16,
+        term_height: u16,
+        show_leaves: bool,
+        rng: &mut impl rand::Rng,
+    ) -> Self {
         Self {
-            raindrop_system: RaindropSystem::new(term_width, term_height, RainIntensity::Light),
-            snow_system: SnowSystem::new(term_width, term_height, SnowIntensity::Light),
+            raindrop_system: RaindropSystem::new(
+                term_width,
+                term_height,
+                RainIntensity::Light,
+                rng,
+            ),
+            snow_system: SnowSystem::new(term_width, term_height, SnowIntensity::Light, rng),
             fog_system: FogSystem::new(term_width, term_height, FogIntensity::Light),
-            thunderstorm_system: ThunderstormSystem::new(term_width, term_height),
-            cloud_system: CloudSystem::new(term_width, term_height),
+            thunderstorm_system: ThunderstormSystem::new(term_width, term_height, rng),
+            cloud_system: CloudSystem::new(term_width, term_height, rng),
             bird_system: BirdSystem::new(term_width, term_height),
             airplane_system: AirplaneSystem::new(term_width, term_height),
-            star_system: StarSystem::new(term_width, term_height),
+            star_system: StarSystem::new(term_width, term_height, rng),
             moon_system: MoonSystem::new(term_width, term_height),
-            chimney_smoke: ChimneySmoke::new(),
+            chimney_smoke: Vec::new(),
+            outside_temperature: None,
             firefly_system: FireflySystem::new(term_width, term_height),
-            falling_leaves: FallingLeaves::new(term_width, term_height),
+            falling_leaves: FallingLeaves::new(term_width, term_height, rng),
             sunny_animation: SunnyAnimation::new(),
             animation_controller: AnimationController::new(),
-            last_frame_time: Instant::now(),
+            sunny_elapsed: Duration::ZERO,
             show_leaves,
+            paused: false,
+            speed_level: NORMAL_SPEED_LEVEL,
+            // The first frame shows one step so there is something to see
+            pending_nanos: SIMULATION_STEP.as_nanos() as f64,
+            steps: 0,
+            particle_counts: Vec::new(),
+        }
+    }
+
+    pub fn set_paused(&mut self, paused: bool) {
+        self.paused = paused;
+    }
+
+    pub fn is_paused(&self) -> bool {
+        self.paused
+    }
+
+    pub fn speed(&self) -> f32 {
+        SPEED_LEVELS[self.speed_level]
+    }
+
+    pub fn speed_up(&mut self) {
+        self.speed_level = (self.speed_level + 1).min(SPEED_LEVELS.len() - 1);
+    }
+
+    pub fn slow_down(&mut self) {
+        self.speed_level = self.speed_level.saturating_sub(1);
+    }
+
+    /// Let `dt` of real time pass, scaled by the speed. Nothing moves until
+    /// the next `begin_frame`, which turns the time into whole steps.
+    pub fn advance(&mut self, dt: Duration, conditions: &WeatherConditions) {
+        if self.paused {
+            return;
+        }
+        let dt = dt.min(MAX_FRAME_TIME);
+        self.pending_nanos += dt.as_nanos() as f64 * self.speed() as f64;
+
+        if !conditions.is_raining && !conditions.is_thunderstorm && !conditions.is_snowing {
+            self.sunny_elapsed += dt.mul_f32(self.speed());
+            if self.sunny_elapsed >= FRAME_DELAY {
+                self.animation_controller.next_frame(&self.sunny_animation);
+                self.sunny_elapsed = Duration::ZERO;
+            }
         }
     }
 
+    /// Work out how many simulation steps this frame gets from the time
+    /// passed to `advance`. Leftover time carries over, so at 60 FPS every
+    /// other frame steps and at 2 FPS each frame takes 15 steps.
+    pub fn begin_frame(&mut self) -> u32 {
+        self.particle_counts.clear();
+        if self.paused {
+            self.steps = 0;
+            return 0;
+        }
+        let step = SIMULATION_STEP.as_nanos() as f64;
+        self.steps = (self.pending_nanos / step).floor() as u32;
+        self.pending_nanos -= self.steps as f64 * step;
+        self.steps
+    }
+
+    pub fn particle_counts(&self) -> &[(&'static str, usize)] {
+        &self.particle_counts
+    }
+
     pub fn update_rain_intensity(&mut self, intensity: RainIntensity) {
         self.raindrop_system.set_intensity(intensity);
     }
@@ -69,6 +161,13 @@ impl AnimationManager {
         self.snow_system.set_wind(speed_kmh, direction_deg);
     }
 
+    pub fn update_temperature(&mut self, celsius: f64) {
+        self.outside_temperature = Some(celsius);
+        for smoke in &mut self.chimney_smoke {
+            smoke.set_temperature(celsius);
+        }
+    }
+
     pub fn update_fog_intensity(&mut self, intensity: FogIntensity) {
         self.fog_system.set_intensity(intensity);
     }
@@ -76,26 +175,35 @@ impl AnimationManager {
     pub fn render_background(
         &mut self,
         renderer: &mut TerminalRenderer,
-        conditions: &WeatherConditions,
         state: &AppState,
+        anchors: &SceneAnchors,
         term_width: u16,
         term_height: u16,
         mut rng: &mut impl rand::Rng,
     ) -> io::Result<()> {
-        // Calculate horizon_y early so it's available for all systems
-        let ground_height = WorldScene::GROUND_HEIGHT;
-        let horizon_y = term_height.saturating_sub(ground_height);
+        let conditions = &state.weather_conditions;
+        let horizon_y = anchors.horizon_y;
 
         if !conditions.is_day {
-            self.star_system.update(term_width, term_height, &mut rng);
+            for _ in 0..self.steps {
+                self.star_system.update(term_width, term_height, &mut rng);
+            }
             self.star_system.render(renderer)?;
-            self.moon_system.update(term_width, term_height);
+            self.particle_counts
+                .push(("stars", self.star_system.particle_count()));
+            for _ in 0..self.steps {
+                self.moon_system.update(term_width, term_height);
+            }
             self.moon_system.render(renderer)?;
 
             if state.should_show_fireflies() {
-                self.firefly_system
-                    .update(term_width, term_height, horizon_y, &mut rng);
+                for _ in 0..self.steps {
+                    self.firefly_system
+                        .update(term_width, term_height, horizon_y, &mut rng);
+                }
                 self.firefly_system.render(renderer)?;
+                self.particle_counts
+                    .push(("fireflies", self.firefly_system.particle_count()));
             }
         }
 
@@ -104,11 +212,17 @@ impl AnimationManager {
             && !conditions.is_snowing
             && conditions.is_day
         {
-            self.bird_system.update(term_width, term_height, &mut rng);
+            for _ in 0..self.steps {
+                self.bird_system.update(term_width, term_height, &mut rng);
+            }
             self.bird_system.render(renderer)?;
+            self.particle_counts
+                .push(("birds", self.bird_system.particle_count()));
         }
 
+        // The sun is taller than the sky in compact layouts
         if state.should_show_sun()
+            && LayoutTier::for_size(term_width, term_height) != LayoutTier::Compact
             && !conditions.is_raining
             && !conditions.is_thunderstorm
             && !conditions.is_snowing
@@ -121,21 +235,19 @@ impl AnimationManager {
         if conditions.is_cloudy
             || (!conditions.is_raining && !conditions.is_thunderstorm && !conditions.is_snowing)
         {
-            let (is_clear, cloud_color) = if let Some(weather) = &state.current_weather {
-                match weather.condition {
-                    crate::weather::WeatherCondition::Clear => (true, Color::White),
-                    crate::weather::WeatherCondition::PartlyCloudy => (false, Color::Grey),
-                    _ => (false, Color::DarkGrey),
-                }
-            } else {
-                (false, Color::DarkGrey)
-            };
+            let is_clear = state.current_weather.as_ref().is_some_and(|weather| {
+                weather.condition == crate::weather::WeatherCondition::Clear
+            });
 
             if conditions.is_cloudy || is_clear {
                 self.cloud_system.set_cloud_color(is_clear);
-                self.cloud_system
-                    .update(term_width, term_height, is_clear, cloud_color, &mut rng);
+                for _ in 0..self.steps {
+                    self.cloud_system
+                        .update(term_width, term_height, is_clear, &mut rng);
+                }
                 self.cloud_system.render(renderer)?;
+                self.particle_counts
+                    .push(("clouds", self.cloud_system.particle_count()));
             }
         }
 
@@ -144,9 +256,13 @@ impl AnimationManager {
             && !conditions.is_snowing
             && !conditions.is_foggy
         {
-            self.airplane_system
-                .update(term_width, term_height, &mut rng);
+            for _ in 0..self.steps {
+                self.airplane_system
+                    .update(term_width, term_height, &mut rng);
+            }
             self.airplane_system.render(renderer)?;
+            self.particle_counts
+                .push(("airplanes", self.airplane_system.particle_count()));
         }
 
         Ok(())
@@ -156,24 +272,39 @@ impl AnimationManager {
         &mut self,
         renderer: &mut TerminalRenderer,
         conditions: &WeatherConditions,
-        term_width: u16,
-        term_height: u16,
+        anchors: &SceneAnchors,
         mut rng: &mut impl rand::Rng,
     ) -> io::Result<()> {
         if conditions.is_raining || conditions.is_thunderstorm {
             return Ok(());
         }
-        let ground_height = WorldScene::GROUND_HEIGHT;
-        let horizon_y = term_height.saturating_sub(ground_height);
-        let house_width = House::WIDTH;
-        let house_height = House::HEIGHT;
-        let house_x = (term_width / 2).saturating_sub(house_width / 2);
-        let house_y = horizon_y.saturating_sub(house_height);
-        let chimney_x = house_x + House::CHIMNEY_X_OFFSET;
-        let chimney_y = house_y;
 
-        self.chimney_smoke.update(chimney_x, chimney_y, &mut rng);
-        self.chimney_smoke.render(renderer)?;
+        let temperature = self.outside_temperature;
+        self.chimney_smoke
+            .resize_with(anchors.smoke_emitters.len(), || {
+                let mut smoke = ChimneySmoke::new();
+                if let Some(celsius) = temperature {
+                    smoke.set_temperature(celsius);
+                }
+                smoke
+            });
+
+        for (smoke, &(emitter_x, emitter_y)) in
+            self.chimney_smoke.iter_mut().zip(&anchors.smoke_emitters)
+        {
+            for _ in 0..self.steps {
+                smoke.update(emitter_x, emitter_y, &mut rng);
+            }
+            smoke.render(renderer)?;
+        }
+        if !self.chimney_smoke.is_empty() {
+            let puffs = self
+                .chimney_smoke
+                .iter()
+                .map(ChimneySmoke::particle_count)
+                .sum();
+            self.particle_counts.push(("smoke", puffs));
+        }
 
         Ok(())
     }
@@ -187,29 +318,53 @@ impl AnimationManager {
         mut rng: &mut impl rand::Rng,
     ) -> io::Result<()> {
         if conditions.is_thunderstorm {
-            self.raindrop_system
-                .update(term_width, term_height, &mut rng);
+            for _ in 0..self.steps {
+                self.raindrop_system
+                    .update(term_width, term_height, &mut rng);
+            }
             self.raindrop_system.render(renderer)?;
+            self.particle_counts
+                .push(("rain", self.raindrop_system.particle_count()));
 
-            self.thunderstorm_system
-                .update(term_width, term_height, &mut rng);
+            // A flash lasts a single step, so remember it even when the
+            // frame takes several
+            let mut flashed = false;
+            for _ in 0..self.steps {
+                self.thunderstorm_system
+                    .update(term_width, term_height, &mut rng);
+                flashed |= self.thunderstorm_system.is_flashing();
+            }
             self.thunderstorm_system.render(renderer)?;
+            self.particle_counts
+                .push(("lightning", self.thunderstorm_system.particle_count()));
 
-            if self.thunderstorm_system.is_flashing() {
+            if flashed {
                 renderer.flash_screen()?;
             }
         } else if conditions.is_raining {
-            self.raindrop_system
-                .update(term_width, term_height, &mut rng);
+            for _ in 0..self.steps {
+                self.raindrop_system
+                    .update(term_width, term_height, &mut rng);
+            }
             self.raindrop_system.render(renderer)?;
+            self.particle_counts
+                .push(("rain", self.raindrop_system.particle_count()));
         } else if conditions.is_snowing {
-            self.snow_system.update(term_width, term_height, &mut rng);
+            for _ in 0..self.steps {
+                self.snow_system.update(term_width, term_height, &mut rng);
+            }
             self.snow_system.render(renderer)?;
+            self.particle_counts
+                .push(("snow", self.snow_system.particle_count()));
         }
 
         if conditions.is_foggy {
-            self.fog_system.update(term_width, term_height, &mut rng);
+            for _ in 0..self.steps {
+                self.fog_system.update(term_width, term_height, &mut rng);
+            }
             self.fog_system.render(renderer)?;
+            self.particle_counts
+                .push(("fog", self.fog_system.particle_count()));
         }
 
         if self.show_leaves
@@ -217,22 +372,104 @@ impl AnimationManager {
             && !conditions.is_thunderstorm
             && !conditions.is_snowing
         {
-            self.falling_leaves
-                .update(term_width, term_height, &mut rng);
+            for _ in 0..self.steps {
+                self.falling_leaves
+                    .update(term_width, term_height, &mut rng);
+            }
             self.falling_leaves.render(renderer)?;
+            self.particle_counts
+                .push(("leaves", self.falling_leaves.particle_count()));
         }
 
         Ok(())
     }
+}
 
-    pub fn update_sunny_animation(&mut self, conditions: &WeatherConditions) {
-        if !conditions.is_raining
-            && !conditions.is_thunderstorm
-            && !conditions.is_snowing
-            && self.last_frame_time.elapsed() >= FRAME_DELAY
-        {
-            self.animation_controller.next_frame(&self.sunny_animation);
-            self.last_frame_time = Instant::now();
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Steps taken by a frame that follows `dt` of real time.
+    fn frame(manager: &mut AnimationManager, dt: Duration) -> u32 {
+        manager.advance(dt, &WeatherConditions::default());
+        manager.begin_frame()
+    }
+
+    fn new_manager() -> AnimationManager {
+        let mut manager = AnimationManager::new(80, 24, false, &mut rand::rng());
+        // The step every manager starts with
+        assert_eq!(manager.begin_frame(), 1);
+        manager
+    }
+
+    #[test]
+    fn test_normal_speed_steps_once_per_frame() {
+        let mut manager = new_manager();
+        assert_eq!(manager.speed(), 1.0);
+        assert!((0..10).all(|_| frame(&mut manager, SIMULATION_STEP) == 1));
+    }
+
+    #[test]
+    fn test_half_speed_steps_every_other_frame() {
+        let mut manager = new_manager();
+        manager.slow_down();
+        manager.slow_down();
+        assert_eq!(manager.speed(), 0.5);
+        let steps: u32 = (0..10).map(|_| frame(&mut manager, SIMULATION_STEP)).sum();
+        assert_eq!(steps, 5);
+    }
+
+    #[test]
+    fn test_steps_follow_time_not_frames() {
+        // Ten seconds are the same number of steps at any frame rate, give or
+        // take the nanoseconds lost dividing a second into frames
+        for fps in [1, 2, 10, 30, 60, 120] {
+            let mut manager = new_manager();
+            let dt = Duration::from_secs(1) / fps;
+            let steps: u32 = (0..fps * 10).map(|_| frame(&mut manager, dt)).sum();
+            assert!(
+                (SIMULATION_RATE * 10 - 1..=SIMULATION_RATE * 10).contains(&steps),
+                "{} steps at {} fps",
+                steps,
+                fps
+            );
+        }
+
+        // Frames cut short by input only add the time that really passed
+        let mut manager = new_manager();
+        let steps: u32 = (0..100)
+            .map(|_| frame(&mut manager, Duration::from_millis(5)))
+            .sum();
+        assert_eq!(steps, 15);
+    }
+
+    #[test]
+    fn test_long_gaps_are_not_caught_up() {
+        let mut manager = new_manager();
+        let steps = frame(&mut manager, Duration::from_secs(3600));
+        assert_eq!(steps, MAX_FRAME_TIME.as_secs() as u32 * SIMULATION_RATE);
+    }
+
+    #[test]
+    fn test_pause_stops_steps() {
+        let mut manager = new_manager();
+        manager.speed_up();
+        manager.set_paused(true);
+        assert_eq!(frame(&mut manager, SIMULATION_STEP), 0);
+        manager.set_paused(false);
+        assert!(frame(&mut manager, SIMULATION_STEP) >= 1);
+    }
+
+    #[test]
+    fn test_speed_is_clamped() {
+        let mut manager = AnimationManager::new(80, 24, false, &mut rand::rng());
+        for _ in 0..20 {
+            manager.speed_up();
+        }
+        assert_eq!(manager.speed(), *SPEED_LEVELS.last().unwrap());
+        for _ in 0..20 {
+            manager.slow_down();
         }
+        assert_eq!(manager.speed(), SPEED_LEVELS[0]);
     }
 }
@@ -1,21 +1,35 @@
 use crate::animation_manager::AnimationManager;
 use crate::app_state::AppState;
-use crate::config::Config;
-use crate::error::WeatherError;
+use crate::config::{Config, Location, PowerMode, SplitLayout};
+use crate::hud::HudStyle;
+use crate::keymap::{Action, Keymap};
+use crate::overlay;
+use crate::pane::{self, Pane, WeatherFeed};
+use crate::profile::{Profiler, Stage};
 use crate::render::TerminalRenderer;
-use crate::scene::WorldScene;
+use crate::scene::{Scene, SceneClock, WorldScene};
+use crate::theme::Theme;
 use crate::weather::{
     OpenMeteoProvider, WeatherClient, WeatherCondition, WeatherData, WeatherLocation,
 };
-use crossterm::event::{self, Event, KeyCode, KeyModifiers};
+use crossterm::event::{self, Event, MouseButton, MouseEvent, MouseEventKind};
+use rand::SeedableRng;
+use rand::rngs::StdRng;
 use std::io;
 use std::sync::Arc;
-use std::time::Duration;
-use tokio::sync::mpsc;
-
-const REFRESH_INTERVAL: Duration = Duration::from_secs(300);
-const INPUT_POLL_FPS: u64 = 30;
-const FRAME_DURATION: Duration = Duration::from_millis(1000 / INPUT_POLL_FPS);
+use std::time::{Duration, Instant};
+
+pub const REFRESH_INTERVAL: Duration = Duration::from_secs(300);
+pub const DEFAULT_FPS: u32 = 30;
+/// Frame rate of `--battery-saver`: animations keep their speed but move in
+/// bigger jumps.
+pub const BATTERY_SAVER_FPS: u32 = 2;
+pub const MAX_FPS: u32 = 120;
+/// Frame rate while the terminal is out of focus in eco mode: the animations
+/// are frozen and only the HUD needs to stay current.
+const UNFOCUSED_FPS: u32 = 1;
+/// Eco mode drops to `BATTERY_SAVER_FPS` after this long without input.
+const IDLE_AFTER: Duration = Duration::from_secs(60);
 
 fn generate_offline_weather(rng: &mut impl rand::Rng) -> WeatherData {
     use chrono::{Local, Timelike};
@@ -55,227 +69,693 @@ fn generate_offline_weather(rng: &mut impl rand::Rng) -> WeatherData {
     }
 }
 
+pub fn simulated_weather(condition_str: &str, simulate_night: bool) -> WeatherData {
+    let simulated_condition = condition_str
+        .parse::<WeatherCondition>()
+        .unwrap_or_else(|e| {
+            eprintln!("{}", e);
+            WeatherCondition::Clear
+        });
+
+    WeatherData {
+        condition: simulated_condition,
+        temperature: 20.0,
+        apparent_temperature: 19.0,
+        humidity: 65.0,
+        precipitation: if simulated_condition.is_raining() {
+            2.5
+        } else {
+            0.0
+        },
+        wind_speed: if simulated_condition.is_thunderstorm() {
+            45.0
+        } else {
+            10.0
+        },
+        wind_direction: 225.0,
+        cloud_cover: 50.0,
+        pressure: 1013.0,
+        visibility: Some(10000.0),
+        is_day: !simulate_night,
+        moon_phase: Some(0.5),
+        timestamp: "simulated".to_string(),
+    }
+}
+
+/// Whether `event` is the user doing something, as opposed to a resize or
+/// the pointer passing over the window. Only this ends idle in eco mode.
+fn is_user_input(event: &Event) -> bool {
+    match event {
+        Event::Key(_) => true,
+        Event::Mouse(mouse_event) => matches!(mouse_event.kind, MouseEventKind::Down(_)),
+        _ => false,
+    }
+}
+
+/// What the frame loop is doing to save power.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Activity {
+    Active,
+    Idle,
+    Unfocused,
+}
+
+/// The HUD label for a location: its configured name, else its city.
+pub fn location_label(location: &Location) -> Option<String> {
+    location.name.clone().or_else(|| location.city.clone())
+}
+
 pub struct App {
-    state: AppState,
-    animations: AnimationManager,
-    scene: WorldScene,
-    weather_receiver: mpsc::Receiver<Result<WeatherData, WeatherError>>,
+    panes: Vec<Pane>,
+    split: SplitLayout,
     hide_hud: bool,
+    hud_style: HudStyle,
+    theme: Theme,
+    cycle: Vec<Location>,
+    active_location: usize,
+    simulated: Option<WeatherData>,
+    show_help: bool,
+    show_details: bool,
+    show_locations: bool,
+    show_debug: bool,
+    keymap: Keymap,
+    /// The only source of randomness for every pane's animations.
+    rng: StdRng,
+    frame_duration: Duration,
+    power_mode: PowerMode,
+    focused: bool,
+    last_input: Instant,
+    profiler: Profiler,
 }
 
 impl App {
+    /// `scenes` holds one scene per configured location, in order. With
+    /// `simulated` set every pane shows that weather and nothing is fetched.
+    /// The same `seed` and weather give the same frames.
     pub fn new(
         config: &Config,
-        simulate_condition: Option<String>,
-        simulate_night: bool,
+        scenes: Vec<Box<dyn Scene>>,
+        simulated: Option<WeatherData>,
+        seed: Option<u64>,
         show_leaves: bool,
         term_width: u16,
         term_height: u16,
     ) -> Self {
-        let location = WeatherLocation {
-            latitude: config.location.latitude,
-            longitude: config.location.longitude,
-            elevation: None,
+        let locations = config.pane_locations();
+        let rects = pane::pane_rects(locations.len(), term_width, term_height, config.split);
+        let provider = Arc::new(OpenMeteoProvider::new());
+        let mut rng = match seed {
+            Some(seed) => StdRng::seed_from_u64(seed),
+            None => rand::make_rng(),
         };
 
-        let mut state = AppState::new(
-            location,
-            config.location.city.clone(),
-            config.location.display,
-            config.location.hide,
-            config.units,
-        );
-        let mut animations = AnimationManager::new(term_width, term_height, show_leaves);
-        let scene = WorldScene::new(term_width, term_height);
-
-        let (tx, rx) = mpsc::channel(1);
-
-        if let Some(ref condition_str) = simulate_condition {
-            let simulated_condition =
-                condition_str
-                    .parse::<WeatherCondition>()
-                    .unwrap_or_else(|e| {
-                        eprintln!("{}", e);
-                        WeatherCondition::Clear
-                    });
+        // An invalid [hud] table was already reported when loading
+        let hud_format = config.hud_template().unwrap_or_default();
+        let keymap = config.keymap().unwrap_or_default();
 
-            let weather = WeatherData {
-                condition: simulated_condition,
-                temperature: 20.0,
-                apparent_temperature: 19.0,
-                humidity: 65.0,
-                precipitation: if simulated_condition.is_raining() {
-                    2.5
-                } else {
-                    0.0
-                },
-                wind_speed: if simulated_condition.is_thunderstorm() {
-                    45.0
-                } else {
-                    10.0
-                },
-                wind_direction: 225.0,
-                cloud_cover: 50.0,
-                pressure: 1013.0,
-                visibility: Some(10000.0),
-                is_day: !simulate_night,
-                moon_phase: Some(0.5),
-                timestamp: "simulated".to_string(),
+        let mut panes = Vec::with_capacity(locations.len());
+        for ((location_config, scene), rect) in locations.into_iter().zip(scenes).zip(rects) {
+            let location = WeatherLocation {
+                latitude: location_config.latitude,
+                longitude: location_config.longitude,
+                elevation: None,
             };
 
-            let rain_intensity = weather.condition.rain_intensity();
-            let snow_intensity = weather.condition.snow_intensity();
-
-            let wind_speed = weather.wind_speed;
-            let wind_direction = weather.wind_direction;
+            let mut state = AppState::new(
+                location,
+                location_label(location_config),
+                location_config.display,
+                location_config.hide,
+                config.units,
+            );
+            state.hud_format = hud_format.clone();
+            state.quit_key = Some(keymap.quit_key());
+            let animations = AnimationManager::new(rect.width, rect.height, show_leaves, &mut rng);
+            let clock = match seed {
+                Some(_) => SceneClock::SEEDED,
+                None => SceneClock::Local,
+            };
+            let scene = WorldScene::new(rect.width, rect.height, scene).with_clock(clock);
 
-            state.update_weather(weather);
-            animations.update_rain_intensity(rain_intensity);
-            animations.update_snow_intensity(snow_intensity);
-            animations.update_wind(wind_speed as f32, wind_direction as f32);
-        } else {
-            let provider = Arc::new(OpenMeteoProvider::new());
-            let weather_client = WeatherClient::new(provider, REFRESH_INTERVAL);
-            let units = config.units;
-
-            tokio::spawn(async move {
-                loop {
-                    let result = weather_client.get_current_weather(&location, &units).await;
-                    if tx.send(result).await.is_err() {
-                        break;
-                    }
-                    tokio::time::sleep(REFRESH_INTERVAL).await;
-                }
+            let feed = simulated.is_none().then(|| {
+                let client = WeatherClient::new(provider.clone(), REFRESH_INTERVAL);
+                WeatherFeed::spawn(client, location, config.units, REFRESH_INTERVAL)
             });
+
+            let mut pane = Pane::new(state, animations, scene, feed, rect);
+            if let Some(weather) = &simulated {
+                pane.apply_weather(weather.clone());
+            }
+            panes.push(pane);
         }
 
         Self {
-            state,
-            animations,
-            scene,
-            weather_receiver: rx,
+            panes,
+            split: config.split,
             hide_hud: config.hide_hud,
+            hud_style: config.hud_style().unwrap_or_default(),
+            theme: config.theme().unwrap_or_default(),
+            cycle: config.cycle_locations(),
+            active_location: 0,
+            simulated,
+            show_help: false,
+            show_details: false,
+            show_locations: false,
+            show_debug: false,
+            keymap,
+            rng,
+            frame_duration: Duration::from_secs(1) / DEFAULT_FPS,
+            power_mode: config.power_mode,
+            focused: true,
+            last_input: Instant::now(),
+            profiler: Profiler::new(),
         }
     }
 
-    pub async fn run(&mut self, renderer: &mut TerminalRenderer) -> io::Result<()> {
-        let mut rng = rand::rng();
-        loop {
-            if let Ok(result) = self.weather_receiver.try_recv() {
-                match result {
-                    Ok(weather) => {
-                        let rain_intensity = weather.condition.rain_intensity();
-                        let snow_intensity = weather.condition.snow_intensity();
-                        let fog_intensity = weather.condition.fog_intensity();
-                        let wind_speed = weather.wind_speed;
-                        let wind_direction = weather.wind_direction;
-
-                        self.state.update_weather(weather);
-                        self.animations.update_rain_intensity(rain_intensity);
-                        self.animations.update_snow_intensity(snow_intensity);
-                        self.animations.update_fog_intensity(fog_intensity);
-                        self.animations
-                            .update_wind(wind_speed as f32, wind_direction as f32);
-                    }
-                    Err(error) => {
-                        let _error_msg = match &error {
-                            WeatherError::Network(net_err) => net_err.user_friendly_message(),
-                            _ => format!("Failed to fetch weather: {}", error),
-                        };
-
-                        if self.state.current_weather.is_none() {
-                            let offline_weather = generate_offline_weather(&mut rng);
-                            let rain_intensity = offline_weather.condition.rain_intensity();
-                            let snow_intensity = offline_weather.condition.snow_intensity();
-                            let fog_intensity = offline_weather.condition.fog_intensity();
-                            let wind_speed = offline_weather.wind_speed;
-                            let wind_direction = offline_weather.wind_direction;
-
-                            self.state.update_weather(offline_weather);
-                            self.state.set_offline_mode(true);
-                            self.animations.update_rain_intensity(rain_intensity);
-                            self.animations.update_snow_intensity(snow_intensity);
-                            self.animations.update_fog_intensity(fog_intensity);
-                            self.animations
-                                .update_wind(wind_speed as f32, wind_direction as f32);
-                        } else {
-                            self.state.set_offline_mode(true);
-                        }
-                    }
+    /// Aim for `fps` frames per second instead of `DEFAULT_FPS`. Animations
+    /// run at the same speed either way.
+    pub fn with_fps(mut self, fps: u32) -> Self {
+        self.frame_duration = Duration::from_secs(1) / fps.clamp(1, MAX_FPS);
+        self
+    }
+
+    /// Start with the debug overlay open.
+    pub fn with_debug(mut self, show: bool) -> Self {
+        self.show_debug = show;
+        self
+    }
+
+    pub fn frame_duration(&self) -> Duration {
+        self.frame_duration
+    }
+
+    fn activity(&self) -> Activity {
+        if self.power_mode != PowerMode::Eco {
+            Activity::Active
+        } else if !self.focused {
+            Activity::Unfocused
+        } else if self.last_input.elapsed() >= IDLE_AFTER {
+            Activity::Idle
+        } else {
+            Activity::Active
+        }
+    }
+
+    /// How long the current frame should last, allowing for eco mode.
+    fn target_frame_duration(&self) -> Duration {
+        match self.activity() {
+            Activity::Active => self.frame_duration,
+            Activity::Idle => self
+                .frame_duration
+                .max(Duration::from_secs(1) / BATTERY_SAVER_FPS),
+            Activity::Unfocused => Duration::from_secs(1) / UNFOCUSED_FPS,
+        }
+    }
+
+    fn debug_rows(&self) -> Vec<(&'static str, String)> {
+        let stats = self.profiler.stats();
+        let millis = |time: Duration| format!("{:.2} ms", time.as_secs_f64() * 1000.0);
+        let target = 1.0 / self.target_frame_duration().as_secs_f64();
+        let mut rows = vec![
+            ("FPS", format!("{:.1} (target {:.0})", stats.fps, target)),
+            ("Frame", millis(stats.frame_time())),
+        ];
+        for (stage, time) in Stage::ALL.iter().zip(stats.stages) {
+            rows.push((stage.label(), millis(time)));
+        }
+        rows.push(("Bytes/flush", stats.flush_bytes.to_string()));
+
+        // Summed over panes, in the order the systems are drawn
+        let mut particles: Vec<(&'static str, usize)> = Vec::new();
+        for pane in &self.panes {
+            for &(name, count) in pane.animations().particle_counts() {
+                match particles.iter_mut().find(|(n, _)| *n == name) {
+                    Some((_, total)) => *total += count,
+                    None => particles.push((name, count)),
                 }
             }
+        }
+        rows.extend(
+            particles
+                .into_iter()
+                .map(|(name, count)| (name, count.to_string())),
+        );
 
-            renderer.clear()?;
+        let power = match (self.power_mode, self.activity()) {
+            (PowerMode::Normal, _) => "normal",
+            (PowerMode::Eco, Activity::Active) => "eco",
+            (PowerMode::Eco, Activity::Idle) => "eco, idle",
+            (PowerMode::Eco, Activity::Unfocused) => "eco, unfocused",
+        };
+        rows.push(("Power", power.to_string()));
+        rows
+    }
 
-            let (term_width, term_height) = renderer.get_size();
+    /// Switch the full-screen scene to `cycle[index]`. Ignored in split view,
+    /// where every pane already has its own location.
+    async fn select_location(&mut self, index: usize) {
+        if self.panes.len() != 1 || index >= self.cycle.len() || index == self.active_location {
+            return;
+        }
+        self.active_location = index;
 
-            self.animations.render_background(
-                renderer,
-                &self.state.weather_conditions,
-                &self.state,
-                term_width,
-                term_height,
-                &mut rng,
-            )?;
+        let location = &self.cycle[index];
+        let pane = &mut self.panes[0];
+        pane.retarget(location, location_label(location)).await;
+        if let Some(weather) = &self.simulated {
+            pane.apply_weather(weather.clone());
+        }
+    }
 
-            self.scene
-                .render(renderer, &self.state.weather_conditions)?;
+    fn layout(&mut self, term_width: u16, term_height: u16) {
+        let rects = pane::pane_rects(self.panes.len(), term_width, term_height, self.split);
+        for (pane, rect) in self.panes.iter_mut().zip(rects) {
+            pane.resize(rect);
+        }
+    }
 
-            self.animations.render_chimney_smoke(
-                renderer,
-                &self.state.weather_conditions,
-                term_width,
-                term_height,
-                &mut rng,
-            )?;
+    fn toggle_pause(&mut self) {
+        for pane in &mut self.panes {
+            let animations = pane.animations_mut();
+            animations.set_paused(!animations.is_paused());
+        }
+    }
+
+    fn change_speed(&mut self, faster: bool) {
+        for pane in &mut self.panes {
+            let animations = pane.animations_mut();
+            if faster {
+                animations.speed_up();
+            } else {
+                animations.slow_down();
+            }
+        }
+    }
 
-            self.animations.render_foreground(
+    async fn handle_action(&mut self, action: Action) {
+        match action {
+            Action::Quit => {}
+            Action::Refresh => {
+                for pane in &self.panes {
+                    pane.refresh_weather();
+                }
+            }
+            Action::Pause => self.toggle_pause(),
+            Action::SpeedUp => self.change_speed(true),
+            Action::SlowDown => self.change_speed(false),
+            Action::ToggleHud => self.hide_hud = !self.hide_hud,
+            Action::ToggleHelp => self.show_help = !self.show_help,
+            Action::ToggleDetails => self.show_details = !self.show_details,
+            Action::ToggleLocations => self.show_locations = !self.show_locations,
+            Action::ToggleDebug => self.show_debug = !self.show_debug,
+            Action::CloseOverlay => {
+                self.show_help = false;
+                self.show_details = false;
+                self.show_locations = false;
+            }
+            Action::NextLocation => {
+                let next = (self.active_location + 1) % self.cycle.len();
+                self.select_location(next).await;
+            }
+            Action::PreviousLocation => {
+                let len = self.cycle.len();
+                self.select_location((self.active_location + len - 1) % len)
+                    .await;
+            }
+            Action::SelectLocation(index) => self.select_location(index).await,
+        }
+    }
+
+    /// List labels for the saved locations. Hidden ones only get a number.
+    fn location_labels(&self) -> Vec<String> {
+        self.cycle
+            .iter()
+            .enumerate()
+            .map(|(i, location)| {
+                if location.hide {
+                    return format!("Location {}", i + 1);
+                }
+                location_label(location).unwrap_or_else(|| {
+                    format!("{:.2}, {:.2}", location.latitude, location.longitude)
+                })
+            })
+            .collect()
+    }
+
+    async fn handle_mouse(&mut self, event: MouseEvent, screen: (u16, u16)) {
+        if event.kind != MouseEventKind::Down(MouseButton::Left) {
+            return;
+        }
+        let (x, y) = (event.column, event.row);
+
+        if self.show_locations {
+            let labels = self.location_labels();
+            if let Some(index) = overlay::location_at(&labels, self.active_location, screen, (x, y))
+            {
+                self.select_location(index).await;
+            }
+            self.show_locations = false;
+            return;
+        }
+
+        if !self.hide_hud
+            && self
+                .panes
+                .iter()
+                .any(|pane| pane.hud_contains(&self.hud_style, x, y))
+        {
+            self.show_details = !self.show_details;
+            return;
+        }
+
+        for pane in &mut self.panes {
+            if pane.click(x, y) {
+                break;
+            }
+        }
+    }
+
+    fn status_text(&self) -> Option<String> {
+        let animations = self.panes.first()?.animations();
+        if animations.is_paused() {
+            Some("PAUSED".to_string())
+        } else if animations.speed() != 1.0 {
+            Some(format!("Speed {}x", animations.speed()))
+        } else {
+            None
+        }
+    }
+
+    /// Draw one frame of every pane and overlay into the renderer's buffer.
+    pub fn draw(&mut self, renderer: &mut TerminalRenderer) -> io::Result<()> {
+        renderer.clear()?;
+
+        for pane in &mut self.panes {
+            let hud = (!self.hide_hud).then_some(&self.hud_style);
+            pane.render(
                 renderer,
-                &self.state.weather_conditions,
-                term_width,
-                term_height,
-                &mut rng,
+                &self.theme,
+                hud,
+                &mut self.rng,
+                &mut self.profiler,
             )?;
+        }
+        let rects: Vec<_> = self.panes.iter().map(Pane::rect).collect();
+        // Separators and overlays follow the first pane's day or night
+        self.panes[0].apply_palette(renderer, &self.theme);
+        pane::render_separators(renderer, &rects)?;
 
-            self.state.update_loading_animation();
-            self.state.update_cached_info();
-
-            if !self.hide_hud {
-                renderer.render_line_colored(
-                    2,
-                    1,
-                    &self.state.cached_weather_info,
-                    crossterm::style::Color::Cyan,
-                )?;
+        if let Some(status) = self.status_text() {
+            overlay::render_status(renderer, &status)?;
+        }
+        if self.show_details {
+            let now = chrono::Local::now();
+            for pane in &self.panes {
+                pane.apply_palette(renderer, &self.theme);
+                renderer.set_viewport(pane.rect());
+                // Panes this small already show the "too small" notice
+                if !renderer.is_too_small() {
+                    overlay::render_details(renderer, &pane.state.detail_rows(now))?;
+                }
             }
+            renderer.reset_viewport();
+            self.panes[0].apply_palette(renderer, &self.theme);
+        }
+        if self.show_debug {
+            overlay::render_debug(renderer, &self.debug_rows())?;
+        }
+        if self.show_locations {
+            overlay::render_locations(renderer, &self.location_labels(), self.active_location)?;
+        }
+        if self.show_help {
+            overlay::render_help(renderer, &self.keymap)?;
+        }
+        Ok(())
+    }
 
-            renderer.flush()?;
+    /// Let `dt` of real time pass; the next `draw` shows the result.
+    pub fn update_animations(&mut self, dt: Duration) {
+        for pane in &mut self.panes {
+            pane.advance(dt);
+        }
+    }
+
+    pub async fn run(&mut self, renderer: &mut TerminalRenderer) -> io::Result<()> {
+        let mut last_frame = Instant::now();
+        'frames: loop {
+            for pane in &mut self.panes {
+                pane.poll_weather(|| generate_offline_weather(&mut self.rng));
+            }
 
-            if event::poll(FRAME_DURATION)? {
-                match event::read()? {
+            self.draw(renderer)?;
+            self.profiler.time(Stage::Flush, || renderer.flush())?;
+            self.profiler.end_frame(renderer.last_flush_bytes());
+
+            // Handle input until the next frame is due rather than drawing
+            // on every event, so mouse motion can't push the frame rate up
+            loop {
+                let wait = self
+                    .target_frame_duration()
+                    .saturating_sub(last_frame.elapsed());
+                if !event::poll(wait)? {
+                    break;
+                }
+                let event = event::read()?;
+                if is_user_input(&event) {
+                    self.last_input = Instant::now();
+                    // Not every terminal reports getting focus back, but
+                    // typing into one means it has it
+                    self.focused = true;
+                }
+                match event {
                     Event::Resize(width, height) => {
                         renderer.manual_resize(width, height)?;
                     }
-                    Event::Key(key_event) => match key_event.code {
-                        KeyCode::Char('q') | KeyCode::Char('Q') => break,
-                        KeyCode::Char('c')
-                            if key_event.modifiers.contains(KeyModifiers::CONTROL) =>
-                        {
-                            break;
+                    Event::Key(key_event) => {
+                        if let Some(action) = self.keymap.action_for(&key_event) {
+                            if action == Action::Quit {
+                                break 'frames;
+                            }
+                            self.handle_action(action).await;
                         }
-                        _ => {}
-                    },
+                    }
+                    Event::Mouse(mouse_event) => {
+                        self.handle_mouse(mouse_event, renderer.get_size()).await;
+                    }
+                    Event::FocusGained => self.focused = true,
+                    Event::FocusLost => self.focused = false,
                     _ => {}
                 }
             }
 
             let (term_width, term_height) = renderer.get_size();
-            self.scene.update_size(term_width, term_height);
+            self.layout(term_width, term_height);
 
-            self.animations
-                .update_sunny_animation(&self.state.weather_conditions);
+            let now = Instant::now();
+            // Out of focus in eco mode the animations stand still rather
+            // than catch up afterwards
+            if self.activity() != Activity::Unfocused {
+                self.update_animations(now - last_frame);
+            }
+            last_frame = now;
         }
 
         Ok(())
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::render::{MemoryBackend, Style};
+    use crossterm::style::Color;
+    use std::path::PathBuf;
+
+    const CONDITIONS: [&str; 14] = [
+        "clear",
+        "partly_cloudy",
+        "cloudy",
+        "overcast",
+        "fog",
+        "drizzle",
+        "rain",
+        "freezing_rain",
+        "snow",
+        "snow_grains",
+        "rain_showers",
+        "snow_showers",
+        "thunderstorm",
+        "thunderstorm_hail",
+    ];
+    const SIZES: [(u16, u16); 3] = [(40, 12), (80, 24), (120, 40)];
+    const FRAMES: u32 = 20;
+
+    /// The screen after `FRAMES` frames: the characters, then the same grid
+    /// with one key per distinct style, then what each key stands for.
+    fn golden_frame(condition: &str, night: bool, (width, height): (u16, u16)) -> String {
+        let config = Config::default();
+        let weather = simulated_weather(condition, night);
+        let scenes = vec![config.scene.build()];
+        let mut app = App::new(
+            &config,
+            scenes,
+            Some(weather),
+            Some(1),
+            false,
+            width,
+            height,
+        );
+        let mut renderer = TerminalRenderer::headless(width, height);
+        for _ in 0..FRAMES {
+            app.draw(&mut renderer).unwrap();
+            app.update_animations(app.frame_duration());
+        }
+        renderer.flush().unwrap();
+        let memory = renderer.backend::<MemoryBackend>().unwrap();
+
+        let mut styles: Vec<Style> = vec![Style::default()];
+        let mut grid = String::new();
+        for y in 0..memory.height() {
+            let line: String = memory
+                .row(y)
+                .iter()
+                .map(|cell| {
+                    let style = cell.style();
+                    let index = styles.iter().position(|&s| s == style).unwrap_or_else(|| {
+                        styles.push(style);
+                        styles.len() - 1
+                    });
+                    style_key(index)
+                })
+                .collect();
+            grid += line.trim_end();
+            grid.push('\n');
+        }
+
+        let legend: String = styles
+            .iter()
+            .enumerate()
+            .skip(1)
+            .map(|(index, style)| format!("{} {}\n", style_key(index), describe_style(style)))
+            .collect();
+        format!("{}\n{}\n{}", memory.text(), grid, legend)
+    }
+
+    /// The default style is a space; the rest count up from `a`.
+    fn style_key(index: usize) -> char {
+        const KEYS: &str = "abcdefghijklmnopqrstuvwxyzABCDEFGHIJKLMNOPQRSTUVWXYZ0123456789";
+        match index {
+            0 => ' ',
+            _ => KEYS
+                .chars()
+                .nth(index - 1)
+                .or_else(|| char::from_u32(0xC0 + (index - 1 - KEYS.len()) as u32))
+                .unwrap(),
+        }
+    }
+
+    fn describe_style(style: &Style) -> String {
+        let color = |color: Color| match color {
+            Color::Rgb { r, g, b } => format!("#{r:02x}{g:02x}{b:02x}"),
+            other => format!("{other:?}").to_lowercase(),
+        };
+        let mut text = format!("fg={} bg={}", color(style.color), color(style.background));
+        for attribute in Style::ATTRIBUTES {
+            if style.attributes.has(attribute) {
+                text += &format!(" {attribute:?}").to_lowercase();
+            }
+        }
+        text
+    }
+
+    #[test]
+    fn test_eco_mode_slows_down_unattended() {
+        let mut app = crate::snapshot::tests::test_app("clear", (80, 24), 1).with_fps(30);
+        let full = Duration::from_secs(1) / 30;
+        assert_eq!(app.target_frame_duration(), full);
+
+        // Normal mode ignores focus
+        app.focused = false;
+        assert_eq!(app.target_frame_duration(), full);
+
+        app.power_mode = PowerMode::Eco;
+        assert_eq!(app.activity(), Activity::Unfocused);
+        assert_eq!(app.target_frame_duration(), Duration::from_secs(1));
+
+        app.focused = true;
+        assert_eq!(app.activity(), Activity::Active);
+        assert_eq!(app.target_frame_duration(), full);
+
+        app.last_input = Instant::now() - IDLE_AFTER;
+        assert_eq!(app.activity(), Activity::Idle);
+        assert_eq!(
+            app.target_frame_duration(),
+            Duration::from_secs(1) / BATTERY_SAVER_FPS
+        );
+        assert_eq!(app.debug_rows().last().unwrap().1, "eco, idle");
+    }
+
+    #[test]
+    fn test_only_keys_and_clicks_count_as_input() {
+        use crossterm::event::{KeyCode, KeyEvent, KeyModifiers, MouseEvent};
+
+        let mouse = |kind| {
+            Event::Mouse(MouseEvent {
+                kind,
+                column: 3,
+                row: 4,
+                modifiers: KeyModifiers::NONE,
+            })
+        };
+        assert!(is_user_input(&Event::Key(KeyEvent::new(
+            KeyCode::Char('h'),
+            KeyModifiers::NONE
+        ))));
+        assert!(is_user_input(&mouse(MouseEventKind::Down(
+            MouseButton::Left
+        ))));
+        assert!(!is_user_input(&mouse(MouseEventKind::Moved)));
+        assert!(!is_user_input(&mouse(MouseEventKind::Up(
+            MouseButton::Left
+        ))));
+        assert!(!is_user_input(&Event::Resize(80, 24)));
+        assert!(!is_user_input(&Event::FocusGained));
+    }
+
+    /// Compare every condition, day and night, at each size with the files in
+    /// `tests/golden`. Run with `UPDATE_GOLDEN=1` to rewrite them.
+    #[test]
+    fn test_golden_frames() {
+        let dir = PathBuf::from(env!("CARGO_MANIFEST_DIR")).join("tests/golden");
+        let update = std::env::var_os("UPDATE_GOLDEN").is_some();
+        let mut mismatches = Vec::new();
+
+        for condition in CONDITIONS {
+            for night in [false, true] {
+                for size in SIZES {
+                    let name = format!(
+                        "{}_{}_{}x{}.txt",
+                        condition,
+                        if night { "night" } else { "day" },
+                        size.0,
+                        size.1
+                    );
+                    let frame = golden_frame(condition, night, size);
+                    let path = dir.join(&name);
+                    if update {
+                        std::fs::create_dir_all(&dir).unwrap();
+                        std::fs::write(&path, &frame).unwrap();
+                    } else if std::fs::read_to_string(&path).ok().as_deref() != Some(&frame) {
+                        mismatches.push(name);
+                    }
+                }
+            }
+        }
+
+        assert!(
+            mismatches.is_empty(),
+            "frames differ from tests/golden (rerun with UPDATE_GOLDEN=1 if intended): {:?}",
+            mismatches
+        );
+    }
+}
@@ -1,8 +1,10 @@
 use crate::config::LocationDisplay;
+use crate::hud::{HudField, HudTemplate};
 use crate::weather::{
     WeatherCondition, WeatherConditions, WeatherData, WeatherLocation, WeatherUnits,
-    format_precipitation, format_temperature, format_wind_speed,
+    format_precipitation, format_temperature, format_visibility, format_wind_speed, wind_compass,
 };
+use chrono::{DateTime, FixedOffset, Local, NaiveDateTime, TimeDelta, TimeZone};
 use std::time::Instant;
 
 pub struct AppState {
@@ -17,6 +19,9 @@ pub struct AppState {
     pub location_display: LocationDisplay,
     pub hide_location: bool,
     pub units: WeatherUnits,
+    pub hud_format: HudTemplate,
+    /// Shown by `{quit_key}`; unset outside the interactive app.
+    pub quit_key: Option<String>,
 }
 
 impl AppState {
@@ -39,9 +44,21 @@ impl AppState {
             location_display,
             hide_location,
             units,
+            hud_format: HudTemplate::default(),
+            quit_key: None,
         }
     }
 
+    /// Point the state at a different location, dropping the old weather.
+    pub fn set_location(&mut self, location: WeatherLocation, city_name: Option<String>) {
+        self.location = location;
+        self.city_name = city_name;
+        self.current_weather = None;
+        self.weather_conditions = WeatherConditions::default();
+        self.is_offline = false;
+        self.weather_info_needs_update = true;
+    }
+
     pub fn update_weather(&mut self, weather: WeatherData) {
         self.weather_conditions.is_thunderstorm = weather.condition.is_thunderstorm();
         self.weather_conditions.is_snowing = weather.condition.is_snowing();
@@ -91,61 +108,163 @@ impl AppState {
         }
     }
 
-    pub fn update_cached_info(&mut self) {
-        if !self.weather_info_needs_update {
-            return;
+    /// The location as configured by `location_display`, or `None` when it
+    /// must stay hidden.
+    pub fn location_label(&self) -> Option<String> {
+        if self.hide_location {
+            return None;
         }
 
-        let location_str = if self.hide_location {
-            String::new()
+        let (lat_value, lat_dir) = if self.location.latitude >= 0.0 {
+            (self.location.latitude, "N")
         } else {
-            let (lat_value, lat_dir) = if self.location.latitude >= 0.0 {
-                (self.location.latitude, "N")
-            } else {
-                (-self.location.latitude, "S")
-            };
-            let (lon_value, lon_dir) = if self.location.longitude >= 0.0 {
-                (self.location.longitude, "E")
-            } else {
-                (-self.location.longitude, "W")
-            };
-            let coords = format!("{:.2}°{}, {:.2}°{}", lat_value, lat_dir, lon_value, lon_dir);
-            let label = match self.location_display {
-                LocationDisplay::Coordinates => coords,
-                LocationDisplay::City => match &self.city_name {
-                    Some(city) => city.clone(),
-                    None => coords,
-                },
-                LocationDisplay::Mixed => match &self.city_name {
-                    Some(city) => format!("{} ({})", city, coords),
-                    None => coords,
-                },
-            };
-            format!(" | Location: {}", label)
+            (-self.location.latitude, "S")
         };
-
-        self.cached_weather_info = if let Some(ref weather) = self.current_weather {
-            let (temp, temp_unit) = format_temperature(weather.temperature, self.units.temperature);
-            let (wind, wind_unit) = format_wind_speed(weather.wind_speed, self.units.wind_speed);
-            let (precip, precip_unit) =
-                format_precipitation(weather.precipitation, self.units.precipitation);
-
-            let offline_indicator = if self.is_offline { "OFFLINE | " } else { "" };
-
-            format!(
-                "{}Weather: {} | Temp: {:.1}{} | Wind: {:.1}{} | Precip: {:.1}{}{} | Press 'q' to quit",
-                offline_indicator,
-                self.get_condition_text(),
-                temp,
-                temp_unit,
-                wind,
-                wind_unit,
-                precip,
-                precip_unit,
-                location_str
-            )
+        let (lon_value, lon_dir) = if self.location.longitude >= 0.0 {
+            (self.location.longitude, "E")
         } else {
-            format!("Weather: Loading... {}", self.loading_state.current_char())
+            (-self.location.longitude, "W")
+        };
+        let coords = format!("{:.2}°{}, {:.2}°{}", lat_value, lat_dir, lon_value, lon_dir);
+        let label = match self.location_display {
+            LocationDisplay::Coordinates => coords,
+            LocationDisplay::City => match &self.city_name {
+                Some(city) => city.clone(),
+                None => coords,
+            },
+            LocationDisplay::Mixed => match &self.city_name {
+                Some(city) => format!("{} ({})", city, coords),
+                None => coords,
+            },
+        };
+        Some(label)
+    }
+
+    /// Every field of the current weather as (label, value) rows for the
+    /// details panel. `now` is used to work out how old the data is.
+    pub fn detail_rows(&self, now: DateTime<Local>) -> Vec<(&'static str, String)> {
+        let Some(weather) = &self.current_weather else {
+            return vec![("Weather", "Loading...".to_string())];
+        };
+
+        let (temp, temp_unit) = format_temperature(weather.temperature, self.units.temperature);
+        let (feels, _) = format_temperature(weather.apparent_temperature, self.units.temperature);
+        let (wind, wind_unit) = format_wind_speed(weather.wind_speed, self.units.wind_speed);
+        let (precip, precip_unit) =
+            format_precipitation(weather.precipitation, self.units.precipitation);
+        let (arrow, from) = wind_compass(weather.wind_direction);
+        let visibility = match weather.visibility {
+            Some(meters) => {
+                let (value, unit) = format_visibility(meters, self.units.precipitation);
+                format!("{:.1} {}", value, unit)
+            }
+            None => "n/a".to_string(),
+        };
+
+        let mut rows = vec![
+            ("Condition", self.get_condition_text().to_string()),
+            ("Temperature", format!("{:.1}{}", temp, temp_unit)),
+            ("Feels like", format!("{:.1}{}", feels, temp_unit)),
+            ("Humidity", format!("{:.0}%", weather.humidity)),
+            ("Precipitation", format!("{:.1} {}", precip, precip_unit)),
+            ("Cloud cover", format!("{:.0}%", weather.cloud_cover)),
+            (
+                "Wind",
+                format!(
+                    "{:.1} {} {} from {} ({:.0}°)",
+                    wind, wind_unit, arrow, from, weather.wind_direction
+                ),
+            ),
+            ("Pressure", format!("{:.0} hPa", weather.pressure)),
+            ("Visibility", visibility),
+        ];
+
+        if let Some(label) = self.location_label() {
+            rows.push(("Location", label));
+        }
+
+        let updated = match parse_timestamp(&weather.timestamp) {
+            Some(time) => format!(
+                "{} ({})",
+                time.format("%Y-%m-%d %H:%M"),
+                format_age(now.signed_duration_since(time))
+            ),
+            None => weather.timestamp.clone(),
+        };
+        rows.push(("Updated", updated));
+
+        if self.is_offline {
+            rows.push(("Status", "Offline, showing last known data".to_string()));
+        }
+
+        rows
+    }
+
+    /// The value of one HUD placeholder, or `None` when it is unavailable.
+    fn hud_value(&self, weather: &WeatherData, field: HudField) -> Option<String> {
+        let value = match field {
+            HudField::Condition => self.get_condition_text().to_string(),
+            HudField::Temp => {
+                let (temp, unit) = format_temperature(weather.temperature, self.units.temperature);
+                format!("{:.1}{}", temp, unit)
+            }
+            HudField::FeelsLike => {
+                let (temp, unit) =
+                    format_temperature(weather.apparent_temperature, self.units.temperature);
+                format!("{:.1}{}", temp, unit)
+            }
+            HudField::Wind => {
+                let (wind, unit) = format_wind_speed(weather.wind_speed, self.units.wind_speed);
+                format!("{:.1}{}", wind, unit)
+            }
+            HudField::WindDir => {
+                let (arrow, from) = wind_compass(weather.wind_direction);
+                format!("{} {}", arrow, from)
+            }
+            HudField::Humidity => format!("{:.0}%", weather.humidity),
+            HudField::Pressure => format!("{:.0} hPa", weather.pressure),
+            HudField::Precip => {
+                let (precip, unit) =
+                    format_precipitation(weather.precipitation, self.units.precipitation);
+                format!("{:.1}{}", precip, unit)
+            }
+            HudField::Clouds => format!("{:.0}%", weather.cloud_cover),
+            HudField::Visibility => {
+                let (value, unit) =
+                    format_visibility(weather.visibility?, self.units.precipitation);
+                format!("{:.1}{}", value, unit)
+            }
+            HudField::Location => self.location_label()?,
+            HudField::Updated => {
+                let time = parse_timestamp(&weather.timestamp)?;
+                format_age(Local::now().signed_duration_since(time))
+            }
+            HudField::Offline => {
+                if !self.is_offline {
+                    return None;
+                }
+                "OFFLINE".to_string()
+            }
+            HudField::QuitKey => self.quit_key.clone()?,
+        };
+        Some(value)
+    }
+
+    /// Fill `template` in from the current weather, if there is any yet.
+    pub fn format_weather(&self, template: &HudTemplate) -> Option<String> {
+        let weather = self.current_weather.as_ref()?;
+        Some(template.render(|field| self.hud_value(weather, field)))
+    }
+
+    pub fn update_cached_info(&mut self) {
+        // Ages change on their own, so a HUD showing one is always rebuilt
+        if !self.weather_info_needs_update && !self.hud_format.uses(HudField::Updated) {
+            return;
+        }
+
+        self.cached_weather_info = match self.format_weather(&self.hud_format) {
+            Some(info) => info,
+            None => format!("Weather: Loading... {}", self.loading_state.current_char()),
         };
 
         self.weather_info_needs_update = false;
@@ -188,6 +307,36 @@ impl AppState {
     }
 }
 
+/// Read a provider timestamp. Times without an offset are taken as local.
+fn parse_timestamp(timestamp: &str) -> Option<DateTime<FixedOffset>> {
+    if let Ok(time) = DateTime::parse_from_rfc3339(timestamp) {
+        return Some(time);
+    }
+    for format in ["%Y-%m-%dT%H:%M%:z", "%Y-%m-%dT%H:%M:%S%:z"] {
+        if let Ok(time) = DateTime::parse_from_str(timestamp, format) {
+            return Some(time);
+        }
+    }
+    ["%Y-%m-%dT%H:%M", "%Y-%m-%dT%H:%M:%S"]
+        .iter()
+        .find_map(|format| NaiveDateTime::parse_from_str(timestamp, format).ok())
+        .and_then(|naive| Local.from_local_datetime(&naive).single())
+        .map(|time| time.fixed_offset())
+}
+
+fn format_age(age: TimeDelta) -> String {
+    let minutes = age.num_minutes();
+    if minutes < 1 {
+        "just now".to_string()
+    } else if minutes < 60 {
+        format!("{} min ago", minutes)
+    } else if minutes < 48 * 60 {
+        format!("{} h ago", minutes / 60)
+    } else {
+        format!("{} days ago", minutes / (24 * 60))
+    }
+}
+
 pub struct LoadingState {
     pub frame: usize,
     pub last_update: Instant,
@@ -400,4 +549,111 @@ mod tests {
         );
         assert!(!app.cached_weather_info.contains("("));
     }
+
+    #[test]
+    fn test_set_location_resets_weather() {
+        let mut app = create_app_state_full(
+            52.52,
+            13.41,
+            Some("Berlin".to_string()),
+            LocationDisplay::City,
+        );
+        let paris = WeatherLocation {
+            latitude: 48.85,
+            longitude: 2.35,
+            elevation: None,
+        };
+        app.set_location(paris, Some("Paris".to_string()));
+        app.update_cached_info();
+
+        assert!(app.current_weather.is_none());
+        assert!(app.cached_weather_info.starts_with("Weather: Loading"));
+        assert_eq!(app.location, paris);
+    }
+
+    fn detail<'a>(rows: &'a [(&str, String)], label: &str) -> Option<&'a str> {
+        rows.iter()
+            .find(|(l, _)| *l == label)
+            .map(|(_, value)| value.as_str())
+    }
+
+    #[test]
+    fn test_detail_rows_cover_all_fields() {
+        let app = create_app_state_full(
+            52.52,
+            13.41,
+            Some("Berlin".to_string()),
+            LocationDisplay::City,
+        );
+        let now = DateTime::parse_from_rfc3339("2024-01-01T12:25:00Z")
+            .unwrap()
+            .with_timezone(&Local);
+        let rows = app.detail_rows(now);
+
+        assert_eq!(detail(&rows, "Feels like"), Some("18.0°C"));
+        assert_eq!(detail(&rows, "Humidity"), Some("60%"));
+        assert_eq!(detail(&rows, "Pressure"), Some("1013 hPa"));
+        assert_eq!(detail(&rows, "Visibility"), Some("0.0 km"));
+        assert_eq!(detail(&rows, "Wind"), Some("36.0 km/h ↓ from N (0°)"));
+        assert_eq!(detail(&rows, "Location"), Some("Berlin"));
+        assert!(detail(&rows, "Updated").unwrap().ends_with("(25 min ago)"));
+    }
+
+    #[test]
+    fn test_detail_rows_respect_hide_location() {
+        let mut app = create_app_state(52.52, 13.41);
+        app.hide_location = true;
+        let rows = app.detail_rows(Local::now());
+        assert_eq!(detail(&rows, "Location"), None);
+        assert!(rows.iter().all(|(_, value)| !value.contains("52.52")));
+    }
+
+    #[test]
+    fn test_custom_hud_format() {
+        let mut app = create_app_state_full(
+            52.52,
+            13.41,
+            Some("Berlin".to_string()),
+            LocationDisplay::City,
+        );
+        app.hud_format =
+            HudTemplate::parse("{temp} feels {feels_like}, {wind_dir}[ @ {location}][ {offline}]")
+                .unwrap();
+        app.update_cached_info();
+        assert_eq!(app.cached_weather_info, "20.0°C feels 18.0°C, ↓ N @ Berlin");
+
+        app.hide_location = true;
+        app.set_offline_mode(true);
+        app.update_cached_info();
+        assert_eq!(app.cached_weather_info, "20.0°C feels 18.0°C, ↓ N OFFLINE");
+    }
+
+    #[test]
+    fn test_default_hud_names_quit_key() {
+        let mut app = create_app_state(52.52, 13.41);
+        app.update_cached_info();
+        assert!(!app.cached_weather_info.contains("to quit"));
+
+        app.quit_key = Some("x".to_string());
+        app.weather_info_needs_update = true;
+        app.update_cached_info();
+        assert!(app.cached_weather_info.contains("Press 'x' to quit"));
+    }
+
+    #[test]
+    fn test_wind_compass() {
+        assert_eq!(wind_compass(225.0), ('↗', "SW"));
+        assert_eq!(wind_compass(90.0), ('←', "E"));
+        assert_eq!(wind_compass(359.0), ('↓', "N"));
+        assert_eq!(wind_compass(-45.0), ('↘', "NW"));
+    }
+
+    #[test]
+    fn test_parse_timestamp_formats() {
+        assert!(parse_timestamp("2024-01-01T12:00").is_some());
+        assert!(parse_timestamp("2024-01-01T12:00+01:00").is_some());
+        assert!(parse_timestamp("2024-01-01T12:00:00Z").is_some());
+        assert!(parse_timestamp("simulated").is_none());
+        assert_eq!(format_age(TimeDelta::minutes(150)), "2 h ago");
+    }
 }
@@ -2,6 +2,7 @@ use crate::geolocation::GeoLocation;
 use crate::weather::WeatherData;
 use serde::{Deserialize, Serialize};
 use std::path::PathBuf;
+use std::time::Duration;
 use tokio::fs;
 
 const LOCATION_CACHE_DURATION_SECS: u64 = 86400;
@@ -31,7 +32,7 @@ fn current_timestamp() -> u64 {
         .unwrap_or(0)
 }
 
-fn make_location_key(latitude: f64, longitude: f64) -> String {
+pub fn make_location_key(latitude: f64, longitude: f64) -> String {
     format!("{:.2},{:.2}", latitude, longitude)
 }
 
@@ -113,7 +114,8 @@ pub fn save_geocode_cache(city_name: &str, latitude: f64, longitude: f64, langua
     });
 }
 
-pub async fn load_cached_weather(latitude: f64, longitude: f64) -> Option<WeatherData> {
+/// The cached weather for a location and how long ago it was stored.
+pub async fn load_cached_weather(latitude: f64, longitude: f64) -> Option<(WeatherData, Duration)> {
     let cache_path = get_cache_dir()?.join("weather.json");
     let contents = fs::read_to_string(&cache_path).await.ok()?;
     let cache: WeatherCache = serde_json::from_str(&contents).ok()?;
@@ -123,29 +125,29 @@ pub async fn load_cached_weather(latitude: f64, longitude: f64) -> Option<Weathe
         return None;
     }
 
-    let now = current_timestamp();
-    if now - cache.cached_at < WEATHER_CACHE_DURATION_SECS {
-        Some(cache.data)
+    let age = current_timestamp().saturating_sub(cache.cached_at);
+    if age < WEATHER_CACHE_DURATION_SECS {
+        Some((cache.data, Duration::from_secs(age)))
     } else {
         None
     }
 }
 
-pub fn save_weather_cache(weather: &WeatherData, latitude: f64, longitude: f64) {
-    let weather = weather.clone();
-    tokio::spawn(async move {
-        if let Some(cache_dir) = get_cache_dir() {
-            let _ = fs::create_dir_all(&cache_dir).await;
-
-            let cache = WeatherCache {
-                data: weather,
-                cached_at: current_timestamp(),
-                location_key: make_location_key(latitude, longitude),
-            };
-
-            if let Ok(json) = serde_json::to_string(&cache) {
-                let _ = fs::write(cache_dir.join("weather.json"), json).await;
-            }
-        }
-    });
+/// Write the weather cache. Awaited rather than spawned so that one-shot
+/// runs don't exit before the file is written.
+pub async fn save_weather_cache(weather: &WeatherData, latitude: f64, longitude: f64) {
+    let Some(cache_dir) = get_cache_dir() else {
+        return;
+    };
+    let _ = fs::create_dir_all(&cache_dir).await;
+
+    let cache = WeatherCache {
+        data: weather.clone(),
+        cached_at: current_timestamp(),
+        location_key: make_location_key(latitude, longitude),
+    };
+
+    if let Ok(json) = serde_json::to_string(&cache) {
+        let _ = fs::write(cache_dir.join("weather.json"), json).await;
+    }
 }
@@ -1,9 +1,16 @@
 use serde::Deserialize;
+use std::collections::BTreeMap;
 use std::env;
 use std::fs;
 use std::path::PathBuf;
 
 use crate::error::ConfigError;
+use crate::hud::{DEFAULT_FORMAT, HudAlign, HudPosition, HudStyle, HudTemplate};
+use crate::keymap::{KeySpec, Keymap};
+use crate::render::ColorMode;
+use crate::scene::SceneTheme;
+use crate::scene::custom::parse_color;
+use crate::theme::{Palette, Theme, ThemeName};
 use crate::weather::types::WeatherUnits;
 
 pub const ENV_LATITUDE: &str = "WEATHR_LATITUDE";
@@ -18,20 +25,125 @@ pub enum LocationDisplay {
     Mixed,
 }
 
+/// How panes are arranged when several locations are configured.
+#[derive(Deserialize, Debug, Default, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum SplitLayout {
+    /// Pick whichever arrangement gives the panes the most scene-like shape.
+    #[default]
+    Auto,
+    SideBySide,
+    Stacked,
+}
+
+/// How hard to try saving power while weathr runs unattended.
+#[derive(Deserialize, Debug, Default, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum PowerMode {
+    /// Always draw at the full frame rate.
+    #[default]
+    Normal,
+    /// Freeze the animations while the terminal is out of focus and drop to
+    /// a few frames per second after a minute without input.
+    Eco,
+}
+
 #[derive(Deserialize, Debug, Default, Clone)]
 pub struct Config {
     #[serde(default)]
     pub location: Location,
+    /// Extra locations for the split view. When present these replace
+    /// `location` and each gets its own pane.
+    #[serde(default)]
+    pub locations: Vec<Location>,
+    #[serde(default)]
+    pub split: SplitLayout,
+    /// Locations the full-screen scene can switch to with Tab or number keys.
+    #[serde(default)]
+    pub saved_locations: Vec<Location>,
     #[serde(default)]
     pub hide_hud: bool,
     #[serde(default)]
+    pub hud: HudConfig,
+    #[serde(default)]
     pub units: WeatherUnits,
     #[serde(default)]
     pub silent: bool,
+    #[serde(default)]
+    pub power_mode: PowerMode,
+    /// Colors to draw with when the terminal misreports its support.
+    #[serde(default)]
+    pub color_mode: ColorMode,
+    #[serde(default)]
+    pub theme: ThemeConfig,
+    #[serde(default)]
+    pub scene: SceneTheme,
+    #[serde(default)]
+    pub scene_file: Option<String>,
+    /// Overrides for the default key bindings, by action name.
+    #[serde(default)]
+    pub keys: BTreeMap<String, KeySpec>,
+}
+
+/// Layout and contents of the HUD line.
+#[derive(Deserialize, Debug, Clone)]
+#[serde(default)]
+pub struct HudConfig {
+    /// Template with `{placeholder}`s and optional `[sections]`.
+    pub format: String,
+    pub position: HudPosition,
+    pub align: HudAlign,
+    /// Color name or "#rrggbb". Defaults to the theme's `hud` color.
+    pub color: Option<String>,
+}
+
+impl Default for HudConfig {
+    fn default() -> Self {
+        Self {
+            format: DEFAULT_FORMAT.to_string(),
+            position: HudPosition::default(),
+            align: HudAlign::default(),
+            color: None,
+        }
+    }
+}
+
+/// A built-in theme and colors to replace in it.
+#[derive(Deserialize, Debug, Default, Clone)]
+#[serde(default)]
+pub struct ThemeConfig {
+    pub name: ThemeName,
+    /// Roles replaced in the day palette only.
+    pub day: BTreeMap<String, RoleColors>,
+    /// Roles replaced in the night palette only.
+    pub night: BTreeMap<String, RoleColors>,
+    /// Roles replaced in both palettes.
+    #[serde(flatten)]
+    pub roles: BTreeMap<String, RoleColors>,
+}
+
+/// One color, or a list of them for roles like `flowers`.
+#[derive(Deserialize, Debug, Clone, PartialEq)]
+#[serde(untagged)]
+pub enum RoleColors {
+    One(String),
+    Many(Vec<String>),
+}
+
+impl RoleColors {
+    fn as_slice(&self) -> &[String] {
+        match self {
+            RoleColors::One(color) => std::slice::from_ref(color),
+            RoleColors::Many(colors) => colors,
+        }
+    }
 }
 
 #[derive(Deserialize, Debug, Clone)]
 pub struct Location {
+    /// Label shown in the HUD instead of the resolved city name.
+    #[serde(default)]
+    pub name: Option<String>,
     #[serde(default = "default_latitude")]
     pub latitude: f64,
     #[serde(default = "default_longitude")]
@@ -63,6 +175,7 @@ pub fn default_longitude() -> f64 {
 impl Default for Location {
     fn default() -> Self {
         Self {
+            name: None,
             latitude: default_latitude(),
             longitude: default_longitude(),
             auto: true,
@@ -100,7 +213,10 @@ impl Config {
         Ok(config)
     }
 
+    /// Point the first pane at `WEATHR_LATITUDE` / `WEATHR_LONGITUDE`: the
+    /// first of `[[locations]]` when there are any, else `[location]`.
     fn apply_env_overrides(&mut self) -> Result<(), ConfigError> {
+        let location = self.pane_locations_mut().swap_remove(0);
         if let Ok(val) = env::var(ENV_LATITUDE) {
             let lat = val
                 .trim()
@@ -109,8 +225,8 @@ impl Config {
                     name: ENV_LATITUDE,
                     value: val.clone(),
                 })?;
-            self.location.latitude = lat;
-            self.location.auto = false;
+            location.latitude = lat;
+            location.auto = false;
         }
 
         if let Ok(val) = env::var(ENV_LONGITUDE) {
@@ -121,25 +237,104 @@ impl Config {
                     name: ENV_LONGITUDE,
                     value: val.clone(),
                 })?;
-            self.location.longitude = lon;
-            self.location.auto = false;
+            location.longitude = lon;
+            location.auto = false;
         }
 
         Ok(())
     }
 
     fn validate(&self) -> Result<(), ConfigError> {
-        if self.location.latitude < -90.0 || self.location.latitude > 90.0 {
-            return Err(ConfigError::InvalidLatitude(self.location.latitude));
-        }
+        let all = std::iter::once(&self.location)
+            .chain(&self.locations)
+            .chain(&self.saved_locations);
+        for location in all {
+            if location.latitude < -90.0 || location.latitude > 90.0 {
+                return Err(ConfigError::InvalidLatitude(location.latitude));
+            }
 
-        if self.location.longitude < -180.0 || self.location.longitude > 180.0 {
-            return Err(ConfigError::InvalidLongitude(self.location.longitude));
+            if location.longitude < -180.0 || location.longitude > 180.0 {
+                return Err(ConfigError::InvalidLongitude(location.longitude));
+            }
         }
 
+        self.keymap()?;
+        self.hud_template()?;
+        self.hud_style()?;
+        self.theme()?;
+
         Ok(())
     }
 
+    /// The default key bindings with any `[keys]` overrides applied.
+    pub fn keymap(&self) -> Result<Keymap, ConfigError> {
+        Keymap::from_config(&self.keys)
+    }
+
+    pub fn hud_template(&self) -> Result<HudTemplate, ConfigError> {
+        HudTemplate::parse(&self.hud.format).map_err(|message| ConfigError::InvalidHud {
+            field: "format",
+            message,
+        })
+    }
+
+    pub fn hud_style(&self) -> Result<HudStyle, ConfigError> {
+        let color = self
+            .hud
+            .color
+            .as_deref()
+            .map(|color| {
+                parse_color(color).ok_or_else(|| ConfigError::InvalidHud {
+                    field: "color",
+                    message: format!("unknown color {:?}", color),
+                })
+            })
+            .transpose()?;
+        Ok(HudStyle {
+            position: self.hud.position,
+            align: self.hud.align,
+            color,
+        })
+    }
+
+    /// The selected theme with any `[theme]` overrides applied. Roles set
+    /// directly in `[theme]` change both palettes, those in `[theme.day]` or
+    /// `[theme.night]` only one.
+    pub fn theme(&self) -> Result<Theme, ConfigError> {
+        let mut theme = self.theme.name.build();
+        for palette in [&mut theme.day, &mut theme.night] {
+            apply_theme_overrides(palette, &self.theme.roles, "")?;
+        }
+        apply_theme_overrides(&mut theme.day, &self.theme.day, "day.")?;
+        apply_theme_overrides(&mut theme.night, &self.theme.night, "night.")?;
+        Ok(theme)
+    }
+
+    /// The locations to show, one per pane.
+    pub fn pane_locations(&self) -> Vec<&Location> {
+        if self.locations.is_empty() {
+            vec![&self.location]
+        } else {
+            self.locations.iter().collect()
+        }
+    }
+
+    pub fn pane_locations_mut(&mut self) -> Vec<&mut Location> {
+        if self.locations.is_empty() {
+            vec![&mut self.location]
+        } else {
+            self.locations.iter_mut().collect()
+        }
+    }
+
+    /// Locations the single-pane view cycles through: the configured
+    /// location first, then `saved_locations` in order.
+    pub fn cycle_locations(&self) -> Vec<Location> {
+        let mut locations = vec![self.pane_locations()[0].clone()];
+        locations.extend(self.saved_locations.iter().cloned());
+        locations
+    }
+
     pub fn load_from_path(path: &PathBuf) -> Result<Self, ConfigError> {
         let content = fs::read_to_string(path).map_err(|e| ConfigError::ReadError {
             path: path.display().to_string(),
@@ -166,17 +361,70 @@ impl Config {
     }
 
     fn get_config_path() -> Result<PathBuf, ConfigError> {
+        Ok(Self::get_config_dir()?.join("config.toml"))
+    }
+
+    fn get_config_dir() -> Result<PathBuf, ConfigError> {
         let config_dir = dirs::config_dir()
             .or_else(|| dirs::home_dir().map(|h| h.join(".config")))
             .ok_or(ConfigError::NoConfigDir)?;
 
-        Ok(config_dir.join("weathr").join("config.toml"))
+        Ok(config_dir.join("weathr"))
+    }
+
+    /// Resolve `scene_file` to a path. Bare names are looked up in the
+    /// `scenes` directory next to config.toml.
+    pub fn resolve_scene_file(name: &str) -> Result<PathBuf, ConfigError> {
+        let path = PathBuf::from(name);
+        if path.is_absolute() || path.components().count() > 1 {
+            return Ok(path);
+        }
+
+        let mut path = Self::get_config_dir()?.join("scenes").join(name);
+        if path.extension().is_none() {
+            path.set_extension("toml");
+        }
+        Ok(path)
     }
 }
 
+fn apply_theme_overrides(
+    palette: &mut Palette,
+    overrides: &BTreeMap<String, RoleColors>,
+    prefix: &str,
+) -> Result<(), ConfigError> {
+    for (role, colors) in overrides {
+        let invalid = |message: String| ConfigError::InvalidTheme {
+            role: format!("{prefix}{role}"),
+            message,
+        };
+        let slots = palette.role_mut(role).ok_or_else(|| {
+            invalid(format!(
+                "unknown role (expected one of: {})",
+                Palette::ROLES.join(", ")
+            ))
+        })?;
+        let colors = colors.as_slice();
+        if colors.len() != slots.len() {
+            return Err(invalid(format!(
+                "expected {} color{}, got {}",
+                slots.len(),
+                if slots.len() == 1 { "" } else { "s" },
+                colors.len()
+            )));
+        }
+        for (slot, color) in slots.iter_mut().zip(colors) {
+            *slot =
+                parse_color(color).ok_or_else(|| invalid(format!("unknown color {:?}", color)))?;
+        }
+    }
+    Ok(())
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crossterm::style::Color;
     use std::sync::Mutex;
 
     static ENV_MUTEX: Mutex<()> = Mutex::new(());
@@ -307,6 +555,7 @@ longitude = 0.0
     fn test_validation_invalid_latitude_high() {
         let config = Config {
             location: Location {
+                name: None,
                 latitude: 91.0,
                 longitude: 0.0,
                 auto: false,
@@ -315,9 +564,7 @@ longitude = 0.0
                 display: LocationDisplay::default(),
                 city_name_language: "auto".to_string(),
             },
-            hide_hud: false,
-            units: WeatherUnits::default(),
-            silent: false,
+            ..Config::default()
         };
         let result = config.validate();
         assert!(result.is_err());
@@ -328,6 +575,7 @@ longitude = 0.0
     fn test_validation_invalid_latitude_low() {
         let config = Config {
             location: Location {
+                name: None,
                 latitude: -91.0,
                 longitude: 0.0,
                 auto: false,
@@ -336,9 +584,7 @@ longitude = 0.0
                 display: LocationDisplay::default(),
                 city_name_language: "auto".to_string(),
             },
-            hide_hud: false,
-            units: WeatherUnits::default(),
-            silent: false,
+            ..Config::default()
         };
         let result = config.validate();
         assert!(result.is_err());
@@ -349,6 +595,7 @@ longitude = 0.0
     fn test_validation_invalid_longitude_high() {
         let config = Config {
             location: Location {
+                name: None,
                 latitude: 0.0,
                 longitude: 181.0,
                 auto: false,
@@ -357,9 +604,7 @@ longitude = 0.0
                 display: LocationDisplay::default(),
                 city_name_language: "auto".to_string(),
             },
-            hide_hud: false,
-            units: WeatherUnits::default(),
-            silent: false,
+            ..Config::default()
         };
         let result = config.validate();
         assert!(result.is_err());
@@ -370,6 +615,7 @@ longitude = 0.0
     fn test_validation_invalid_longitude_low() {
         let config = Config {
             location: Location {
+                name: None,
                 latitude: 0.0,
                 longitude: -181.0,
                 auto: false,
@@ -378,9 +624,7 @@ longitude = 0.0
                 display: LocationDisplay::default(),
                 city_name_language: "auto".to_string(),
             },
-            hide_hud: false,
-            units: WeatherUnits::default(),
-            silent: false,
+            ..Config::default()
         };
         let result = config.validate();
         assert!(result.is_err());
@@ -391,6 +635,7 @@ longitude = 0.0
     fn test_validation_valid_config() {
         let config = Config {
             location: Location {
+                name: None,
                 latitude: 52.52,
                 longitude: 13.41,
                 auto: false,
@@ -399,14 +644,222 @@ longitude = 0.0
                 display: LocationDisplay::default(),
                 city_name_language: "auto".to_string(),
             },
-            hide_hud: false,
-            units: WeatherUnits::default(),
-            silent: false,
+            ..Config::default()
         };
         let result = config.validate();
         assert!(result.is_ok());
     }
 
+    #[test]
+    fn test_validation_invalid_pane_location() {
+        let toml_content = r#"
+[[locations]]
+latitude = 52.52
+longitude = 13.41
+
+[[locations]]
+latitude = 95.0
+longitude = 0.0
+"#;
+        let config: Config = toml::from_str(toml_content).unwrap();
+        let result = config.validate();
+        assert_eq!(result.unwrap_err().kind(), "InvalidLatitude");
+    }
+
+    #[test]
+    fn test_config_keys_table() {
+        let toml_content = r#"
+[keys]
+refresh = "ctrl+r"
+quit = ["q", "F10"]
+"#;
+        let config: Config = toml::from_str(toml_content).unwrap();
+        assert_eq!(config.keys["refresh"], KeySpec::One("ctrl+r".to_string()));
+        assert!(config.validate().is_ok());
+    }
+
+    #[test]
+    fn test_readme_keys_example() {
+        let readme = include_str!("../README.md");
+        let start = readme.find("```toml\n[keys]\n").unwrap() + "```toml\n".len();
+        let end = start + readme[start..].find("```").unwrap();
+        let config: Config = toml::from_str(&readme[start..end]).unwrap();
+        assert!(config.validate().is_ok());
+    }
+
+    #[test]
+    fn test_validation_key_conflict() {
+        let toml_content = r#"
+[keys]
+toggle_hud = "q"
+"#;
+        let config: Config = toml::from_str(toml_content).unwrap();
+        let result = config.validate();
+        assert_eq!(result.unwrap_err().kind(), "KeyConflict");
+    }
+
+    #[test]
+    fn test_config_hud_table() {
+        let toml_content = r##"
+[hud]
+format = "{condition} {temp}[ | {location}]"
+position = "bottom"
+align = "right"
+color = "#ffaa00"
+"##;
+        let config: Config = toml::from_str(toml_content).unwrap();
+        let style = config.hud_style().unwrap();
+        assert_eq!(style.position, HudPosition::Bottom);
+        assert_eq!(style.align, HudAlign::Right);
+        assert!(config.validate().is_ok());
+    }
+
+    #[test]
+    fn test_validation_invalid_hud_format() {
+        let toml_content = r#"
+[hud]
+format = "{temperature}"
+"#;
+        let config: Config = toml::from_str(toml_content).unwrap();
+        let result = config.validate();
+        assert_eq!(result.unwrap_err().kind(), "InvalidHud");
+    }
+
+    #[test]
+    fn test_config_multiple_locations() {
+        let toml_content = r#"
+split = "stacked"
+
+[[locations]]
+latitude = 52.52
+longitude = 13.41
+city = "Berlin"
+
+[[locations]]
+latitude = 35.68
+longitude = 139.65
+city = "Tokyo"
+"#;
+        let config: Config = toml::from_str(toml_content).unwrap();
+        assert_eq!(config.split, SplitLayout::Stacked);
+        let panes = config.pane_locations();
+        assert_eq!(panes.len(), 2);
+        assert_eq!(panes[1].city.as_deref(), Some("Tokyo"));
+        assert!(!panes[1].auto);
+    }
+
+    #[test]
+    fn test_color_mode() {
+        assert_eq!(Config::default().color_mode, ColorMode::Auto);
+        let config: Config = toml::from_str("color_mode = \"256\"").unwrap();
+        assert_eq!(config.color_mode, ColorMode::Ansi256);
+        let config: Config = toml::from_str("color_mode = \"none\"").unwrap();
+        assert_eq!(config.color_mode, ColorMode::None);
+        assert!(toml::from_str::<Config>("color_mode = \"8\"").is_err());
+    }
+
+    #[test]
+    fn test_theme_overrides() {
+        let toml_content = r##"
+[theme]
+name = "gruvbox"
+roof = "#aa3322"
+flowers = ["red", "yellow", "white", "magenta"]
+
+[theme.night]
+sky = "#101830"
+"##;
+        let config: Config = toml::from_str(toml_content).unwrap();
+        assert_eq!(config.theme.name, ThemeName::Gruvbox);
+        let theme = config.theme().unwrap();
+        let roof = Color::Rgb {
+            r: 0xaa,
+            g: 0x33,
+            b: 0x22,
+        };
+        assert_eq!(theme.day.roof, roof);
+        assert_eq!(theme.night.roof, roof);
+        assert_eq!(theme.day.flowers[2], Color::White);
+        assert_eq!(theme.day.sky, ThemeName::Gruvbox.build().day.sky);
+        assert_eq!(
+            theme.night.sky,
+            Color::Rgb {
+                r: 0x10,
+                g: 0x18,
+                b: 0x30
+            }
+        );
+        assert!(config.validate().is_ok());
+    }
+
+    #[test]
+    fn test_theme_default() {
+        let config = Config::default();
+        assert_eq!(config.theme.name, ThemeName::Default);
+        assert_eq!(config.theme().unwrap(), Theme::default());
+    }
+
+    #[test]
+    fn test_validation_invalid_theme() {
+        for toml_content in [
+            "[theme]\ncastle = \"red\"",
+            "[theme]\nroof = \"purplish\"",
+            "[theme]\nroof = \"#aébcd\"",
+            "[theme]\nflowers = [\"red\"]",
+            "[theme.day]\nroof = [\"red\", \"blue\"]",
+        ] {
+            let config: Config = toml::from_str(toml_content).unwrap();
+            let result = config.validate();
+            assert_eq!(result.unwrap_err().kind(), "InvalidTheme", "{toml_content}");
+        }
+        assert!(toml::from_str::<Config>("[theme]\nname = \"neon\"").is_err());
+    }
+
+    #[test]
+    fn test_power_mode() {
+        assert_eq!(Config::default().power_mode, PowerMode::Normal);
+        let config: Config = toml::from_str("power_mode = \"eco\"").unwrap();
+        assert_eq!(config.power_mode, PowerMode::Eco);
+        assert!(toml::from_str::<Config>("power_mode = \"turbo\"").is_err());
+    }
+
+    #[test]
+    fn test_cycle_locations_start_with_primary() {
+        let toml_content = r#"
+[location]
+latitude = 52.52
+longitude = 13.41
+
+[[saved_locations]]
+name = "Home"
+latitude = 48.85
+longitude = 2.35
+
+[[saved_locations]]
+name = "Office"
+latitude = 51.51
+longitude = -0.13
+"#;
+        let config: Config = toml::from_str(toml_content).unwrap();
+        let names: Vec<_> = config
+            .cycle_locations()
+            .iter()
+            .map(|l| l.name.clone())
+            .collect();
+        assert_eq!(
+            names,
+            vec![None, Some("Home".to_string()), Some("Office".to_string())]
+        );
+    }
+
+    #[test]
+    fn test_pane_locations_fall_back_to_location() {
+        let config = Config::default();
+        assert_eq!(config.split, SplitLayout::Auto);
+        assert_eq!(config.pane_locations().len(), 1);
+        assert!(config.pane_locations()[0].auto);
+    }
+
     #[test]
     fn test_config_units_default() {
         let toml_content = r#"
@@ -456,6 +909,24 @@ precipitation = "inch"
         );
     }
 
+    #[test]
+    fn test_scene_default() {
+        let config: Config = toml::from_str("").unwrap();
+        assert_eq!(config.scene, SceneTheme::House);
+    }
+
+    #[test]
+    fn test_scene_city() {
+        let config: Config = toml::from_str(r#"scene = "city""#).unwrap();
+        assert_eq!(config.scene, SceneTheme::City);
+    }
+
+    #[test]
+    fn test_scene_mountain_alias() {
+        let config: Config = toml::from_str(r#"scene = "mountain""#).unwrap();
+        assert_eq!(config.scene, SceneTheme::Cabin);
+    }
+
     #[test]
     fn test_location_display_default() {
         let toml_content = r#"
@@ -621,6 +1092,33 @@ city_name_language = "ru"
         }
     }
 
+    #[test]
+    fn test_env_var_overrides_first_pane_location() {
+        let _guard = ENV_MUTEX.lock().unwrap();
+        unsafe {
+            env::set_var("WEATHR_LATITUDE", "35.6762");
+            env::set_var("WEATHR_LONGITUDE", "139.6503");
+        }
+        let toml_content = r#"
+[[locations]]
+latitude = 52.52
+longitude = 13.41
+
+[[locations]]
+latitude = 48.85
+longitude = 2.35
+"#;
+        let mut config: Config = toml::from_str(toml_content).unwrap();
+        config.apply_env_overrides().unwrap();
+        let panes = config.pane_locations();
+        assert_eq!((panes[0].latitude, panes[0].longitude), (35.6762, 139.6503));
+        assert_eq!((panes[1].latitude, panes[1].longitude), (48.85, 2.35));
+        unsafe {
+            env::remove_var("WEATHR_LATITUDE");
+            env::remove_var("WEATHR_LONGITUDE");
+        }
+    }
+
     #[test]
     fn test_env_var_invalid_latitude() {
         let _guard = ENV_MUTEX.lock().unwrap();
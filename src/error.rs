@@ -16,6 +16,28 @@ pub enum WeatherError {
     Geolocation(#[from] GeolocationError),
 }
 
+impl WeatherError {
+    /// Process exit status for this error, following the BSD sysexits.h
+    /// conventions so scripts can tell failures apart.
+    pub fn exit_code(&self) -> i32 {
+        match self {
+            // EX_TEMPFAIL: worth retrying later
+            WeatherError::Network(NetworkError::Timeout { .. })
+            | WeatherError::Network(NetworkError::ConnectionRefused { .. }) => 75,
+            // EX_NOHOST
+            WeatherError::Network(NetworkError::DnsFailure { .. }) => 68,
+            // EX_DATAERR
+            WeatherError::Network(NetworkError::JsonParse { .. }) => 65,
+            // EX_UNAVAILABLE
+            WeatherError::Network(_) | WeatherError::Geolocation(_) => 69,
+            // EX_CONFIG
+            WeatherError::Config(_) => 78,
+            // EX_IOERR
+            WeatherError::Terminal(_) => 74,
+        }
+    }
+}
+
 #[derive(ThisError, Debug)]
 pub enum NetworkError {
     #[error("failed to create HTTP client: {0}")]
@@ -150,6 +172,32 @@ pub enum ConfigError {
 
     #[error("invalid value for ${name} (expected a float, got {value:?})")]
     InvalidEnvVar { name: &'static str, value: String },
+
+    #[error("invalid scene file {path}:{line}: {message}")]
+    InvalidScene {
+        path: String,
+        line: usize,
+        message: String,
+    },
+
+    #[error("key {key:?} is bound to both {first} and {second}")]
+    KeyConflict {
+        key: String,
+        first: String,
+        second: String,
+    },
+
+    #[error("invalid key binding for {action:?}: {message}")]
+    InvalidKeyBinding { action: String, message: String },
+
+    #[error("invalid hud.{field}: {message}")]
+    InvalidHud {
+        field: &'static str,
+        message: String,
+    },
+
+    #[error("invalid theme.{role}: {message}")]
+    InvalidTheme { role: String, message: String },
 }
 
 impl ConfigError {
@@ -162,6 +210,11 @@ impl ConfigError {
             ConfigError::InvalidLatitude(_) => "InvalidLatitude",
             ConfigError::InvalidLongitude(_) => "InvalidLongitude",
             ConfigError::InvalidEnvVar { .. } => "InvalidEnvVar",
+            ConfigError::InvalidScene { .. } => "InvalidScene",
+            ConfigError::KeyConflict { .. } => "KeyConflict",
+            ConfigError::InvalidKeyBinding { .. } => "InvalidKeyBinding",
+            ConfigError::InvalidHud { .. } => "InvalidHud",
+            ConfigError::InvalidTheme { .. } => "InvalidTheme",
         }
     }
 }
@@ -0,0 +1,281 @@
+use crossterm::style::Color;
+use serde::Deserialize;
+
+/// The HUD text used when `hud.format` is not set.
+pub const DEFAULT_FORMAT: &str = "[{offline} | ]Weather: {condition} | Temp: {temp} | Wind: {wind} | Precip: {precip}[ | Press '{quit_key}' to quit][ | Location: {location}]";
+
+/// A value that can be placed in the HUD with `{name}`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum HudField {
+    Condition,
+    Temp,
+    FeelsLike,
+    Wind,
+    WindDir,
+    Humidity,
+    Pressure,
+    Precip,
+    Clouds,
+    Visibility,
+    Location,
+    Updated,
+    Offline,
+    /// The first key bound to quit, as the user configured it.
+    QuitKey,
+}
+
+impl HudField {
+    fn from_name(name: &str) -> Option<Self> {
+        let field = match name {
+            "condition" => Self::Condition,
+            "temp" => Self::Temp,
+            "feels_like" => Self::FeelsLike,
+            "wind" => Self::Wind,
+            "wind_dir" => Self::WindDir,
+            "humidity" => Self::Humidity,
+            "pressure" => Self::Pressure,
+            "precip" => Self::Precip,
+            "clouds" => Self::Clouds,
+            "visibility" => Self::Visibility,
+            "location" => Self::Location,
+            "updated" => Self::Updated,
+            "offline" => Self::Offline,
+            "quit_key" => Self::QuitKey,
+            _ => return None,
+        };
+        Some(field)
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+enum Segment {
+    Text(String),
+    Field(HudField),
+    /// Shown only when every field inside it has a value.
+    Section(Vec<Segment>),
+}
+
+/// A parsed `hud.format` string. `{name}` inserts a value, text in `[...]`
+/// is dropped when any value inside it is missing, and `{{`, `}}`, `[[`, `]]`
+/// produce literal brackets.
+#[derive(Debug, Clone, PartialEq)]
+pub struct HudTemplate {
+    segments: Vec<Segment>,
+}
+
+impl HudTemplate {
+    pub fn parse(format: &str) -> Result<Self, String> {
+        let mut segments = Vec::new();
+        let mut section: Option<Vec<Segment>> = None;
+        let mut text = String::new();
+        let mut chars = format.chars().peekable();
+
+        while let Some(ch) = chars.next() {
+            match ch {
+                '{' | '}' | '[' | ']' if chars.peek() == Some(&ch) => {
+                    chars.next();
+                    text.push(ch);
+                }
+                '{' => {
+                    let mut name = String::new();
+                    loop {
+                        match chars.next() {
+                            Some('}') => break,
+                            Some(c) => name.push(c),
+                            None => return Err(format!("unclosed placeholder {{{name}")),
+                        }
+                    }
+                    let field = HudField::from_name(name.trim())
+                        .ok_or_else(|| format!("unknown placeholder {{{name}}}"))?;
+                    let current = section.as_mut().unwrap_or(&mut segments);
+                    flush_text(&mut text, current);
+                    current.push(Segment::Field(field));
+                }
+                '[' => {
+                    if section.is_some() {
+                        return Err("sections cannot be nested".to_string());
+                    }
+                    flush_text(&mut text, &mut segments);
+                    section = Some(Vec::new());
+                }
+                ']' => {
+                    let Some(mut parts) = section.take() else {
+                        return Err("unmatched ]".to_string());
+                    };
+                    flush_text(&mut text, &mut parts);
+                    segments.push(Segment::Section(parts));
+                }
+                '}' => return Err("unmatched }".to_string()),
+                _ => text.push(ch),
+            }
+        }
+
+        if section.is_some() {
+            return Err("unclosed [".to_string());
+        }
+        flush_text(&mut text, &mut segments);
+        Ok(Self { segments })
+    }
+
+    pub fn uses(&self, field: HudField) -> bool {
+        fn contains(segments: &[Segment], field: HudField) -> bool {
+            segments.iter().any(|segment| match segment {
+                Segment::Field(f) => *f == field,
+                Segment::Section(parts) => contains(parts, field),
+                Segment::Text(_) => false,
+            })
+        }
+        contains(&self.segments, field)
+    }
+
+    /// Fill in the template. `value` returns `None` (or an empty string) for
+    /// fields that are unavailable.
+    pub fn render(&self, value: impl Fn(HudField) -> Option<String>) -> String {
+        let lookup = |field| value(field).filter(|v| !v.is_empty());
+        let mut out = String::new();
+        for segment in &self.segments {
+            match segment {
+                Segment::Text(text) => out.push_str(text),
+                Segment::Field(field) => out.push_str(&lookup(*field).unwrap_or_default()),
+                Segment::Section(parts) => {
+                    let mut section = String::new();
+                    let complete = parts.iter().all(|part| match part {
+                        Segment::Text(text) => {
+                            section.push_str(text);
+                            true
+                        }
+                        Segment::Field(field) => {
+                            lookup(*field).map(|v| section.push_str(&v)).is_some()
+                        }
+                        Segment::Section(_) => false,
+                    });
+                    if complete {
+                        out.push_str(&section);
+                    }
+                }
+            }
+        }
+        out
+    }
+}
+
+impl Default for HudTemplate {
+    fn default() -> Self {
+        Self::parse(DEFAULT_FORMAT).expect("default HUD format is valid")
+    }
+}
+
+fn flush_text(text: &mut String, segments: &mut Vec<Segment>) {
+    if !text.is_empty() {
+        segments.push(Segment::Text(std::mem::take(text)));
+    }
+}
+
+#[derive(Deserialize, Debug, Default, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum HudPosition {
+    #[default]
+    Top,
+    Bottom,
+}
+
+#[derive(Deserialize, Debug, Default, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum HudAlign {
+    #[default]
+    Left,
+    Center,
+    Right,
+}
+
+/// Where and in which color the HUD line is drawn.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct HudStyle {
+    pub position: HudPosition,
+    pub align: HudAlign,
+    /// `None` uses the theme's `hud` color.
+    pub color: Option<Color>,
+}
+
+impl HudStyle {
+    const MARGIN: u16 = 2;
+
+    /// Top-left cell of a HUD line `text_width` wide in a `width`×`height` area.
+    pub fn origin(&self, text_width: u16, width: u16, height: u16) -> (u16, u16) {
+        let y = match self.position {
+            HudPosition::Top => 1,
+            HudPosition::Bottom => height.saturating_sub(2),
+        };
+        let x = match self.align {
+            HudAlign::Left => Self::MARGIN,
+            HudAlign::Center => width.saturating_sub(text_width) / 2,
+            HudAlign::Right => width
+                .saturating_sub(text_width + Self::MARGIN)
+                .max(Self::MARGIN),
+        };
+        (x, y)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn values(field: HudField) -> Option<String> {
+        match field {
+            HudField::Condition => Some("Rain".to_string()),
+            HudField::Temp => Some("12.0°C".to_string()),
+            HudField::Offline => Some(String::new()),
+            _ => None,
+        }
+    }
+
+    #[test]
+    fn test_placeholders_and_sections() {
+        let template = HudTemplate::parse("{condition} {temp}[ in {location}][ ({temp})]").unwrap();
+        assert_eq!(template.render(values), "Rain 12.0°C (12.0°C)");
+    }
+
+    #[test]
+    fn test_empty_value_hides_section() {
+        let template = HudTemplate::parse("[{offline} | ]{condition}").unwrap();
+        assert_eq!(template.render(values), "Rain");
+    }
+
+    #[test]
+    fn test_escaped_brackets() {
+        let template = HudTemplate::parse("[[{condition}]] {{x}}").unwrap();
+        assert_eq!(template.render(values), "[Rain] {x}");
+    }
+
+    #[test]
+    fn test_parse_errors() {
+        assert!(HudTemplate::parse("{nope}").is_err());
+        assert!(HudTemplate::parse("{temp").is_err());
+        assert!(HudTemplate::parse("[a [b]]").is_err());
+        assert!(HudTemplate::parse("a]").is_err());
+        assert!(HudTemplate::parse("[a").is_err());
+    }
+
+    #[test]
+    fn test_default_format_uses_location() {
+        assert!(HudTemplate::default().uses(HudField::Location));
+        assert!(!HudTemplate::default().uses(HudField::Updated));
+    }
+
+    #[test]
+    fn test_origin_alignment() {
+        let style = HudStyle {
+            position: HudPosition::Bottom,
+            align: HudAlign::Right,
+            color: Some(Color::Cyan),
+        };
+        assert_eq!(style.origin(10, 100, 30), (88, 28));
+
+        let centered = HudStyle {
+            align: HudAlign::Center,
+            ..HudStyle::default()
+        };
+        assert_eq!(centered.origin(10, 100, 30), (45, 1));
+    }
+}
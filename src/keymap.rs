@@ -0,0 +1,603 @@
+use crate::error::ConfigError;
+use crossterm::event::{KeyCode, KeyEvent, KeyEventKind, KeyModifiers};
+use serde::Deserialize;
+use std::collections::{BTreeMap, HashMap};
+use std::fmt;
+
+/// Something the user can trigger from the keyboard.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Action {
+    Quit,
+    Refresh,
+    Pause,
+    SpeedUp,
+    SlowDown,
+    ToggleHud,
+    ToggleDetails,
+    ToggleLocations,
+    ToggleHelp,
+    ToggleDebug,
+    CloseOverlay,
+    NextLocation,
+    PreviousLocation,
+    /// Jump to a saved location, counting from 0.
+    SelectLocation(usize),
+}
+
+impl Action {
+    /// Actions in the order they are listed in the help overlay.
+    const ALL: [Action; 21] = [
+        Action::Quit,
+        Action::Refresh,
+        Action::Pause,
+        Action::SpeedUp,
+        Action::SlowDown,
+        Action::ToggleHud,
+        Action::ToggleDetails,
+        Action::ToggleLocations,
+        Action::NextLocation,
+        Action::PreviousLocation,
+        Action::SelectLocation(0),
+        Action::SelectLocation(1),
+        Action::SelectLocation(2),
+        Action::SelectLocation(3),
+        Action::SelectLocation(4),
+        Action::SelectLocation(5),
+        Action::SelectLocation(6),
+        Action::SelectLocation(7),
+        Action::SelectLocation(8),
+        Action::ToggleDebug,
+        Action::ToggleHelp,
+    ];
+
+    /// The name used for this action in the `[keys]` config table.
+    pub fn name(self) -> String {
+        match self {
+            Action::Quit => "quit".to_string(),
+            Action::Refresh => "refresh".to_string(),
+            Action::Pause => "pause".to_string(),
+            Action::SpeedUp => "speed_up".to_string(),
+            Action::SlowDown => "slow_down".to_string(),
+            Action::ToggleHud => "toggle_hud".to_string(),
+            Action::ToggleDetails => "details".to_string(),
+            Action::ToggleLocations => "locations".to_string(),
+            Action::ToggleHelp => "help".to_string(),
+            Action::ToggleDebug => "debug".to_string(),
+            Action::CloseOverlay => "close".to_string(),
+            Action::NextLocation => "next_location".to_string(),
+            Action::PreviousLocation => "previous_location".to_string(),
+            Action::SelectLocation(index) => format!("location_{}", index + 1),
+        }
+    }
+
+    pub fn from_name(name: &str) -> Option<Self> {
+        if let Some(number) = name.strip_prefix("location_") {
+            return match number.parse::<usize>() {
+                Ok(n @ 1..=9) => Some(Action::SelectLocation(n - 1)),
+                _ => None,
+            };
+        }
+        std::iter::once(Action::CloseOverlay)
+            .chain(Action::ALL)
+            .find(|action| action.name() == name)
+    }
+
+    pub fn description(self) -> &'static str {
+        match self {
+            Action::Quit => "Quit",
+            Action::Refresh => "Refresh weather now",
+            Action::Pause => "Pause / resume animations",
+            Action::SpeedUp => "Faster animations",
+            Action::SlowDown => "Slower animations",
+            Action::ToggleHud => "Show / hide the HUD",
+            Action::ToggleDetails => "Show / hide weather details",
+            Action::ToggleLocations => "Show / hide saved locations",
+            Action::ToggleHelp => "Show / hide this help",
+            Action::ToggleDebug => "Show / hide performance stats",
+            Action::CloseOverlay => "Close this window",
+            Action::NextLocation => "Next saved location",
+            Action::PreviousLocation => "Previous saved location",
+            Action::SelectLocation(_) => "Switch saved location",
+        }
+    }
+
+    fn default_keys(self) -> &'static [&'static str] {
+        match self {
+            Action::Quit => &["q", "Q"],
+            Action::Refresh => &["r", "R"],
+            Action::Pause => &["space"],
+            Action::SpeedUp => &["+", "="],
+            Action::SlowDown => &["-", "_"],
+            Action::ToggleHud => &["h", "H"],
+            Action::ToggleDetails => &["i", "I"],
+            Action::ToggleLocations => &["l", "L"],
+            Action::ToggleHelp => &["?"],
+            Action::ToggleDebug => &["f12"],
+            Action::CloseOverlay => &["esc"],
+            Action::NextLocation => &["tab"],
+            Action::PreviousLocation => &["shift+tab"],
+            Action::SelectLocation(0) => &["1"],
+            Action::SelectLocation(1) => &["2"],
+            Action::SelectLocation(2) => &["3"],
+            Action::SelectLocation(3) => &["4"],
+            Action::SelectLocation(4) => &["5"],
+            Action::SelectLocation(5) => &["6"],
+            Action::SelectLocation(6) => &["7"],
+            Action::SelectLocation(7) => &["8"],
+            Action::SelectLocation(8) => &["9"],
+            Action::SelectLocation(_) => &[],
+        }
+    }
+}
+
+/// A key together with the modifiers that must be held, parsed from
+/// descriptors such as `"ctrl+r"`, `"F5"` or `"shift+tab"`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct KeyBinding {
+    code: KeyCode,
+    modifiers: KeyModifiers,
+}
+
+impl KeyBinding {
+    pub fn parse(descriptor: &str) -> Result<Self, String> {
+        let descriptor = descriptor.trim();
+        if descriptor.is_empty() {
+            return Err("empty key".to_string());
+        }
+
+        // "+" on its own (or as the last part of "ctrl++") is the plus key
+        let (prefix, key) = match descriptor.strip_suffix("++") {
+            Some(prefix) => (Some(prefix), "+"),
+            None if descriptor == "+" => (None, "+"),
+            None => match descriptor.rsplit_once('+') {
+                Some((prefix, key)) => (Some(prefix), key),
+                None => (None, descriptor),
+            },
+        };
+
+        let mut modifiers = KeyModifiers::NONE;
+        for modifier in prefix.into_iter().flat_map(|p| p.split('+')) {
+            modifiers |= match modifier.to_ascii_lowercase().as_str() {
+                "ctrl" | "control" => KeyModifiers::CONTROL,
+                "alt" | "meta" => KeyModifiers::ALT,
+                "shift" => KeyModifiers::SHIFT,
+                other => return Err(format!("unknown modifier {other:?}")),
+            };
+        }
+
+        let mut chars = key.chars();
+        let code = match (chars.next(), chars.next()) {
+            (Some(c), None) => KeyCode::Char(c),
+            _ => Self::parse_named(key)?,
+        };
+
+        Ok(Self::new(code, modifiers))
+    }
+
+    fn parse_named(key: &str) -> Result<KeyCode, String> {
+        let lower = key.to_ascii_lowercase();
+        let code = match lower.as_str() {
+            "space" => KeyCode::Char(' '),
+            "tab" => KeyCode::Tab,
+            "backtab" => KeyCode::BackTab,
+            "esc" | "escape" => KeyCode::Esc,
+            "enter" | "return" => KeyCode::Enter,
+            "backspace" => KeyCode::Backspace,
+            "delete" | "del" => KeyCode::Delete,
+            "insert" | "ins" => KeyCode::Insert,
+            "up" => KeyCode::Up,
+            "down" => KeyCode::Down,
+            "left" => KeyCode::Left,
+            "right" => KeyCode::Right,
+            "home" => KeyCode::Home,
+            "end" => KeyCode::End,
+            "pageup" => KeyCode::PageUp,
+            "pagedown" => KeyCode::PageDown,
+            _ => match lower.strip_prefix('f').map(str::parse::<u8>) {
+                Some(Ok(n @ 1..=24)) => KeyCode::F(n),
+                _ => return Err(format!("unknown key {key:?}")),
+            },
+        };
+        Ok(code)
+    }
+
+    /// Normalise so that bindings compare equal to the events terminals send.
+    fn new(code: KeyCode, mut modifiers: KeyModifiers) -> Self {
+        let code = match code {
+            KeyCode::Tab if modifiers.contains(KeyModifiers::SHIFT) => KeyCode::BackTab,
+            code => code,
+        };
+        // Shift is already part of the character (or implied by BackTab),
+        // and terminals disagree on whether they report it.
+        if matches!(code, KeyCode::Char(_) | KeyCode::BackTab) {
+            modifiers.remove(KeyModifiers::SHIFT);
+        }
+        Self { code, modifiers }
+    }
+
+    pub fn from_event(event: &KeyEvent) -> Self {
+        let relevant = KeyModifiers::CONTROL | KeyModifiers::ALT | KeyModifiers::SHIFT;
+        Self::new(event.code, event.modifiers & relevant)
+    }
+}
+
+impl fmt::Display for KeyBinding {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.modifiers.contains(KeyModifiers::CONTROL) {
+            write!(f, "Ctrl+")?;
+        }
+        if self.modifiers.contains(KeyModifiers::ALT) {
+            write!(f, "Alt+")?;
+        }
+        if self.modifiers.contains(KeyModifiers::SHIFT) {
+            write!(f, "Shift+")?;
+        }
+        match self.code {
+            KeyCode::Char(' ') => write!(f, "Space"),
+            KeyCode::Char(c) => write!(f, "{c}"),
+            KeyCode::BackTab => write!(f, "Shift+Tab"),
+            KeyCode::F(n) => write!(f, "F{n}"),
+            KeyCode::PageUp => write!(f, "PageUp"),
+            KeyCode::PageDown => write!(f, "PageDown"),
+            code => write!(f, "{code:?}"),
+        }
+    }
+}
+
+/// One descriptor or a list of them, as written in the `[keys]` table.
+#[derive(Deserialize, Debug, Clone, PartialEq)]
+#[serde(untagged)]
+pub enum KeySpec {
+    One(String),
+    Many(Vec<String>),
+}
+
+impl KeySpec {
+    fn descriptors(&self) -> &[String] {
+        match self {
+            KeySpec::One(key) => std::slice::from_ref(key),
+            KeySpec::Many(keys) => keys,
+        }
+    }
+}
+
+/// Which action each key triggers.
+#[derive(Debug, Clone)]
+pub struct Keymap {
+    bindings: HashMap<KeyBinding, Action>,
+    by_action: Vec<(Action, Vec<KeyBinding>)>,
+}
+
+impl Keymap {
+    /// Start from the default bindings and replace those of every action
+    /// named in `overrides`. Fails if a name or key is not recognised, or if
+    /// two actions end up sharing a key.
+    pub fn from_config(overrides: &BTreeMap<String, KeySpec>) -> Result<Self, ConfigError> {
+        let mut by_action: Vec<(Action, Vec<KeyBinding>)> = Action::ALL
+            .into_iter()
+            .chain(std::iter::once(Action::CloseOverlay))
+            .map(|action| {
+                let keys = action
+                    .default_keys()
+                    .iter()
+                    .map(|key| KeyBinding::parse(key).expect("default key bindings are valid"))
+                    .collect();
+                (action, keys)
+            })
+            .collect();
+
+        for (name, spec) in overrides {
+            let action = Action::from_name(name).ok_or_else(|| ConfigError::InvalidKeyBinding {
+                action: name.clone(),
+                message: "unknown action".to_string(),
+            })?;
+            let keys = spec
+                .descriptors()
+                .iter()
+                .map(|key| KeyBinding::parse(key))
+                .collect::<Result<Vec<_>, _>>()
+                .map_err(|message| ConfigError::InvalidKeyBinding {
+                    action: name.clone(),
+                    message,
+                })?;
+            if let Some((_, existing)) = by_action.iter_mut().find(|(a, _)| *a == action) {
+                *existing = keys;
+            }
+        }
+
+        let mut bindings = HashMap::new();
+        for (action, keys) in &by_action {
+            for key in keys {
+                if *key == ctrl_c() {
+                    return Err(ConfigError::KeyConflict {
+                        key: key.to_string(),
+                        first: Action::Quit.name(),
+                        second: action.name(),
+                    });
+                }
+                if let Some(other) = bindings.insert(*key, *action)
+                    && other != *action
+                {
+                    return Err(ConfigError::KeyConflict {
+                        key: key.to_string(),
+                        first: other.name(),
+                        second: action.name(),
+                    });
+                }
+            }
+        }
+
+        Ok(Self {
+            bindings,
+            by_action,
+        })
+    }
+
+    /// The action bound to a key press. Ctrl+C always quits. Releases are
+    /// ignored (some platforms report them, and toggles would undo
+    /// themselves); held keys only repeat the speed controls.
+    pub fn action_for(&self, event: &KeyEvent) -> Option<Action> {
+        let key = KeyBinding::from_event(event);
+        let action = if key == ctrl_c() {
+            Action::Quit
+        } else {
+            *self.bindings.get(&key)?
+        };
+        match event.kind {
+            KeyEventKind::Press => Some(action),
+            KeyEventKind::Repeat if matches!(action, Action::SpeedUp | Action::SlowDown) => {
+                Some(action)
+            }
+            _ => None,
+        }
+    }
+
+    /// The name of the first key bound to quit, for hints such as the HUD's.
+    /// Ctrl+C when every other quit key was unbound.
+    pub fn quit_key(&self) -> String {
+        self.by_action
+            .iter()
+            .find(|(action, _)| *action == Action::Quit)
+            .and_then(|(_, keys)| keys.first())
+            .copied()
+            .unwrap_or_else(ctrl_c)
+            .to_string()
+    }
+
+    /// (keys, description) rows for the help overlay. Location shortcuts are
+    /// folded into a single row.
+    pub fn help_entries(&self) -> Vec<(String, &'static str)> {
+        let join = |keys: &[KeyBinding]| {
+            let mut names: Vec<String> = Vec::new();
+            for key in keys {
+                let name = key.to_string();
+                // "q / Q" says nothing more than "q"
+                if !names.iter().any(|n| n.eq_ignore_ascii_case(&name)) {
+                    names.push(name);
+                }
+            }
+            names.join(" / ")
+        };
+
+        let mut entries = Vec::new();
+        let mut location_keys = Vec::new();
+        for (action, keys) in &self.by_action {
+            match action {
+                Action::SelectLocation(_) => location_keys.extend(keys.iter().copied()),
+                Action::CloseOverlay => {}
+                _ if keys.is_empty() => {}
+                _ => entries.push((join(keys), action.description())),
+            }
+        }
+
+        let defaults = (1..=9).map(|n| n.to_string()).collect::<Vec<_>>();
+        let location_names: Vec<String> = location_keys.iter().map(|k| k.to_string()).collect();
+        let location_label = if location_names == defaults {
+            "1-9".to_string()
+        } else {
+            join(&location_keys)
+        };
+        if !location_label.is_empty() {
+            let at = entries
+                .iter()
+                .position(|(_, d)| *d == Action::ToggleHelp.description())
+                .unwrap_or(entries.len());
+            entries.insert(
+                at,
+                (location_label, Action::SelectLocation(0).description()),
+            );
+        }
+
+        entries
+    }
+}
+
+impl Default for Keymap {
+    fn default() -> Self {
+        Self::from_config(&BTreeMap::new()).expect("default key bindings do not conflict")
+    }
+}
+
+fn ctrl_c() -> KeyBinding {
+    KeyBinding::new(KeyCode::Char('c'), KeyModifiers::CONTROL)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn press(code: KeyCode, modifiers: KeyModifiers) -> KeyEvent {
+        KeyEvent::new(code, modifiers)
+    }
+
+    fn overrides(entries: &[(&str, KeySpec)]) -> BTreeMap<String, KeySpec> {
+        entries
+            .iter()
+            .map(|(name, spec)| (name.to_string(), spec.clone()))
+            .collect()
+    }
+
+    #[test]
+    fn test_parse_key_descriptors() {
+        let parse = |s| KeyBinding::parse(s).unwrap();
+        assert_eq!(
+            parse("ctrl+r"),
+            KeyBinding::new(KeyCode::Char('r'), KeyModifiers::CONTROL)
+        );
+        assert_eq!(
+            parse("F5"),
+            KeyBinding::new(KeyCode::F(5), KeyModifiers::NONE)
+        );
+        assert_eq!(
+            parse("space"),
+            KeyBinding::new(KeyCode::Char(' '), KeyModifiers::NONE)
+        );
+        assert_eq!(parse("shift+tab"), parse("backtab"));
+        assert_eq!(
+            parse("ctrl++"),
+            KeyBinding::new(KeyCode::Char('+'), KeyModifiers::CONTROL)
+        );
+        assert_eq!(
+            parse("+"),
+            KeyBinding::new(KeyCode::Char('+'), KeyModifiers::NONE)
+        );
+    }
+
+    #[test]
+    fn test_parse_rejects_unknown_keys() {
+        assert!(KeyBinding::parse("hyper+x").is_err());
+        assert!(KeyBinding::parse("F30").is_err());
+        assert!(KeyBinding::parse("enterr").is_err());
+        assert!(KeyBinding::parse("").is_err());
+    }
+
+    #[test]
+    fn test_default_keymap_matches_events() {
+        let keymap = Keymap::default();
+        let action = |code, modifiers| keymap.action_for(&press(code, modifiers));
+
+        assert_eq!(
+            action(KeyCode::Char('q'), KeyModifiers::NONE),
+            Some(Action::Quit)
+        );
+        assert_eq!(
+            action(KeyCode::Char('c'), KeyModifiers::CONTROL),
+            Some(Action::Quit)
+        );
+        assert_eq!(
+            action(KeyCode::Char('?'), KeyModifiers::SHIFT),
+            Some(Action::ToggleHelp)
+        );
+        assert_eq!(
+            action(KeyCode::BackTab, KeyModifiers::SHIFT),
+            Some(Action::PreviousLocation)
+        );
+        assert_eq!(
+            action(KeyCode::Char('3'), KeyModifiers::NONE),
+            Some(Action::SelectLocation(2))
+        );
+        assert_eq!(action(KeyCode::Char('x'), KeyModifiers::NONE), None);
+    }
+
+    #[test]
+    fn test_only_presses_trigger_actions() {
+        let keymap = Keymap::default();
+        let with_kind = |code, kind| {
+            let mut event = press(code, KeyModifiers::NONE);
+            event.kind = kind;
+            keymap.action_for(&event)
+        };
+
+        assert_eq!(with_kind(KeyCode::Char('h'), KeyEventKind::Release), None);
+        assert_eq!(with_kind(KeyCode::Char(' '), KeyEventKind::Release), None);
+        assert_eq!(with_kind(KeyCode::Char('q'), KeyEventKind::Release), None);
+        assert_eq!(with_kind(KeyCode::Char('h'), KeyEventKind::Repeat), None);
+        assert_eq!(
+            with_kind(KeyCode::Char('+'), KeyEventKind::Repeat),
+            Some(Action::SpeedUp)
+        );
+    }
+
+    #[test]
+    fn test_override_replaces_default_keys() {
+        let keymap = Keymap::from_config(&overrides(&[
+            ("refresh", KeySpec::One("ctrl+r".to_string())),
+            (
+                "quit",
+                KeySpec::Many(vec!["F10".to_string(), "x".to_string()]),
+            ),
+        ]))
+        .unwrap();
+
+        assert_eq!(
+            keymap.action_for(&press(KeyCode::Char('r'), KeyModifiers::CONTROL)),
+            Some(Action::Refresh)
+        );
+        assert_eq!(
+            keymap.action_for(&press(KeyCode::Char('r'), KeyModifiers::NONE)),
+            None
+        );
+        assert_eq!(
+            keymap.action_for(&press(KeyCode::F(10), KeyModifiers::NONE)),
+            Some(Action::Quit)
+        );
+        assert_eq!(
+            keymap.action_for(&press(KeyCode::Char('q'), KeyModifiers::NONE)),
+            None
+        );
+        assert_eq!(keymap.quit_key(), "F10");
+    }
+
+    #[test]
+    fn test_quit_key_falls_back_to_ctrl_c() {
+        assert_eq!(Keymap::default().quit_key(), "q");
+        let keymap =
+            Keymap::from_config(&overrides(&[("quit", KeySpec::Many(Vec::new()))])).unwrap();
+        assert_eq!(keymap.quit_key(), "Ctrl+c");
+    }
+
+    #[test]
+    fn test_conflicting_bindings_are_rejected() {
+        let err = Keymap::from_config(&overrides(&[("pause", KeySpec::One("r".to_string()))]))
+            .unwrap_err();
+        assert_eq!(err.kind(), "KeyConflict");
+
+        let err = Keymap::from_config(&overrides(&[(
+            "toggle_hud",
+            KeySpec::One("ctrl+c".to_string()),
+        )]))
+        .unwrap_err();
+        assert_eq!(err.kind(), "KeyConflict");
+    }
+
+    #[test]
+    fn test_unknown_action_or_key_is_rejected() {
+        let err = Keymap::from_config(&overrides(&[("explode", KeySpec::One("e".to_string()))]))
+            .unwrap_err();
+        assert_eq!(err.kind(), "InvalidKeyBinding");
+
+        let err = Keymap::from_config(&overrides(&[(
+            "refresh",
+            KeySpec::One("ctrl+nope".to_string()),
+        )]))
+        .unwrap_err();
+        assert_eq!(err.kind(), "InvalidKeyBinding");
+    }
+
+    #[test]
+    fn test_help_entries_follow_bindings() {
+        let entries = Keymap::default().help_entries();
+        assert_eq!(entries[0], ("q".to_string(), "Quit"));
+        assert!(entries.contains(&("1-9".to_string(), "Switch saved location")));
+        assert_eq!(entries.last().unwrap().1, "Show / hide this help");
+
+        let keymap =
+            Keymap::from_config(&overrides(&[("refresh", KeySpec::One("F5".to_string()))]))
+                .unwrap();
+        assert!(
+            keymap
+                .help_entries()
+                .contains(&("F5".to_string(), "Refresh weather now"))
+        );
+    }
+}
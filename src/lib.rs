@@ -3,6 +3,9 @@ pub mod cache;
 pub mod config;
 pub mod error;
 pub mod geolocation;
+pub mod hud;
+pub mod keymap;
 pub mod render;
 pub mod scene;
+pub mod theme;
 pub mod weather;
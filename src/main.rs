@@ -1,25 +1,28 @@
-mod animation;
 mod animation_manager;
 mod app;
 mod app_state;
-mod cache;
-mod config;
-mod error;
-mod geolocation;
-mod render;
-mod scene;
-mod weather;
-
-use clap::{CommandFactory, Parser};
+mod oneshot;
+mod output;
+mod overlay;
+mod pane;
+mod profile;
+mod record;
+mod snapshot;
+
+use clap::{CommandFactory, Parser, Subcommand};
 use clap_complete::{Shell, generate};
 use config::Config;
 use crossterm::{
-    cursor, execute,
+    cursor,
+    event::{DisableFocusChange, DisableMouseCapture},
+    execute,
     style::ResetColor,
     terminal::{LeaveAlternateScreen, disable_raw_mode},
 };
 use render::TerminalRenderer;
+use std::path::PathBuf;
 use std::{io, panic};
+use weathr::{animation, config, error, geolocation, hud, keymap, render, scene, theme, weather};
 
 const LONG_VERSION: &str = concat!(
     env!("CARGO_PKG_VERSION"),
@@ -49,6 +52,7 @@ struct Cli {
     #[arg(
         short,
         long,
+        global = true,
         value_name = "CONDITION",
         help = "Simulate weather condition (clear, rain, drizzle, snow, etc.)"
     )]
@@ -57,6 +61,7 @@ struct Cli {
     #[arg(
         short,
         long,
+        global = true,
         help = "Simulate night time (for testing moon, stars, fireflies)"
     )]
     night: bool,
@@ -64,10 +69,14 @@ struct Cli {
     #[arg(short, long, help = "Enable falling autumn leaves")]
     leaves: bool,
 
-    #[arg(long, help = "Auto-detect location via IP (uses ipinfo.io)")]
+    #[arg(
+        long,
+        global = true,
+        help = "Auto-detect location via IP (uses ipinfo.io)"
+    )]
     auto_location: bool,
 
-    #[arg(long, help = "Hide location coordinates in UI")]
+    #[arg(long, global = true, help = "Hide location coordinates in UI")]
     hide_location: bool,
 
     #[arg(long, help = "Hide HUD (status line)")]
@@ -75,6 +84,7 @@ struct Cli {
 
     #[arg(
         long,
+        global = true,
         conflicts_with = "metric",
         help = "Use imperial units (°F, mph, inch)"
     )]
@@ -82,6 +92,7 @@ struct Cli {
 
     #[arg(
         long,
+        global = true,
         conflicts_with = "imperial",
         help = "Use metric units (°C, km/h, mm)"
     )]
@@ -90,16 +101,262 @@ struct Cli {
     #[arg(long, help = "Run silently (suppress non-error output)")]
     silent: bool,
 
+    #[arg(
+        long,
+        value_name = "SCENE",
+        help = "Scene theme (house, city, seaside, cabin, desert)"
+    )]
+    scene: Option<scene::SceneTheme>,
+
+    #[arg(
+        long,
+        value_name = "FILE",
+        help = "Load a custom scene bundle (name in the scenes config dir, or a path)"
+    )]
+    scene_file: Option<String>,
+
     #[arg(long, value_name = "SHELL", value_enum)]
     pub completions: Option<Shell>,
+
+    #[arg(
+        long,
+        help = "Print the current weather once and exit (same as `weathr now`)"
+    )]
+    once: bool,
+
+    #[arg(
+        long,
+        value_name = "TEMPLATE",
+        help = "Template for --once output, using the same placeholders as hud.format"
+    )]
+    format: Option<String>,
+
+    #[arg(
+        long,
+        global = true,
+        value_name = "FORMAT",
+        value_enum,
+        help = "Print the full reading as json, toml or csv instead of a line (implies --once)"
+    )]
+    output: Option<output::OutputFormat>,
+
+    #[arg(
+        long,
+        global = true,
+        value_name = "N",
+        help = "Seed for the animations; the same seed and weather draw the same frames"
+    )]
+    seed: Option<u64>,
+
+    #[arg(
+        long,
+        global = true,
+        value_name = "N",
+        value_parser = clap::value_parser!(u32).range(1..=app::MAX_FPS as i64),
+        help = "Frames per second (default 30); animations keep their speed at any rate"
+    )]
+    fps: Option<u32>,
+
+    #[arg(
+        long,
+        global = true,
+        conflicts_with = "fps",
+        help = "Draw only a couple of frames per second to save battery"
+    )]
+    battery_saver: bool,
+
+    #[arg(
+        long,
+        help = "Show frame rate, frame times and particle counts (toggle with F12)"
+    )]
+    debug: bool,
+
+    #[arg(
+        long,
+        value_name = "MODE",
+        help = "Colors to draw with when detection gets it wrong (auto, truecolor, 256, 16, none)"
+    )]
+    color_mode: Option<render::ColorMode>,
+
+    #[arg(
+        long,
+        value_name = "NAME",
+        help = "Color theme (default, solarized, gruvbox, high-contrast, monochrome)"
+    )]
+    theme: Option<theme::ThemeName>,
+
+    #[arg(long, help = "Print the JSON Schema of the --output document and exit")]
+    schema: bool,
+
+    #[command(subcommand)]
+    command: Option<Command>,
+}
+
+#[derive(Subcommand)]
+enum Command {
+    /// Print the current weather once and exit. Works without a terminal.
+    Now {
+        #[arg(
+            long,
+            value_name = "TEMPLATE",
+            help = "Template for the line, using the same placeholders as hud.format"
+        )]
+        format: Option<String>,
+    },
+    /// Draw the scene off-screen and write the final frame. Works without a terminal.
+    Snapshot {
+        #[arg(long, default_value_t = 100)]
+        width: u16,
+
+        #[arg(long, default_value_t = 30)]
+        height: u16,
+
+        #[arg(long, value_enum, default_value = "ansi")]
+        format: snapshot::SnapshotFormat,
+
+        #[arg(
+            long,
+            default_value_t = 30,
+            help = "Animation frames to run before capturing"
+        )]
+        frames: u32,
+
+        #[arg(long, value_name = "FILE", help = "Write to FILE instead of stdout")]
+        out: Option<PathBuf>,
+    },
+    /// Record the animation as an asciinema v2 cast. Works without a terminal.
+    Record {
+        #[arg(long, default_value_t = 100)]
+        width: u16,
+
+        #[arg(long, default_value_t = 30)]
+        height: u16,
+
+        #[arg(
+            long,
+            default_value = "10s",
+            value_parser = record::parse_duration,
+            help = "Length of the recording, e.g. 10s, 500ms or 1m"
+        )]
+        duration: std::time::Duration,
+
+        #[arg(long, value_name = "FILE", help = "Where to write the .cast file")]
+        out: PathBuf,
+    },
+}
+
+/// Exit status for a bad command line (EX_USAGE).
+const EXIT_USAGE: i32 = 64;
+/// Exit status when an output file can't be written (EX_CANTCREAT).
+const EXIT_CANT_CREATE: i32 = 73;
+/// Largest off-screen render, in cells along either side. Keeps the frame
+/// buffers to a few megabytes.
+const MAX_HEADLESS_SIZE: u16 = 1000;
+
+/// Report an error from a headless command and exit with its status.
+fn exit_with(error: error::WeatherError) -> ! {
+    let message = match &error {
+        error::WeatherError::Network(net_err) => net_err.user_friendly_message(),
+        _ => error.to_string(),
+    };
+    eprintln!("{}", message);
+    std::process::exit(error.exit_code());
+}
+
+/// Exit unless `width`×`height` fits an off-screen render.
+fn check_headless_size(width: u16, height: u16) {
+    if width < render::MIN_TERMINAL_WIDTH || height < render::MIN_TERMINAL_HEIGHT {
+        eprintln!(
+            "Size must be at least {}x{}",
+            render::MIN_TERMINAL_WIDTH,
+            render::MIN_TERMINAL_HEIGHT
+        );
+        std::process::exit(EXIT_USAGE);
+    }
+    if width > MAX_HEADLESS_SIZE || height > MAX_HEADLESS_SIZE {
+        eprintln!(
+            "Size must be at most {}x{}",
+            MAX_HEADLESS_SIZE, MAX_HEADLESS_SIZE
+        );
+        std::process::exit(EXIT_USAGE);
+    }
+}
+
+/// Exit if a global flag was given to a subcommand that ignores it.
+fn check_subcommand_flags(cli: &Cli) {
+    let (name, ignored) = match &cli.command {
+        Some(Command::Now { .. }) => (
+            "now",
+            vec![
+                ("--seed", cli.seed.is_some()),
+                ("--fps", cli.fps.is_some()),
+                ("--battery-saver", cli.battery_saver),
+            ],
+        ),
+        Some(Command::Snapshot { .. }) => ("snapshot", vec![("--output", cli.output.is_some())]),
+        Some(Command::Record { .. }) => ("record", vec![("--output", cli.output.is_some())]),
+        None => return,
+    };
+    if let Some((flag, _)) = ignored.into_iter().find(|&(_, given)| given) {
+        eprintln!("{} can't be used with `weathr {}`", flag, name);
+        std::process::exit(EXIT_USAGE);
+    }
+}
+
+/// The weather for `snapshot` and `record`, exiting if it can't be fetched.
+async fn headless_weather(
+    config: &Config,
+    simulate: Option<&str>,
+    simulate_night: bool,
+) -> weather::WeatherData {
+    match oneshot::fetch(config, simulate, simulate_night).await {
+        Ok(fetched) => fetched.data,
+        Err(e) => exit_with(e),
+    }
+}
+
+/// One scene per pane, from `scene_file` or the built-in `scene`. Custom
+/// bundles are checked against the width of the pane they go in.
+fn build_scenes(config: &Config, term_width: u16, term_height: u16) -> Vec<Box<dyn scene::Scene>> {
+    let locations = config.pane_locations();
+    let rects = pane::pane_rects(locations.len(), term_width, term_height, config.split);
+    let mut scenes: Vec<Box<dyn scene::Scene>> = Vec::new();
+    for rect in rects {
+        let scene: Box<dyn scene::Scene> = match &config.scene_file {
+            Some(name) => {
+                let loaded = Config::resolve_scene_file(name)
+                    .and_then(|path| scene::custom::CustomScene::load(&path, rect.width));
+                match loaded {
+                    Ok(custom) => Box::new(custom),
+                    Err(e) => {
+                        eprintln!("Error loading scene: {}", e);
+                        std::process::exit(1);
+                    }
+                }
+            }
+            None => config.scene.build(),
+        };
+        scenes.push(scene);
+    }
+    scenes
 }
 
 #[tokio::main]
 async fn main() -> io::Result<()> {
     let default_hook = panic::take_hook();
     panic::set_hook(Box::new(move |info| {
-        let _ = disable_raw_mode();
-        let _ = execute!(io::stdout(), LeaveAlternateScreen, cursor::Show, ResetColor);
+        // Headless commands never took over the terminal and may be piped
+        if render::terminal_active() {
+            let _ = disable_raw_mode();
+            let _ = execute!(
+                io::stdout(),
+                DisableMouseCapture,
+                DisableFocusChange,
+                LeaveAlternateScreen,
+                cursor::Show,
+                ResetColor
+            );
+        }
         default_hook(info);
     }));
 
@@ -152,8 +409,31 @@ async fn main() -> io::Result<()> {
         return Ok(());
     }
 
+    if cli.schema {
+        print!("{}", output::SCHEMA);
+        return Ok(());
+    }
+
+    check_subcommand_flags(&cli);
+
+    let once = cli.once || cli.output.is_some() || matches!(cli.command, Some(Command::Now { .. }));
+    let offscreen = matches!(
+        cli.command,
+        Some(Command::Snapshot { .. } | Command::Record { .. })
+    );
+    let headless = once || offscreen;
+    let fps = if cli.battery_saver {
+        app::BATTERY_SAVER_FPS
+    } else {
+        cli.fps.unwrap_or(app::DEFAULT_FPS)
+    };
+
     let mut config = match Config::load() {
         Ok(config) => config,
+        Err(e) if headless => {
+            eprintln!("Error loading config: {}", e);
+            std::process::exit(error::WeatherError::from(e).exit_code());
+        }
         Err(e) => {
             eprintln!("Error loading config: {}", e);
             eprintln!("\nFix or recreate it at:");
@@ -173,11 +453,18 @@ async fn main() -> io::Result<()> {
     };
 
     // CLI Overrides
-    if cli.auto_location {
-        config.location.auto = true;
+    for location in config.pane_locations_mut() {
+        if cli.auto_location {
+            location.auto = true;
+        }
+        if cli.hide_location {
+            location.hide = true;
+        }
     }
     if cli.hide_location {
-        config.location.hide = true;
+        for location in &mut config.saved_locations {
+            location.hide = true;
+        }
     }
     if cli.hide_hud {
         config.hide_hud = true;
@@ -188,23 +475,39 @@ async fn main() -> io::Result<()> {
     if cli.metric {
         config.units = weather::WeatherUnits::metric();
     }
-    if cli.silent {
+    // Progress messages go to stdout, which is reserved for the result
+    if cli.silent || headless {
         config.silent = true;
     }
+    if let Some(scene) = cli.scene {
+        config.scene = scene;
+        config.scene_file = None;
+    }
+    if let Some(scene_file) = cli.scene_file {
+        config.scene_file = Some(scene_file);
+    }
+    if let Some(color_mode) = cli.color_mode {
+        config.color_mode = color_mode;
+    }
+    if let Some(theme) = cli.theme {
+        config.theme.name = theme;
+    }
 
     let lat_from_env = std::env::var(config::ENV_LATITUDE).is_ok();
     let lon_from_env = std::env::var(config::ENV_LONGITUDE).is_ok();
     if lat_from_env || lon_from_env {
+        let location = config.pane_locations()[0];
         info(
             config.silent,
             &format!(
                 "Location overridden via environment: ({:.4}, {:.4})",
-                config.location.latitude, config.location.longitude
+                location.latitude, location.longitude
             ),
         );
     }
 
-    if !config.location.auto
+    if config.locations.is_empty()
+        && !config.location.auto
         && config.location.latitude == config::default_latitude()
         && config.location.longitude == config::default_longitude()
         && !lat_from_env
@@ -213,60 +516,117 @@ async fn main() -> io::Result<()> {
         eprintln!("Warning: No location set, defaulting to Berlin (52.52, 13.41).");
     }
 
-    // Auto-detect location if enabled
-    if config.location.auto {
-        info(config.silent, "Auto-detecting location...");
-        match geolocation::detect_location().await {
-            Ok(geo_loc) => {
-                if let Some(city) = &geo_loc.city {
-                    info(
-                        config.silent,
-                        &format!(
-                            "Location detected: {} ({:.4}, {:.4})",
-                            city, geo_loc.latitude, geo_loc.longitude
-                        ),
-                    );
-                } else {
-                    info(
-                        config.silent,
-                        &format!(
-                            "Location detected: {:.4}, {:.4}",
-                            geo_loc.latitude, geo_loc.longitude
-                        ),
-                    );
+    // Each resolve can mean a geolocation and a geocoding request, so the
+    // headless commands skip what they never show: saved locations are only
+    // reachable by cycling in the TUI, and one-shot output is the first pane
+    let silent = config.silent;
+    let panes = if once && !offscreen { 1 } else { usize::MAX };
+    for location in config.pane_locations_mut().into_iter().take(panes) {
+        resolve_location(location, silent).await;
+    }
+    if !headless {
+        for location in &mut config.saved_locations {
+            resolve_location(location, silent).await;
+        }
+    }
+
+    if let Some(Command::Snapshot {
+        width,
+        height,
+        format,
+        frames,
+        out,
+    }) = &cli.command
+    {
+        check_headless_size(*width, *height);
+        let weather = headless_weather(&config, cli.simulate.as_deref(), cli.night).await;
+        let scenes = build_scenes(&config, *width, *height);
+        let mut app = app::App::new(
+            &config,
+            scenes,
+            Some(weather),
+            cli.seed,
+            cli.leaves,
+            *width,
+            *height,
+        )
+        .with_fps(fps);
+        let picture = snapshot::run(&mut app, (*width, *height), *frames, *format)?;
+        match out {
+            Some(path) => {
+                if let Err(e) = std::fs::write(path, picture) {
+                    eprintln!("Failed to write {}: {}", path.display(), e);
+                    std::process::exit(EXIT_CANT_CREATE);
                 }
-                config.location.latitude = geo_loc.latitude;
-                config.location.longitude = geo_loc.longitude;
-                config.location.city = geo_loc.city;
-            }
-            Err(e) => {
-                eprintln!("{}", e.user_friendly_message());
             }
+            None => print!("{}", picture),
         }
+        return Ok(());
     }
 
-    // Resolve city name via reverse geocoding when needed but not yet known
-    if config.location.city.is_none()
-        && !config.location.hide
-        && matches!(
-            config.location.display,
-            config::LocationDisplay::City | config::LocationDisplay::Mixed
-        )
+    if let Some(Command::Record {
+        width,
+        height,
+        duration,
+        out,
+    }) = &cli.command
     {
-        info(config.silent, "Resolving city name...");
-        if let Some(city) = geolocation::reverse_geocode(
-            config.location.latitude,
-            config.location.longitude,
-            &config.location.city_name_language,
+        check_headless_size(*width, *height);
+        let weather = headless_weather(&config, cli.simulate.as_deref(), cli.night).await;
+        let scenes = build_scenes(&config, *width, *height);
+        let mut app = app::App::new(
+            &config,
+            scenes,
+            Some(weather),
+            cli.seed,
+            cli.leaves,
+            *width,
+            *height,
         )
-        .await
-        {
-            info(config.silent, &format!("City resolved: {}", city));
-            config.location.city = Some(city);
+        .with_fps(fps);
+        let mut file = match std::fs::File::create(out) {
+            Ok(file) => io::BufWriter::new(file),
+            Err(e) => {
+                eprintln!("Failed to write {}: {}", out.display(), e);
+                std::process::exit(EXIT_CANT_CREATE);
+            }
+        };
+        record::run(&mut app, (*width, *height), *duration, &mut file)?;
+        return Ok(());
+    }
+
+    if once {
+        let result = match cli.output {
+            Some(format) => {
+                oneshot::report(&config, format, cli.simulate.as_deref(), cli.night).await
+            }
+            None => {
+                let format = match &cli.command {
+                    Some(Command::Now {
+                        format: Some(format),
+                    }) => format,
+                    _ => cli.format.as_deref().unwrap_or(oneshot::DEFAULT_FORMAT),
+                };
+                let template = match hud::HudTemplate::parse(format) {
+                    Ok(template) => template,
+                    Err(e) => {
+                        eprintln!("Invalid --format: {}", e);
+                        std::process::exit(EXIT_USAGE);
+                    }
+                };
+                oneshot::run(&config, &template, cli.simulate.as_deref(), cli.night).await
+            }
+        };
+        match result {
+            Ok(line) => {
+                println!("{}", line);
+                return Ok(());
+            }
+            Err(e) => exit_with(e),
         }
     }
 
-    let mut renderer = match TerminalRenderer::new() {
+    let mut renderer = match TerminalRenderer::new(config.color_mode) {
         Ok(r) => r,
         Err(e) => {
             eprintln!("\n{}\n", e.user_friendly_message());
@@ -274,6 +634,9 @@ async fn main() -> io::Result<()> {
         }
     };
 
+    let (term_width, term_height) = renderer.get_size();
+    let scenes = build_scenes(&config, term_width, term_height);
+
     if let Err(e) = renderer.init() {
         eprintln!("\n{}\n", e.user_friendly_message());
         std::process::exit(1);
@@ -281,14 +644,21 @@ async fn main() -> io::Result<()> {
 
     let (term_width, term_height) = renderer.get_size();
 
+    let simulated = cli
+        .simulate
+        .as_deref()
+        .map(|condition| app::simulated_weather(condition, cli.night));
     let mut app = app::App::new(
         &config,
-        cli.simulate,
-        cli.night,
+        scenes,
+        simulated,
+        cli.seed,
         cli.leaves,
         term_width,
         term_height,
-    );
+    )
+    .with_fps(fps)
+    .with_debug(cli.debug);
 
     let result = tokio::select! {
         res = app.run(&mut renderer) => res,
@@ -306,3 +676,59 @@ async fn main() -> io::Result<()> {
 
     Ok(())
 }
+
+/// Auto-detect coordinates and resolve the city name for one location,
+/// as configured.
+async fn resolve_location(location: &mut config::Location, silent: bool) {
+    if location.auto {
+        info(silent, "Auto-detecting location...");
+        match geolocation::detect_location().await {
+            Ok(geo_loc) => {
+                if let Some(city) = &geo_loc.city {
+                    info(
+                        silent,
+                        &format!(
+                            "Location detected: {} ({:.4}, {:.4})",
+                            city, geo_loc.latitude, geo_loc.longitude
+                        ),
+                    );
+                } else {
+                    info(
+                        silent,
+                        &format!(
+                            "Location detected: {:.4}, {:.4}",
+                            geo_loc.latitude, geo_loc.longitude
+                        ),
+                    );
+                }
+                location.latitude = geo_loc.latitude;
+                location.longitude = geo_loc.longitude;
+                location.city = geo_loc.city;
+            }
+            Err(e) => {
+                eprintln!("{}", e.user_friendly_message());
+            }
+        }
+    }
+
+    // Resolve city name via reverse geocoding when needed but not yet known
+    if location.city.is_none()
+        && !location.hide
+        && matches!(
+            location.display,
+            config::LocationDisplay::City | config::LocationDisplay::Mixed
+        )
+    {
+        info(silent, "Resolving city name...");
+        if let Some(city) = geolocation::reverse_geocode(
+            location.latitude,
+            location.longitude,
+            &location.city_name_language,
+        )
+        .await
+        {
+            info(silent, &format!("City resolved: {}", city));
+            location.city = Some(city);
+        }
+    }
+}
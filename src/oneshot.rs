@@ -0,0 +1,133 @@
+use crate::app::{REFRESH_INTERVAL, location_label, simulated_weather};
+use crate::app_state::AppState;
+use crate::config::{Config, Location};
+use crate::error::WeatherError;
+use crate::hud::HudTemplate;
+use crate::output::{
+    LocationReport, OutputFormat, Report, SCHEMA_VERSION, SourceReport, WeatherReport,
+};
+use crate::weather::{
+    DataSource, FetchedWeather, OpenMeteoProvider, WeatherClient, WeatherLocation,
+};
+use std::sync::Arc;
+use std::time::Duration;
+
+/// The line printed by `weathr now` when no `--format` is given.
+pub const DEFAULT_FORMAT: &str =
+    "{condition} {temp}[ (feels {feels_like})] | {wind} {wind_dir}[ | {location}]";
+
+/// Fetch the weather for the first configured location once and format it
+/// with `template`. With `simulate` set, no request is made.
+pub async fn run(
+    config: &Config,
+    template: &HudTemplate,
+    simulate: Option<&str>,
+    simulate_night: bool,
+) -> Result<String, WeatherError> {
+    let fetched = fetch(config, simulate, simulate_night).await?;
+    let location_config = config.pane_locations()[0];
+    let mut state = AppState::new(
+        location(location_config),
+        location_label(location_config),
+        location_config.display,
+        location_config.hide,
+        config.units,
+    );
+    state.update_weather(fetched.data);
+    Ok(state.format_weather(template).unwrap_or_default())
+}
+
+/// Like `run`, but prints the full reading as `format`.
+pub async fn report(
+    config: &Config,
+    format: OutputFormat,
+    simulate: Option<&str>,
+    simulate_night: bool,
+) -> Result<String, WeatherError> {
+    let fetched = fetch(config, simulate, simulate_night).await?;
+    let location_config = config.pane_locations()[0];
+    let report = Report {
+        schema_version: SCHEMA_VERSION,
+        source: SourceReport::new(fetched.source, fetched.age),
+        location: (!location_config.hide).then(|| LocationReport {
+            name: location_label(location_config),
+            latitude: location_config.latitude,
+            longitude: location_config.longitude,
+        }),
+        units: config.units,
+        weather: WeatherReport::new(&fetched.data, &config.units),
+    };
+    Ok(report.render(format))
+}
+
+fn location(config: &Location) -> WeatherLocation {
+    WeatherLocation {
+        latitude: config.latitude,
+        longitude: config.longitude,
+        elevation: None,
+    }
+}
+
+/// The weather for the first configured location, or simulated weather.
+pub async fn fetch(
+    config: &Config,
+    simulate: Option<&str>,
+    simulate_night: bool,
+) -> Result<FetchedWeather, WeatherError> {
+    if let Some(condition) = simulate {
+        return Ok(FetchedWeather {
+            data: simulated_weather(condition, simulate_night),
+            source: DataSource::Simulated,
+            age: Duration::ZERO,
+        });
+    }
+
+    let client = WeatherClient::new(Arc::new(OpenMeteoProvider::new()), REFRESH_INTERVAL);
+    client
+        .fetch_current_weather(&location(config.pane_locations()[0]), &config.units)
+        .await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::LocationDisplay;
+
+    #[tokio::test]
+    async fn test_simulated_line() {
+        let mut config = Config::default();
+        config.location.city = Some("Berlin".to_string());
+        config.location.display = LocationDisplay::City;
+        let template = HudTemplate::parse(DEFAULT_FORMAT).unwrap();
+
+        let line = run(&config, &template, Some("rain"), false).await.unwrap();
+        assert_eq!(line, "Rain 20.0°C (feels 19.0°C) | 36.0km/h ↗ SW | Berlin");
+
+        config.location.hide = true;
+        let line = run(&config, &template, Some("rain"), false).await.unwrap();
+        assert!(!line.contains("Berlin"));
+    }
+
+    #[tokio::test]
+    async fn test_simulated_report() {
+        let mut config = Config::default();
+        config.location.city = Some("Berlin".to_string());
+
+        let json = report(&config, OutputFormat::Json, Some("snow"), true)
+            .await
+            .unwrap();
+        let value: serde_json::Value = serde_json::from_str(&json).unwrap();
+        assert_eq!(value["source"]["origin"], "simulated");
+        assert!(value["source"]["provider"].is_null());
+        assert_eq!(value["location"]["name"], "Berlin");
+        assert_eq!(value["weather"]["condition"], "snow");
+        assert_eq!(value["weather"]["is_day"], false);
+
+        config.location.hide = true;
+        let json = report(&config, OutputFormat::Json, Some("snow"), true)
+            .await
+            .unwrap();
+        assert!(!json.contains("Berlin"));
+        assert!(!json.contains("52.52"));
+    }
+}
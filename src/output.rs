@@ -0,0 +1,260 @@
+use crate::weather::{
+    DataSource, WeatherCondition, WeatherData, WeatherUnits, format_precipitation,
+    format_temperature, format_wind_speed,
+};
+use clap::ValueEnum;
+use serde::Serialize;
+use std::time::Duration;
+
+/// Bumped whenever a field is renamed, removed or changes meaning. Adding a
+/// field does not bump it.
+pub const SCHEMA_VERSION: u32 = 1;
+
+/// JSON Schema for the `--output json` document, printed by `--schema`.
+pub const SCHEMA: &str = include_str!("../schema/output.json");
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum OutputFormat {
+    Json,
+    Toml,
+    Csv,
+}
+
+/// Everything `weathr now --output` prints.
+#[derive(Debug, Clone, Serialize)]
+pub struct Report {
+    pub schema_version: u32,
+    pub source: SourceReport,
+    /// `None` when the location is hidden.
+    pub location: Option<LocationReport>,
+    pub units: WeatherUnits,
+    pub weather: WeatherReport,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct SourceReport {
+    /// The weather provider, or `None` for simulated weather.
+    pub provider: Option<&'static str>,
+    pub origin: DataSource,
+    pub cache_age_secs: u64,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct LocationReport {
+    pub name: Option<String>,
+    pub latitude: f64,
+    pub longitude: f64,
+}
+
+/// `WeatherData` with temperature, wind speed and precipitation in the
+/// configured units.
+#[derive(Debug, Clone, Serialize)]
+pub struct WeatherReport {
+    pub condition: WeatherCondition,
+    pub temperature: f64,
+    pub apparent_temperature: f64,
+    pub humidity: f64,
+    pub precipitation: f64,
+    pub wind_speed: f64,
+    pub wind_direction: f64,
+    pub cloud_cover: f64,
+    pub pressure: f64,
+    pub visibility: Option<f64>,
+    pub is_day: bool,
+    pub moon_phase: Option<f64>,
+    pub timestamp: String,
+}
+
+impl WeatherReport {
+    pub fn new(weather: &WeatherData, units: &WeatherUnits) -> Self {
+        Self {
+            condition: weather.condition,
+            temperature: format_temperature(weather.temperature, units.temperature).0,
+            apparent_temperature: format_temperature(
+                weather.apparent_temperature,
+                units.temperature,
+            )
+            .0,
+            humidity: weather.humidity,
+            precipitation: format_precipitation(weather.precipitation, units.precipitation).0,
+            wind_speed: format_wind_speed(weather.wind_speed, units.wind_speed).0,
+            wind_direction: weather.wind_direction,
+            cloud_cover: weather.cloud_cover,
+            pressure: weather.pressure,
+            visibility: weather.visibility,
+            is_day: weather.is_day,
+            moon_phase: weather.moon_phase,
+            timestamp: weather.timestamp.clone(),
+        }
+    }
+}
+
+impl SourceReport {
+    pub fn new(origin: DataSource, age: Duration) -> Self {
+        Self {
+            provider: (origin != DataSource::Simulated).then_some("open-meteo"),
+            origin,
+            cache_age_secs: age.as_secs(),
+        }
+    }
+}
+
+impl Report {
+    pub fn render(&self, format: OutputFormat) -> String {
+        // Plain structs with string keys; serializing them cannot fail
+        match format {
+            OutputFormat::Json => serde_json::to_string_pretty(self).expect("report serializes"),
+            OutputFormat::Toml => toml::to_string(self).expect("report serializes"),
+            OutputFormat::Csv => self.to_csv(),
+        }
+    }
+
+    /// A header line and one record. Missing values are empty fields.
+    fn to_csv(&self) -> String {
+        let location = self.location.as_ref();
+        let weather = &self.weather;
+        let columns: [(&str, String); 23] = [
+            ("schema_version", self.schema_version.to_string()),
+            (
+                "provider",
+                self.source.provider.unwrap_or_default().to_string(),
+            ),
+            ("origin", name(&self.source.origin)),
+            ("cache_age_secs", self.source.cache_age_secs.to_string()),
+            (
+                "location",
+                location.and_then(|l| l.name.clone()).unwrap_or_default(),
+            ),
+            ("latitude", optional(location.map(|l| l.latitude))),
+            ("longitude", optional(location.map(|l| l.longitude))),
+            ("temperature_unit", name(&self.units.temperature)),
+            ("wind_speed_unit", name(&self.units.wind_speed)),
+            ("precipitation_unit", name(&self.units.precipitation)),
+            ("condition", name(&weather.condition)),
+            ("temperature", weather.temperature.to_string()),
+            (
+                "apparent_temperature",
+                weather.apparent_temperature.to_string(),
+            ),
+            ("humidity", weather.humidity.to_string()),
+            ("precipitation", weather.precipitation.to_string()),
+            ("wind_speed", weather.wind_speed.to_string()),
+            ("wind_direction", weather.wind_direction.to_string()),
+            ("cloud_cover", weather.cloud_cover.to_string()),
+            ("pressure", weather.pressure.to_string()),
+            ("visibility", optional(weather.visibility)),
+            ("is_day", weather.is_day.to_string()),
+            ("moon_phase", optional(weather.moon_phase)),
+            ("timestamp", weather.timestamp.clone()),
+        ];
+
+        let header: Vec<&str> = columns.iter().map(|(column, _)| *column).collect();
+        let record: Vec<String> = columns.iter().map(|(_, value)| csv_field(value)).collect();
+        format!("{}\n{}", header.join(","), record.join(","))
+    }
+}
+
+/// The serialized name of a unit or condition, e.g. `partly_cloudy` or `kmh`.
+fn name(value: &impl Serialize) -> String {
+    match serde_json::to_value(value) {
+        Ok(serde_json::Value::String(name)) => name,
+        _ => String::new(),
+    }
+}
+
+fn optional(value: Option<f64>) -> String {
+    value.map(|v| v.to_string()).unwrap_or_default()
+}
+
+/// Quote a CSV field when it contains a separator, quote or line break.
+fn csv_field(value: &str) -> String {
+    if value.contains([',', '"', '\n', '\r']) {
+        format!("\"{}\"", value.replace('"', "\"\""))
+    } else {
+        value.to_string()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::weather::types::TemperatureUnit;
+
+    fn report() -> Report {
+        let weather = WeatherData {
+            condition: WeatherCondition::PartlyCloudy,
+            temperature: 10.0,
+            apparent_temperature: 8.0,
+            humidity: 70.0,
+            precipitation: 0.0,
+            wind_speed: 5.0,
+            wind_direction: 225.0,
+            cloud_cover: 40.0,
+            pressure: 1013.0,
+            visibility: None,
+            is_day: true,
+            moon_phase: Some(0.5),
+            timestamp: "2024-01-01T12:00+01:00".to_string(),
+        };
+        let units = WeatherUnits {
+            temperature: TemperatureUnit::Fahrenheit,
+            ..WeatherUnits::default()
+        };
+        Report {
+            schema_version: SCHEMA_VERSION,
+            source: SourceReport::new(DataSource::DiskCache, Duration::from_secs(42)),
+            location: Some(LocationReport {
+                name: Some("Berlin, \"Mitte\"".to_string()),
+                latitude: 52.52,
+                longitude: 13.41,
+            }),
+            weather: WeatherReport::new(&weather, &units),
+            units,
+        }
+    }
+
+    #[test]
+    fn test_json_uses_configured_units() {
+        let json: serde_json::Value =
+            serde_json::from_str(&report().render(OutputFormat::Json)).unwrap();
+        assert_eq!(json["schema_version"], 1);
+        assert_eq!(json["source"]["origin"], "disk_cache");
+        assert_eq!(json["source"]["cache_age_secs"], 42);
+        assert_eq!(json["units"]["temperature"], "fahrenheit");
+        assert_eq!(json["weather"]["temperature"], 50.0);
+        assert_eq!(json["weather"]["wind_speed"], 18.0);
+        assert_eq!(json["weather"]["condition"], "partly_cloudy");
+        assert!(json["weather"]["visibility"].is_null());
+    }
+
+    #[test]
+    fn test_toml_round_trips() {
+        let text = report().render(OutputFormat::Toml);
+        let value: toml::Table = toml::from_str(&text).unwrap();
+        assert_eq!(value["source"]["provider"].as_str(), Some("open-meteo"));
+        assert_eq!(value["location"]["latitude"].as_float(), Some(52.52));
+    }
+
+    #[test]
+    fn test_csv_escapes_and_hidden_location() {
+        let csv = report().render(OutputFormat::Csv);
+        let (header, record) = csv.split_once('\n').unwrap();
+        assert_eq!(header.split(',').count(), 23);
+        assert!(record.contains(",\"Berlin, \"\"Mitte\"\"\",52.52,13.41,"));
+        assert!(record.contains(",partly_cloudy,50,"));
+
+        let mut hidden = report();
+        hidden.location = None;
+        let csv = hidden.render(OutputFormat::Csv);
+        assert!(csv.contains(",42,,,,fahrenheit,"));
+    }
+
+    #[test]
+    fn test_schema_matches_version() {
+        let schema: serde_json::Value = serde_json::from_str(SCHEMA).unwrap();
+        assert_eq!(
+            schema["properties"]["schema_version"]["const"],
+            SCHEMA_VERSION
+        );
+    }
+}
@@ -0,0 +1,253 @@
+use crate::keymap::Keymap;
+use crate::render::{Rect, Style, TerminalRenderer};
+use crate::theme::Palette;
+use crossterm::style::{Attribute, Color};
+use std::io;
+
+type Line = Vec<(String, Color)>;
+
+/// Width and height of a box with this title and content, at most
+/// `width`×`height`.
+fn box_size(width: u16, height: u16, title: &str, lines: &[Line]) -> (u16, u16) {
+    let content_width = lines
+        .iter()
+        .map(|spans| spans.iter().map(|(text, _)| text.chars().count()).sum())
+        .chain(std::iter::once(title.chars().count() + 2))
+        .max()
+        .unwrap_or(0) as u16;
+
+    (
+        (content_width + 4).min(width),
+        (lines.len() as u16 + 2).min(height),
+    )
+}
+
+/// Where `render_box` puts a box with this title and content in a
+/// `width`×`height` area.
+fn box_rect(width: u16, height: u16, title: &str, lines: &[Line]) -> Rect {
+    let (box_width, box_height) = box_size(width, height, title, lines);
+    Rect::new(
+        (width - box_width) / 2,
+        (height - box_height) / 2,
+        box_width,
+        box_height,
+    )
+}
+
+/// Draw a bordered box centred in the current viewport. Each line is a list of
+/// (text, color) spans so callers can highlight parts of it.
+pub fn render_box(renderer: &mut TerminalRenderer, title: &str, lines: &[Line]) -> io::Result<()> {
+    let (width, height) = renderer.get_size();
+    draw_box(
+        renderer,
+        box_rect(width, height, title, lines),
+        title,
+        lines,
+    )
+}
+
+fn draw_box(
+    renderer: &mut TerminalRenderer,
+    rect: Rect,
+    title: &str,
+    lines: &[Line],
+) -> io::Result<()> {
+    // Anything smaller has no room for the corners
+    if rect.width < 2 || rect.height < 2 {
+        return Ok(());
+    }
+    let palette = renderer.palette();
+    let (border, text_color) = (palette.border, palette.text);
+    let (left, top) = (rect.x, rect.y);
+    let right = left + rect.width - 1;
+    let bottom = top + rect.height - 1;
+
+    for y in top..=bottom {
+        for x in left..=right {
+            let ch = match (x == left || x == right, y == top || y == bottom) {
+                (true, true) => '+',
+                (false, true) => '-',
+                (true, false) => '|',
+                (false, false) => ' ',
+            };
+            renderer.render_char(x, y, ch, border)?;
+        }
+    }
+    let title_style = Style::new(text_color).with(Attribute::Bold);
+    renderer.render_line_colored(left + 2, top, &format!(" {title} "), title_style)?;
+
+    for (row, spans) in lines.iter().enumerate() {
+        let y = top + 1 + row as u16;
+        if y >= bottom {
+            break;
+        }
+        let mut x = left + 2;
+        for (text, color) in spans {
+            let room = right.saturating_sub(x) as usize;
+            let clipped: String = text.chars().take(room).collect();
+            renderer.render_line_colored(x, y, &clipped, *color)?;
+            x += clipped.chars().count() as u16;
+        }
+    }
+
+    Ok(())
+}
+
+pub fn render_help(renderer: &mut TerminalRenderer, keymap: &Keymap) -> io::Result<()> {
+    let palette = renderer.palette();
+    let entries = keymap.help_entries();
+    let key_width = entries
+        .iter()
+        .map(|(key, _)| key.chars().count())
+        .max()
+        .unwrap_or(0);
+    let lines: Vec<Line> = entries
+        .iter()
+        .map(|(key, action)| {
+            vec![
+                (format!("{key:<key_width$}  "), palette.accent),
+                (action.to_string(), palette.text),
+            ]
+        })
+        .collect();
+    render_box(renderer, "Keys", &lines)
+}
+
+/// One aligned `label  value` line per row.
+fn row_lines(rows: &[(&str, String)], palette: &Palette) -> Vec<Line> {
+    let label_width = rows
+        .iter()
+        .map(|(label, _)| label.chars().count())
+        .max()
+        .unwrap_or(0);
+    rows.iter()
+        .map(|(label, value)| {
+            vec![
+                (format!("{label:<label_width$}  "), palette.accent),
+                (value.clone(), palette.text),
+            ]
+        })
+        .collect()
+}
+
+/// The details panel: one aligned `label  value` line per row.
+pub fn render_details(renderer: &mut TerminalRenderer, rows: &[(&str, String)]) -> io::Result<()> {
+    let lines = row_lines(rows, renderer.palette());
+    render_box(renderer, "Weather details", &lines)
+}
+
+/// Frame rate and similar numbers, in a box in the top-right corner so the
+/// scene stays visible.
+pub fn render_debug(renderer: &mut TerminalRenderer, rows: &[(&str, String)]) -> io::Result<()> {
+    let lines = row_lines(rows, renderer.palette());
+    let (width, height) = renderer.get_size();
+    let (box_width, box_height) = box_size(width, height.saturating_sub(1), "Debug", &lines);
+    if box_height < 2 {
+        return Ok(());
+    }
+    let rect = Rect::new(width - box_width, 1, box_width, box_height);
+    draw_box(renderer, rect, "Debug", &lines)
+}
+
+fn location_lines(labels: &[String], active: usize, palette: &Palette) -> Vec<Line> {
+    labels
+        .iter()
+        .enumerate()
+        .map(|(i, label)| {
+            let color = if i == active {
+                palette.accent
+            } else {
+                palette.text
+            };
+            let marker = if i == active { '>' } else { ' ' };
+            vec![(format!("{marker} {}  {label}", i + 1), color)]
+        })
+        .collect()
+}
+
+/// The saved locations, with the one on screen highlighted.
+pub fn render_locations(
+    renderer: &mut TerminalRenderer,
+    labels: &[String],
+    active: usize,
+) -> io::Result<()> {
+    let lines = location_lines(labels, active, renderer.palette());
+    render_box(renderer, "Locations", &lines)
+}
+
+/// Which entry of the location list drawn on a `width`×`height` screen is
+/// at (x, y), if any.
+pub fn location_at(
+    labels: &[String],
+    active: usize,
+    (width, height): (u16, u16),
+    (x, y): (u16, u16),
+) -> Option<usize> {
+    // Colors don't change the layout
+    let lines = location_lines(labels, active, &Palette::default());
+    let rect = box_rect(width, height, "Locations", &lines);
+    let inner = Rect::new(
+        rect.x + 1,
+        rect.y + 1,
+        rect.width.saturating_sub(2),
+        rect.height.saturating_sub(2),
+    );
+    if !inner.contains(x, y) {
+        return None;
+    }
+    Some((y - inner.y) as usize).filter(|&i| i < labels.len())
+}
+
+/// A short notice in the bottom-right corner, e.g. "PAUSED".
+pub fn render_status(renderer: &mut TerminalRenderer, text: &str) -> io::Result<()> {
+    let (width, height) = renderer.get_size();
+    let len = text.chars().count() as u16;
+    let x = width.saturating_sub(len + 2);
+    let color = renderer.palette().accent;
+    renderer.render_line_colored(x, height.saturating_sub(1), text, color)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_location_at_maps_rows_to_entries() {
+        let labels = vec!["Home".to_string(), "Work".to_string(), "Cabin".to_string()];
+        let lines = location_lines(&labels, 0, &Palette::default());
+        let rect = box_rect(80, 24, "Locations", &lines);
+
+        let inside = |row| (rect.x + 2, rect.y + 1 + row);
+        assert_eq!(location_at(&labels, 0, (80, 24), inside(0)), Some(0));
+        assert_eq!(location_at(&labels, 0, (80, 24), inside(2)), Some(2));
+        assert_eq!(location_at(&labels, 0, (80, 24), (rect.x, rect.y)), None);
+        assert_eq!(location_at(&labels, 0, (80, 24), (0, 0)), None);
+    }
+
+    #[test]
+    fn test_box_title_is_bold() {
+        use crate::render::MemoryBackend;
+
+        let mut renderer = TerminalRenderer::headless(80, 24);
+        let lines = vec![vec![("text".to_string(), Color::White)]];
+        let rect = box_rect(80, 24, "Title", &lines);
+        render_box(&mut renderer, "Title", &lines).unwrap();
+        renderer.flush().unwrap();
+
+        let row = renderer.backend::<MemoryBackend>().unwrap().row(rect.y);
+        let title = row[rect.x as usize + 3];
+        assert_eq!(title.character, 'T');
+        assert!(title.attributes.has(Attribute::Bold));
+        assert!(!row[rect.x as usize].attributes.has(Attribute::Bold));
+    }
+
+    #[test]
+    fn test_box_in_empty_area_draws_nothing() {
+        let lines = vec![vec![("text".to_string(), Color::White)]];
+        for (width, height) in [(0, 0), (80, 0), (0, 24), (1, 1)] {
+            let mut renderer = TerminalRenderer::headless(80, 24);
+            renderer.set_viewport(Rect::new(0, 0, width, height));
+            render_box(&mut renderer, "Title", &lines).unwrap();
+        }
+    }
+}
@@ -0,0 +1,442 @@
+use crate::animation_manager::AnimationManager;
+use crate::app_state::AppState;
+use crate::config::{Location, SplitLayout};
+use crate::error::WeatherError;
+use crate::hud::HudStyle;
+use crate::profile::{Profiler, Stage};
+use crate::render::{Rect, Style, TerminalRenderer};
+use crate::scene::WorldScene;
+use crate::theme::Theme;
+use crate::weather::{WeatherClient, WeatherData, WeatherLocation, WeatherUnits};
+use crossterm::style::Color;
+use std::io;
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::sync::{Notify, mpsc, watch};
+
+// Scenes are drawn for roughly 3:1 (in cells) terminals, so auto split
+// prefers whichever arrangement keeps panes closest to that shape.
+const IDEAL_PANE_ASPECT: f32 = 3.0;
+
+type WeatherResult = (WeatherLocation, Result<WeatherData, WeatherError>);
+
+/// A background task fetching weather for one pane. The task follows
+/// whichever location was last sent to it.
+pub struct WeatherFeed {
+    client: WeatherClient,
+    location_tx: watch::Sender<WeatherLocation>,
+    refresh: Arc<Notify>,
+    receiver: mpsc::Receiver<WeatherResult>,
+}
+
+impl WeatherFeed {
+    pub fn spawn(
+        client: WeatherClient,
+        location: WeatherLocation,
+        units: WeatherUnits,
+        refresh_interval: Duration,
+    ) -> Self {
+        let (tx, receiver) = mpsc::channel(1);
+        let (location_tx, mut location_rx) = watch::channel(location);
+        let refresh = Arc::new(Notify::new());
+        let task_client = client.clone();
+        let task_refresh = refresh.clone();
+
+        tokio::spawn(async move {
+            let mut forced = false;
+            loop {
+                let location = *location_rx.borrow_and_update();
+                let result = if forced {
+                    task_client.refresh_weather(&location, &units).await
+                } else {
+                    task_client.get_current_weather(&location, &units).await
+                };
+                if tx.send((location, result)).await.is_err() {
+                    break;
+                }
+                forced = false;
+                tokio::select! {
+                    _ = tokio::time::sleep(refresh_interval) => {}
+                    _ = task_refresh.notified() => forced = true,
+                    changed = location_rx.changed() => {
+                        if changed.is_err() {
+                            break;
+                        }
+                    }
+                }
+            }
+        });
+
+        Self {
+            client,
+            location_tx,
+            refresh,
+            receiver,
+        }
+    }
+
+    /// Fetch again right away, ignoring anything cached.
+    pub fn refresh(&self) {
+        self.refresh.notify_one();
+    }
+}
+
+/// A single location's weather, scene and animations, drawn into its own
+/// rectangle of the terminal.
+pub struct Pane {
+    pub state: AppState,
+    animations: AnimationManager,
+    scene: WorldScene,
+    feed: Option<WeatherFeed>,
+    rect: Rect,
+}
+
+impl Pane {
+    /// `feed` is `None` when the pane shows simulated weather.
+    pub fn new(
+        state: AppState,
+        animations: AnimationManager,
+        scene: WorldScene,
+        feed: Option<WeatherFeed>,
+        rect: Rect,
+    ) -> Self {
+        Self {
+            state,
+            animations,
+            scene,
+            feed,
+            rect,
+        }
+    }
+
+    pub fn rect(&self) -> Rect {
+        self.rect
+    }
+
+    pub fn resize(&mut self, rect: Rect) {
+        self.rect = rect;
+        self.scene.update_size(rect.width, rect.height);
+    }
+
+    /// Whether (x, y) falls on this pane's HUD line as drawn with `hud`.
+    pub fn hud_contains(&self, hud: &HudStyle, x: u16, y: u16) -> bool {
+        let len = self.state.cached_weather_info.chars().count() as u16;
+        let (hud_x, hud_y) = hud.origin(len, self.rect.width, self.rect.height);
+        Rect::new(self.rect.x + hud_x, self.rect.y + hud_y, len, 1).contains(x, y)
+    }
+
+    /// Pass a click at (x, y) on the terminal to the scene. Returns true if
+    /// the scene reacted to it.
+    pub fn click(&mut self, x: u16, y: u16) -> bool {
+        self.rect.contains(x, y) && self.scene.click(x - self.rect.x, y - self.rect.y)
+    }
+
+    pub fn refresh_weather(&self) {
+        if let Some(feed) = &self.feed {
+            feed.refresh();
+        }
+    }
+
+    pub fn animations(&self) -> &AnimationManager {
+        &self.animations
+    }
+
+    pub fn animations_mut(&mut self) -> &mut AnimationManager {
+        &mut self.animations
+    }
+
+    pub fn apply_weather(&mut self, weather: WeatherData) {
+        let rain_intensity = weather.condition.rain_intensity();
+        let snow_intensity = weather.condition.snow_intensity();
+        let fog_intensity = weather.condition.fog_intensity();
+        let wind_speed = weather.wind_speed;
+        let wind_direction = weather.wind_direction;
+        let temperature = weather.temperature;
+
+        self.state.update_weather(weather);
+        self.animations.update_rain_intensity(rain_intensity);
+        self.animations.update_snow_intensity(snow_intensity);
+        self.animations.update_fog_intensity(fog_intensity);
+        self.animations
+            .update_wind(wind_speed as f32, wind_direction as f32);
+        self.animations.update_temperature(temperature);
+    }
+
+    /// Take the latest result from the weather task, if one has arrived.
+    /// `offline_weather` supplies placeholder data when the very first fetch fails.
+    pub fn poll_weather(&mut self, offline_weather: impl FnOnce() -> WeatherData) {
+        let Some(feed) = &mut self.feed else {
+            return;
+        };
+        let Ok((location, result)) = feed.receiver.try_recv() else {
+            return;
+        };
+        // A fetch that was already running when the location changed
+        if location != self.state.location {
+            return;
+        }
+
+        match result {
+            Ok(weather) => self.apply_weather(weather),
+            Err(error) => {
+                let _error_msg = match &error {
+                    WeatherError::Network(net_err) => net_err.user_friendly_message(),
+                    _ => format!("Failed to fetch weather: {}", error),
+                };
+
+                if self.state.current_weather.is_none() {
+                    self.apply_weather(offline_weather());
+                }
+                self.state.set_offline_mode(true);
+            }
+        }
+    }
+
+    /// Switch to another location. Weather fetched for it earlier is shown
+    /// straight away while the feed refreshes in the background.
+    pub async fn retarget(&mut self, location: &Location, city_name: Option<String>) {
+        let weather_location = WeatherLocation {
+            latitude: location.latitude,
+            longitude: location.longitude,
+            elevation: None,
+        };
+        self.state.set_location(weather_location, city_name);
+        self.state.location_display = location.display;
+        self.state.hide_location = location.hide;
+
+        let Some(feed) = &self.feed else {
+            return;
+        };
+        feed.location_tx.send_replace(weather_location);
+        if let Some(cached) = feed.client.cached_weather(&weather_location).await {
+            self.apply_weather(cached);
+        }
+    }
+
+    /// Draw the pane with the day or night palette of `theme`, whichever
+    /// matches its weather. The palette stays set afterwards.
+    pub fn render(
+        &mut self,
+        renderer: &mut TerminalRenderer,
+        theme: &Theme,
+        hud: Option<&HudStyle>,
+        rng: &mut impl rand::Rng,
+        profiler: &mut Profiler,
+    ) -> io::Result<()> {
+        self.apply_palette(renderer, theme);
+        renderer.set_viewport(self.rect);
+        // Custom art that no longer fits after a resize would be cut off
+        let result = if renderer.is_too_small() || !self.scene.fits() {
+            let (width, height) = renderer.get_size();
+            renderer.render_centered_colored(
+                &[
+                    "Terminal too small".to_string(),
+                    format!("{}x{}", width, height),
+                ],
+                height.saturating_sub(2) / 2,
+                renderer.palette().accent,
+            )
+        } else {
+            self.render_frame(renderer, hud, rng, profiler)
+        };
+        renderer.reset_viewport();
+        result
+    }
+
+    fn render_frame(
+        &mut self,
+        renderer: &mut TerminalRenderer,
+        hud: Option<&HudStyle>,
+        mut rng: &mut impl rand::Rng,
+        profiler: &mut Profiler,
+    ) -> io::Result<()> {
+        let (width, height) = renderer.get_size();
+        let steps = self.animations.begin_frame();
+        self.scene.advance(steps);
+        let anchors = self.scene.anchors();
+
+        let sky = renderer.palette().sky;
+        if sky != Color::Reset {
+            let blank = " ".repeat(width as usize);
+            for y in 0..anchors.horizon_y {
+                renderer.render_line_colored(0, y, &blank, Style::default().on(sky))?;
+            }
+        }
+
+        profiler.time(Stage::Background, || {
+            self.animations.render_background(
+                renderer,
+                &self.state,
+                &anchors,
+                width,
+                height,
+                &mut rng,
+            )
+        })?;
+
+        profiler.time(Stage::Scene, || {
+            self.scene.render(renderer, &self.state.weather_conditions)
+        })?;
+
+        profiler.time(Stage::ChimneySmoke, || {
+            self.animations.render_chimney_smoke(
+                renderer,
+                &self.state.weather_conditions,
+                &anchors,
+                &mut rng,
+            )
+        })?;
+
+        profiler.time(Stage::Foreground, || {
+            self.animations.render_foreground(
+                renderer,
+                &self.state.weather_conditions,
+                width,
+                height,
+                &mut rng,
+            )
+        })?;
+
+        self.state.update_loading_animation();
+        self.state.update_cached_info();
+
+        if let Some(hud) = hud {
+            let text = &self.state.cached_weather_info;
+            let (x, y) = hud.origin(text.chars().count() as u16, width, height);
+            let color = hud.color.unwrap_or(renderer.palette().hud);
+            renderer.render_line_colored(x, y, text, color)?;
+        }
+
+        Ok(())
+    }
+
+    /// Switch the renderer to this pane's palette, e.g. before drawing an
+    /// overlay over it.
+    pub fn apply_palette(&self, renderer: &mut TerminalRenderer, theme: &Theme) {
+        renderer.set_palette(theme.palette(self.state.weather_conditions.is_day));
+    }
+
+    /// Let `dt` of real time pass for this pane's animations.
+    pub fn advance(&mut self, dt: Duration) {
+        self.animations.advance(dt, &self.state.weather_conditions);
+    }
+}
+
+/// Turn `Auto` into a concrete arrangement for `count` panes.
+pub fn resolve_split(split: SplitLayout, count: usize, width: u16, height: u16) -> SplitLayout {
+    if split != SplitLayout::Auto {
+        return split;
+    }
+    if count <= 1 {
+        return SplitLayout::SideBySide;
+    }
+
+    let gaps = count as f32 - 1.0;
+    let side_aspect = (width as f32 - gaps) / count as f32 / height as f32;
+    let stacked_aspect = width as f32 / ((height as f32 - gaps) / count as f32);
+    let distance = |aspect: f32| (aspect / IDEAL_PANE_ASPECT).ln().abs();
+
+    if distance(side_aspect) <= distance(stacked_aspect) {
+        SplitLayout::SideBySide
+    } else {
+        SplitLayout::Stacked
+    }
+}
+
+/// Divide the terminal into `count` panes separated by one-cell gaps.
+pub fn pane_rects(count: usize, width: u16, height: u16, split: SplitLayout) -> Vec<Rect> {
+    let count = count.max(1) as u16;
+    let split = resolve_split(split, count as usize, width, height);
+    let total = match split {
+        SplitLayout::Stacked => height,
+        _ => width,
+    };
+    let available = total.saturating_sub(count - 1);
+    let base = available / count;
+    let extra = available % count;
+
+    let mut rects = Vec::with_capacity(count as usize);
+    let mut offset = 0;
+    for i in 0..count {
+        let size = base + u16::from(i < extra);
+        rects.push(match split {
+            SplitLayout::Stacked => Rect::new(0, offset, width, size),
+            _ => Rect::new(offset, 0, size, height),
+        });
+        // The gap after the last pane can fall past u16::MAX on huge sizes
+        offset = offset.saturating_add(size).saturating_add(1);
+    }
+    rects
+}
+
+/// Draw the lines between adjacent panes.
+pub fn render_separators(renderer: &mut TerminalRenderer, rects: &[Rect]) -> io::Result<()> {
+    let color = renderer.palette().border;
+    for pair in rects.windows(2) {
+        let (first, second) = (pair[0], pair[1]);
+        if second.x > first.x {
+            let x = first.x + first.width;
+            for y in first.y..first.y + first.height {
+                renderer.render_char(x, y, '│', color)?;
+            }
+        } else {
+            let y = first.y + first.height;
+            for x in first.x..first.x + first.width {
+                renderer.render_char(x, y, '─', color)?;
+            }
+        }
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_single_pane_fills_terminal() {
+        let rects = pane_rects(1, 120, 40, SplitLayout::Auto);
+        assert_eq!(rects, vec![Rect::new(0, 0, 120, 40)]);
+    }
+
+    #[test]
+    fn test_side_by_side_leaves_separator_column() {
+        let rects = pane_rects(2, 161, 40, SplitLayout::SideBySide);
+        assert_eq!(rects[0], Rect::new(0, 0, 80, 40));
+        assert_eq!(rects[1], Rect::new(81, 0, 80, 40));
+    }
+
+    #[test]
+    fn test_stacked_distributes_remainder() {
+        let rects = pane_rects(3, 120, 62, SplitLayout::Stacked);
+        let heights: Vec<u16> = rects.iter().map(|r| r.height).collect();
+        assert_eq!(heights, vec![20, 20, 20]);
+        assert_eq!(rects[2].y, 42);
+
+        let rects = pane_rects(2, 120, 42, SplitLayout::Stacked);
+        assert_eq!(rects[0].height, 21);
+        assert_eq!(rects[1].height, 20);
+    }
+
+    #[test]
+    fn test_largest_terminal_does_not_overflow() {
+        let rects = pane_rects(1, u16::MAX, u16::MAX, SplitLayout::Auto);
+        assert_eq!(rects, vec![Rect::new(0, 0, u16::MAX, u16::MAX)]);
+    }
+
+    #[test]
+    fn test_auto_split_follows_terminal_shape() {
+        assert_eq!(
+            resolve_split(SplitLayout::Auto, 2, 240, 40),
+            SplitLayout::SideBySide
+        );
+        assert_eq!(
+            resolve_split(SplitLayout::Auto, 2, 120, 60),
+            SplitLayout::Stacked
+        );
+        assert_eq!(
+            resolve_split(SplitLayout::Stacked, 2, 240, 40),
+            SplitLayout::Stacked
+        );
+    }
+}
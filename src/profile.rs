@@ -0,0 +1,132 @@
+use std::time::{Duration, Instant};
+
+/// The parts of a frame timed for the debug overlay.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Stage {
+    Background,
+    Scene,
+    ChimneySmoke,
+    Foreground,
+    Flush,
+}
+
+impl Stage {
+    pub const ALL: [Stage; 5] = [
+        Stage::Background,
+        Stage::Scene,
+        Stage::ChimneySmoke,
+        Stage::Foreground,
+        Stage::Flush,
+    ];
+
+    /// Named after the function doing the work, so the numbers point
+    /// straight at the code.
+    pub fn label(self) -> &'static str {
+        match self {
+            Stage::Background => "render_background",
+            Stage::Scene => "WorldScene::render",
+            Stage::ChimneySmoke => "render_chimney_smoke",
+            Stage::Foreground => "render_foreground",
+            Stage::Flush => "flush",
+        }
+    }
+}
+
+/// Averages over the last full window of about a second.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct FrameStats {
+    pub fps: f64,
+    /// Time per frame spent in each stage, in `Stage::ALL` order.
+    pub stages: [Duration; Stage::ALL.len()],
+    pub flush_bytes: usize,
+}
+
+impl FrameStats {
+    pub fn frame_time(&self) -> Duration {
+        self.stages.iter().sum()
+    }
+}
+
+/// Collects frame rate, stage times and output size while frames are drawn.
+pub struct Profiler {
+    window_start: Instant,
+    frames: u32,
+    stages: [Duration; Stage::ALL.len()],
+    flush_bytes: usize,
+    stats: FrameStats,
+}
+
+impl Profiler {
+    pub fn new() -> Self {
+        Self {
+            window_start: Instant::now(),
+            frames: 0,
+            stages: Default::default(),
+            flush_bytes: 0,
+            stats: FrameStats::default(),
+        }
+    }
+
+    /// Run `work`, counting the time it takes towards `stage`.
+    pub fn time<T>(&mut self, stage: Stage, work: impl FnOnce() -> T) -> T {
+        let start = Instant::now();
+        let result = work();
+        self.stages[stage as usize] += start.elapsed();
+        result
+    }
+
+    /// Finish a frame that wrote `flush_bytes` to the terminal.
+    pub fn end_frame(&mut self, flush_bytes: usize) {
+        self.frames += 1;
+        self.flush_bytes += flush_bytes;
+
+        let elapsed = self.window_start.elapsed();
+        if elapsed >= Duration::from_secs(1) {
+            self.stats = FrameStats {
+                fps: self.frames as f64 / elapsed.as_secs_f64(),
+                stages: self.stages.map(|total| total / self.frames),
+                flush_bytes: self.flush_bytes / self.frames as usize,
+            };
+            self.frames = 0;
+            self.stages = Default::default();
+            self.flush_bytes = 0;
+            self.window_start = Instant::now();
+        }
+    }
+
+    pub fn stats(&self) -> &FrameStats {
+        &self.stats
+    }
+}
+
+impl Default for Profiler {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_stats_average_over_a_window() {
+        let mut profiler = Profiler::new();
+        profiler.window_start = Instant::now() - Duration::from_secs(2);
+        for bytes in [100, 300] {
+            profiler.time(Stage::Flush, || {
+                std::thread::sleep(Duration::from_millis(2))
+            });
+            profiler.end_frame(bytes);
+        }
+
+        // The first frame closed the stale window on its own
+        let stats = profiler.stats();
+        assert_eq!(stats.flush_bytes, 100);
+        assert!(stats.stages[Stage::Flush as usize] >= Duration::from_millis(2));
+        assert_eq!(stats.stages[Stage::Scene as usize], Duration::ZERO);
+        assert_eq!(stats.frame_time(), stats.stages[Stage::Flush as usize]);
+        assert!(stats.fps > 0.0 && stats.fps < 1.0);
+        assert_eq!(profiler.frames, 1);
+    }
+}
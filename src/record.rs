@@ -0,0 +1,138 @@
+use crate::app::App;
+use crate::render::{StdoutBackend, TerminalRenderer};
+use serde_json::json;
+use std::io::{self, Write};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+/// Written before the first frame: clear the screen and hide the cursor.
+const PREAMBLE: &str = "\u{1b}[2J\u{1b}[H\u{1b}[?25l";
+
+/// Collects what the renderer writes so each frame can become one event.
+#[derive(Clone, Default)]
+struct Capture(Arc<Mutex<Vec<u8>>>);
+
+impl Capture {
+    fn take(&self) -> Vec<u8> {
+        std::mem::take(&mut *self.0.lock().unwrap_or_else(|e| e.into_inner()))
+    }
+}
+
+impl Write for Capture {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        self.0
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .extend_from_slice(buf);
+        Ok(buf.len())
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        Ok(())
+    }
+}
+
+/// Parse `10s`, `500ms`, `2m` or a bare number of seconds.
+pub fn parse_duration(text: &str) -> Result<Duration, String> {
+    let text = text.trim();
+    let (number, scale) = if let Some(ms) = text.strip_suffix("ms") {
+        (ms, 0.001)
+    } else if let Some(s) = text.strip_suffix('s') {
+        (s, 1.0)
+    } else if let Some(m) = text.strip_suffix('m') {
+        (m, 60.0)
+    } else {
+        (text, 1.0)
+    };
+    let value: f64 = number
+        .trim()
+        .parse()
+        .map_err(|_| format!("invalid duration '{text}', expected e.g. 10s, 500ms or 2m"))?;
+    if !value.is_finite() || value <= 0.0 {
+        return Err(format!("duration must be positive, got '{text}'"));
+    }
+    Duration::try_from_secs_f64(value * scale).map_err(|_| format!("duration '{text}' is too long"))
+}
+
+/// Run `app` off-screen at `width`×`height` for `duration` of animation
+/// time and write it to `out` as an asciinema v2 cast. Frames are produced
+/// as fast as possible but timestamped at the app's frame rate.
+pub fn run(
+    app: &mut App,
+    (width, height): (u16, u16),
+    duration: Duration,
+    out: &mut impl Write,
+) -> io::Result<()> {
+    let capture = Capture::default();
+    let backend = StdoutBackend::with_writer(Box::new(capture.clone()));
+    let mut renderer = TerminalRenderer::with_backend(width, height, Box::new(backend));
+
+    let timestamp = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map_or(0, |d| d.as_secs());
+    let header = json!({
+        "version": 2,
+        "width": width,
+        "height": height,
+        "timestamp": timestamp,
+        "env": { "TERM": "xterm-256color" },
+    });
+    writeln!(out, "{header}")?;
+    writeln!(out, "{}", json!([0.0, "o", PREAMBLE]))?;
+
+    let frame_duration = app.frame_duration();
+    let frames = (duration.as_secs_f64() / frame_duration.as_secs_f64()).ceil() as u32;
+    for frame in 0..frames {
+        app.draw(&mut renderer)?;
+        renderer.flush()?;
+        app.update_animations(frame_duration);
+
+        let bytes = capture.take();
+        if bytes.is_empty() {
+            continue;
+        }
+        let time = (frame as f64 * frame_duration.as_secs_f64() * 1000.0).round() / 1000.0;
+        let data = String::from_utf8_lossy(&bytes);
+        writeln!(out, "{}", json!([time, "o", data]))?;
+    }
+    out.flush()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_duration() {
+        assert_eq!(parse_duration("10s"), Ok(Duration::from_secs(10)));
+        assert_eq!(parse_duration("500ms"), Ok(Duration::from_millis(500)));
+        assert_eq!(parse_duration("2m"), Ok(Duration::from_secs(120)));
+        assert_eq!(parse_duration("1.5"), Ok(Duration::from_millis(1500)));
+        assert!(parse_duration("0s").is_err());
+        assert!(parse_duration("soon").is_err());
+        assert!(parse_duration("1e20s").is_err());
+        assert!(parse_duration("NaN").is_err());
+        assert!(parse_duration("infs").is_err());
+    }
+
+    #[test]
+    fn test_cast_events() {
+        let mut app = crate::snapshot::tests::test_app("rain", (60, 20), 7);
+        let mut cast = Vec::new();
+        run(&mut app, (60, 20), Duration::from_millis(200), &mut cast).unwrap();
+
+        let cast = String::from_utf8(cast).unwrap();
+        let mut lines = cast.lines();
+        let header: serde_json::Value = serde_json::from_str(lines.next().unwrap()).unwrap();
+        assert_eq!(header["version"], 2);
+        assert_eq!(header["width"], 60);
+
+        let events: Vec<serde_json::Value> =
+            lines.map(|l| serde_json::from_str(l).unwrap()).collect();
+        assert!(events.len() > 2);
+        assert_eq!(events[0][2], PREAMBLE);
+        assert!(events[1][2].as_str().unwrap().contains("Weather: Rain"));
+        let times: Vec<f64> = events.iter().map(|e| e[0].as_f64().unwrap()).collect();
+        assert!(times.windows(2).all(|w| w[0] <= w[1]));
+    }
+}
@@ -0,0 +1,500 @@
+use super::{Cell, Style};
+use crate::error::TerminalError;
+use crossterm::{
+    cursor,
+    event::{DisableFocusChange, DisableMouseCapture, EnableFocusChange, EnableMouseCapture},
+    execute, queue,
+    style::{Print, ResetColor, SetAttribute, SetBackgroundColor, SetForegroundColor},
+    terminal::{self, Clear, ClearType, EnterAlternateScreen, LeaveAlternateScreen},
+};
+use std::any::Any;
+use std::io::{self, BufWriter, Write};
+use std::sync::atomic::{AtomicBool, Ordering};
+
+/// Set while a `StdoutBackend` has the terminal in raw mode on the
+/// alternate screen, so a panic knows whether there is anything to undo.
+static TERMINAL_ACTIVE: AtomicBool = AtomicBool::new(false);
+
+/// Whether the terminal has been taken over and not yet handed back.
+pub fn terminal_active() -> bool {
+    TERMINAL_ACTIVE.load(Ordering::SeqCst)
+}
+
+/// Where `TerminalRenderer` sends finished frames.
+pub trait Backend: Any {
+    /// Take over the output, e.g. switch to the alternate screen.
+    fn init(&mut self) -> Result<(), TerminalError> {
+        Ok(())
+    }
+
+    /// Undo `init`.
+    fn cleanup(&mut self) -> io::Result<()> {
+        Ok(())
+    }
+
+    /// The output is now `width`×`height` and everything on it is gone.
+    fn resize(&mut self, width: u16, height: u16) -> io::Result<()>;
+
+    /// Show the cells that changed since the last call, given in row-major
+    /// order as (x, y, cell).
+    fn draw(&mut self, changes: &[(u16, u16, Cell)]) -> io::Result<()>;
+
+    /// Bytes of output produced by the last `draw`, for the debug overlay.
+    fn last_draw_bytes(&self) -> usize {
+        0
+    }
+}
+
+/// Passes writes through to `inner`, counting the bytes.
+struct CountingWriter {
+    inner: Box<dyn Write>,
+    count: usize,
+}
+
+impl Write for CountingWriter {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        let written = self.inner.write(buf)?;
+        self.count += written;
+        Ok(written)
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        self.inner.flush()
+    }
+}
+
+/// Draws with ANSI escape sequences, normally to the terminal.
+pub struct StdoutBackend {
+    out: CountingWriter,
+    /// False when writing somewhere other than the terminal, which then
+    /// needs no raw mode or alternate screen.
+    terminal: bool,
+    last_draw_bytes: usize,
+}
+
+impl StdoutBackend {
+    pub fn new() -> Self {
+        Self {
+            terminal: true,
+            ..Self::with_writer(Box::new(BufWriter::new(io::stdout())))
+        }
+    }
+
+    /// Write the escape sequences for each frame to `out` instead.
+    pub fn with_writer(out: Box<dyn Write>) -> Self {
+        Self {
+            out: CountingWriter {
+                inner: out,
+                count: 0,
+            },
+            terminal: false,
+            last_draw_bytes: 0,
+        }
+    }
+}
+
+impl Default for StdoutBackend {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Backend for StdoutBackend {
+    fn init(&mut self) -> Result<(), TerminalError> {
+        if !self.terminal {
+            return Ok(());
+        }
+        terminal::enable_raw_mode().map_err(TerminalError::RawModeError)?;
+        TERMINAL_ACTIVE.store(true, Ordering::SeqCst);
+        execute!(
+            self.out,
+            EnterAlternateScreen,
+            EnableMouseCapture,
+            EnableFocusChange,
+            cursor::Hide
+        )
+        .map_err(TerminalError::InitError)?;
+        Ok(())
+    }
+
+    fn cleanup(&mut self) -> io::Result<()> {
+        if !self.terminal {
+            return Ok(());
+        }
+        execute!(
+            self.out,
+            DisableMouseCapture,
+            DisableFocusChange,
+            LeaveAlternateScreen,
+            cursor::Show,
+            ResetColor
+        )?;
+        terminal::disable_raw_mode()?;
+        TERMINAL_ACTIVE.store(false, Ordering::SeqCst);
+        Ok(())
+    }
+
+    fn resize(&mut self, _width: u16, _height: u16) -> io::Result<()> {
+        execute!(self.out, Clear(ClearType::All))
+    }
+
+    fn draw(&mut self, changes: &[(u16, u16, Cell)]) -> io::Result<()> {
+        self.out.count = 0;
+        self.last_draw_bytes = 0;
+        if changes.is_empty() {
+            return Ok(());
+        }
+
+        // Terminals that know DEC mode 2026 hold the frame back until it is
+        // complete, so a busy frame never shows half drawn.
+        queue!(self.out, terminal::BeginSynchronizedUpdate)?;
+
+        let mut current_style = Style::default();
+        // Where the cursor is, once something has been printed this frame
+        let mut cursor: Option<(u16, u16)> = None;
+        // Cells waiting to be printed in one go at the cursor
+        let mut run = String::new();
+
+        for &(x, y, cell) in changes {
+            let adjacent = cursor == Some((x, y));
+            let style = cell.style();
+            if (!adjacent || style != current_style) && !run.is_empty() {
+                queue!(self.out, Print(&run))?;
+                run.clear();
+            }
+
+            if !adjacent {
+                queue!(self.out, Print(cursor_move(cursor, (x, y))))?;
+            }
+
+            if style != current_style {
+                change_style(&mut self.out, current_style, style)?;
+                current_style = style;
+            }
+
+            run.push(cell.character);
+            cursor = Some((x + 1, y));
+        }
+
+        if !run.is_empty() {
+            queue!(self.out, Print(&run))?;
+        }
+        if current_style != Style::default() {
+            queue!(self.out, ResetColor)?;
+        }
+        queue!(self.out, terminal::EndSynchronizedUpdate)?;
+
+        self.out.flush()?;
+        self.last_draw_bytes = self.out.count;
+        Ok(())
+    }
+
+    fn last_draw_bytes(&self) -> usize {
+        self.last_draw_bytes
+    }
+}
+
+/// Switch the output from drawing in `from` to drawing in `to`. Attributes
+/// can only be turned off together, so losing any of them starts over from
+/// the default style.
+fn change_style(out: &mut impl Write, mut from: Style, to: Style) -> io::Result<()> {
+    if Style::ATTRIBUTES
+        .iter()
+        .any(|&attribute| from.attributes.has(attribute) && !to.attributes.has(attribute))
+    {
+        queue!(out, ResetColor)?;
+        from = Style::default();
+    }
+
+    if to.color != from.color {
+        queue!(out, SetForegroundColor(to.color))?;
+    }
+    if to.background != from.background {
+        queue!(out, SetBackgroundColor(to.background))?;
+    }
+    for attribute in Style::ATTRIBUTES {
+        if to.attributes.has(attribute) && !from.attributes.has(attribute) {
+            queue!(out, SetAttribute(attribute))?;
+        }
+    }
+    Ok(())
+}
+
+/// The shortest escape sequence taking the cursor from `from`, or from
+/// anywhere if that is unknown, to `to`.
+fn cursor_move(from: Option<(u16, u16)>, to: (u16, u16)) -> String {
+    let (x, y) = to;
+    let absolute = format!("\x1b[{};{}H", y + 1, x + 1);
+    let relative = match from {
+        Some((from_x, from_y)) if from_y == y && from_x < x => csi(x - from_x, 'C'),
+        // The carriage return also clears a wrap left pending by printing
+        // into the last column, which would throw off a plain move down.
+        Some((_, from_y)) if from_y < y => {
+            let mut sequence = format!("\r{}", csi(y - from_y, 'B'));
+            if x > 0 {
+                sequence.push_str(&csi(x, 'C'));
+            }
+            sequence
+        }
+        _ => return absolute,
+    };
+
+    if relative.len() < absolute.len() {
+        relative
+    } else {
+        absolute
+    }
+}
+
+/// A cursor movement by `n`, leaving out the count when it is the default 1.
+fn csi(n: u16, code: char) -> String {
+    if n == 1 {
+        format!("\x1b[{code}")
+    } else {
+        format!("\x1b[{n}{code}")
+    }
+}
+
+/// Keeps the last frame as a grid of cells, for tests and off-screen
+/// rendering.
+pub struct MemoryBackend {
+    width: u16,
+    height: u16,
+    cells: Vec<Cell>,
+}
+
+impl MemoryBackend {
+    pub fn new(width: u16, height: u16) -> Self {
+        Self {
+            width,
+            height,
+            cells: vec![Cell::default(); (width as usize) * (height as usize)],
+        }
+    }
+
+    pub fn height(&self) -> u16 {
+        self.height
+    }
+
+    pub fn row(&self, y: u16) -> &[Cell] {
+        let start = (y as usize) * (self.width as usize);
+        &self.cells[start..start + self.width as usize]
+    }
+
+    /// The characters of the frame, one line per row with trailing spaces
+    /// removed.
+    pub fn text(&self) -> String {
+        (0..self.height)
+            .map(|y| {
+                let line: String = self.row(y).iter().map(|cell| cell.character).collect();
+                line.trim_end().to_string() + "\n"
+            })
+            .collect()
+    }
+}
+
+impl Backend for MemoryBackend {
+    fn resize(&mut self, width: u16, height: u16) -> io::Result<()> {
+        *self = Self::new(width, height);
+        Ok(())
+    }
+
+    fn draw(&mut self, changes: &[(u16, u16, Cell)]) -> io::Result<()> {
+        for &(x, y, cell) in changes {
+            if x < self.width && y < self.height {
+                self.cells[(y as usize) * (self.width as usize) + (x as usize)] = cell;
+            }
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crossterm::style::{Attribute, Color};
+    use std::sync::{Arc, Mutex};
+
+    fn cell(character: char, color: Color) -> Cell {
+        Cell::new(character, color.into())
+    }
+
+    #[test]
+    fn test_memory_backend_keeps_grid() {
+        let mut backend = MemoryBackend::new(4, 2);
+        backend
+            .draw(&[
+                (1, 0, cell('a', Color::Red)),
+                (3, 1, cell('b', Color::Blue)),
+            ])
+            .unwrap();
+        assert_eq!(backend.row(0)[1], cell('a', Color::Red));
+        assert_eq!(backend.text(), " a\n   b\n");
+
+        backend.resize(2, 1).unwrap();
+        assert_eq!(backend.text(), "\n");
+    }
+
+    #[test]
+    fn test_drawing_keeps_background() {
+        use crate::render::{Style, TerminalRenderer};
+
+        let mut renderer = TerminalRenderer::headless(4, 1);
+        let sky = Color::Rgb { r: 0, g: 0, b: 64 };
+        renderer
+            .render_line_colored(0, 0, "    ", Style::default().on(sky))
+            .unwrap();
+        renderer.render_char(1, 0, '*', Color::White).unwrap();
+        renderer
+            .render_char(2, 0, '#', Style::new(Color::Red).on(Color::Blue))
+            .unwrap();
+        renderer.flush().unwrap();
+
+        let row = renderer.backend::<MemoryBackend>().unwrap().row(0);
+        assert_eq!(row[1].color, Color::White);
+        assert_eq!(row[1].background, sky);
+        assert_eq!(row[2].background, Color::Blue);
+    }
+
+    #[derive(Clone, Default)]
+    struct Shared(Arc<Mutex<Vec<u8>>>);
+
+    impl Write for Shared {
+        fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+            self.0.lock().unwrap().extend_from_slice(buf);
+            Ok(buf.len())
+        }
+
+        fn flush(&mut self) -> io::Result<()> {
+            Ok(())
+        }
+    }
+
+    fn draw_to_string(changes: &[(u16, u16, Cell)]) -> (String, usize) {
+        let out = Shared::default();
+        let mut backend = StdoutBackend::with_writer(Box::new(out.clone()));
+        backend.draw(changes).unwrap();
+        let bytes = String::from_utf8(out.0.lock().unwrap().clone()).unwrap();
+        (bytes, backend.last_draw_bytes())
+    }
+
+    #[test]
+    fn test_stdout_backend_skips_moves_within_a_run() {
+        let (bytes, count) = draw_to_string(&[
+            (2, 1, cell('a', Color::Red)),
+            (3, 1, cell('b', Color::Red)),
+            (0, 2, cell('c', Color::Reset)),
+        ]);
+        assert_eq!(
+            bytes,
+            "\u{1b}[?2026h\u{1b}[2;3H\u{1b}[38;5;9mab\r\u{1b}[B\u{1b}[39mc\u{1b}[?2026l"
+        );
+        assert_eq!(count, bytes.len());
+    }
+
+    #[test]
+    fn test_stdout_backend_switches_background_and_attributes() {
+        let lit = Style::new(Color::Black)
+            .on(Color::Yellow)
+            .with(Attribute::Bold);
+        let (bytes, _) = draw_to_string(&[
+            (0, 0, Cell::new('#', lit)),
+            (1, 0, Cell::new('#', lit)),
+            (2, 0, Cell::new('!', lit.with(Attribute::Reverse))),
+            (3, 0, cell('.', Color::Black)),
+        ]);
+        assert_eq!(
+            bytes,
+            "\u{1b}[?2026h\u{1b}[1;1H\u{1b}[38;5;0m\u{1b}[48;5;11m\u{1b}[1m##\u{1b}[7m!\u{1b}[0m\u{1b}[38;5;0m.\u{1b}[0m\u{1b}[?2026l"
+        );
+    }
+
+    #[test]
+    fn test_writer_backend_leaves_terminal_alone() {
+        let out = Shared::default();
+        let mut backend = StdoutBackend::with_writer(Box::new(out.clone()));
+        backend.init().unwrap();
+        assert!(!terminal_active());
+        backend.cleanup().unwrap();
+        assert!(out.0.lock().unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_stdout_backend_writes_nothing_without_changes() {
+        assert_eq!(draw_to_string(&[]), (String::new(), 0));
+    }
+
+    #[test]
+    fn test_cursor_move_picks_shortest() {
+        assert_eq!(cursor_move(None, (0, 0)), "\u{1b}[1;1H");
+        assert_eq!(cursor_move(Some((3, 4)), (4, 4)), "\u{1b}[C");
+        assert_eq!(cursor_move(Some((3, 4)), (40, 4)), "\u{1b}[37C");
+        assert_eq!(cursor_move(Some((80, 4)), (0, 5)), "\r\u{1b}[B");
+        assert_eq!(cursor_move(Some((80, 4)), (0, 6)), "\r\u{1b}[2B");
+        assert_eq!(cursor_move(Some((8, 100)), (1, 101)), "\r\u{1b}[B\u{1b}[C");
+        // Far down and across, an absolute move is shorter
+        assert_eq!(cursor_move(Some((0, 4)), (150, 60)), "\u{1b}[61;151H");
+        // Moves backwards are never relative
+        assert_eq!(cursor_move(Some((9, 4)), (2, 4)), "\u{1b}[5;3H");
+        assert_eq!(cursor_move(Some((9, 4)), (2, 3)), "\u{1b}[4;3H");
+    }
+
+    #[test]
+    fn test_stdout_backend_batches_storm_frame() {
+        use crate::animation::raindrops::RaindropSystem;
+        use crate::render::TerminalRenderer;
+        use crate::weather::RainIntensity;
+        use rand::SeedableRng;
+
+        let (width, height) = (200, 60);
+        let mut rng = rand::rngs::StdRng::seed_from_u64(7);
+        let mut rain = RaindropSystem::new(width, height, RainIntensity::Storm, &mut rng);
+        for _ in 0..60 {
+            rain.update(width, height, &mut rng);
+        }
+
+        let out = Shared::default();
+        let mut renderer = TerminalRenderer::with_backend(
+            width,
+            height,
+            Box::new(StdoutBackend::with_writer(Box::new(out.clone()))),
+        );
+        rain.render(&mut renderer).unwrap();
+        renderer.flush().unwrap();
+        let written = out.0.lock().unwrap().len();
+
+        let mut headless = TerminalRenderer::headless(width, height);
+        rain.render(&mut headless).unwrap();
+        headless.flush().unwrap();
+        let memory = headless.backend::<MemoryBackend>().unwrap();
+        let changes: Vec<_> = (0..height)
+            .flat_map(|y| {
+                memory
+                    .row(y)
+                    .iter()
+                    .enumerate()
+                    .filter(|(_, cell)| **cell != Cell::default())
+                    .map(move |(x, cell)| (x as u16, y, *cell))
+            })
+            .collect();
+
+        // One absolute move, one color and one character per changed cell
+        let naive: usize = changes
+            .iter()
+            .map(|&(x, y, cell)| {
+                let mut bytes = format!("\x1b[{};{}H{}", y + 1, x + 1, cell.character).len();
+                if cell.color != Color::Reset {
+                    bytes += 11;
+                }
+                bytes
+            })
+            .sum();
+        assert!(changes.len() > 100);
+        assert!(
+            written * 3 < naive * 2,
+            "{written} bytes written for {} cells, {naive} without batching",
+            changes.len()
+        );
+        assert_eq!(renderer.last_flush_bytes(), written);
+    }
+}
@@ -1,7 +1,65 @@
-use crossterm::style::Color;
+use super::Style;
+use crossterm::style::{Attribute, Color};
+use serde::Deserialize;
 use std::env;
 use std::io::IsTerminal;
 
+/// The 16 ANSI colors in palette order.
+const NAMED: [Color; 16] = [
+    Color::Black,
+    Color::DarkRed,
+    Color::DarkGreen,
+    Color::DarkYellow,
+    Color::DarkBlue,
+    Color::DarkMagenta,
+    Color::DarkCyan,
+    Color::Grey,
+    Color::DarkGrey,
+    Color::Red,
+    Color::Green,
+    Color::Yellow,
+    Color::Blue,
+    Color::Magenta,
+    Color::Cyan,
+    Color::White,
+];
+
+/// Channel values of the xterm 6×6×6 color cube.
+const CUBE_LEVELS: [u8; 6] = [0, 95, 135, 175, 215, 255];
+
+/// Which colors to draw with, for terminals that misreport what they can do.
+#[derive(Deserialize, Debug, Default, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum ColorMode {
+    /// Go by `NO_COLOR`, `COLORTERM` and `TERM`.
+    #[default]
+    Auto,
+    Truecolor,
+    #[serde(rename = "256")]
+    Ansi256,
+    #[serde(rename = "16")]
+    Basic,
+    None,
+}
+
+impl std::str::FromStr for ColorMode {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_lowercase().as_str() {
+            "auto" => Ok(Self::Auto),
+            "truecolor" | "24bit" => Ok(Self::Truecolor),
+            "256" => Ok(Self::Ansi256),
+            "16" => Ok(Self::Basic),
+            "none" => Ok(Self::None),
+            _ => Err(format!(
+                "Unknown color mode: '{}'. Valid options: auto, truecolor, 256, 16, none",
+                s
+            )),
+        }
+    }
+}
+
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum ColorSupport {
     None,
@@ -18,9 +76,24 @@ pub struct TerminalCapabilities {
 }
 
 impl TerminalCapabilities {
-    pub fn detect() -> Self {
+    /// What the terminal supports, unless `mode` says otherwise.
+    pub fn detect(mode: ColorMode) -> Self {
         let is_tty = std::io::stdout().is_terminal();
 
+        let forced = match mode {
+            ColorMode::Auto => None,
+            ColorMode::Truecolor => Some(ColorSupport::TrueColor),
+            ColorMode::Ansi256 => Some(ColorSupport::Ansi256),
+            ColorMode::Basic => Some(ColorSupport::Basic),
+            ColorMode::None => Some(ColorSupport::None),
+        };
+        if let Some(color_support) = forced {
+            return Self {
+                color_support,
+                is_tty,
+            };
+        }
+
         if env::var("NO_COLOR").is_ok() {
             return Self {
                 color_support: ColorSupport::None,
@@ -58,21 +131,52 @@ impl TerminalCapabilities {
         }
     }
 
-    pub fn adjust_color(&self, color: Color) -> Color {
-        if self.color_support == ColorSupport::None {
-            return Color::Reset;
+    /// Every color passed through unchanged.
+    pub fn full() -> Self {
+        Self {
+            color_support: ColorSupport::TrueColor,
+            is_tty: false,
         }
+    }
 
+    pub fn adjust_color(&self, color: Color) -> Color {
         match self.color_support {
             ColorSupport::None => Color::Reset,
             ColorSupport::Basic => match color {
-                Color::Rgb { .. } => Color::White,
+                Color::Rgb { r, g, b } => nearest_basic(r, g, b),
+                Color::AnsiValue(n) if n < 16 => NAMED[n as usize],
+                Color::AnsiValue(n) => {
+                    let (r, g, b) = ansi_rgb(n);
+                    nearest_basic(r, g, b)
+                }
+                _ => color,
+            },
+            ColorSupport::Ansi256 => match color {
+                Color::Rgb { r, g, b } => Color::AnsiValue(nearest_ansi256(r, g, b)),
                 _ => color,
             },
-            ColorSupport::Ansi256 => color,
             ColorSupport::TrueColor => color,
         }
     }
+
+    /// The style with its colors adjusted and any attributes the terminal is
+    /// unlikely to show dropped. Basic consoles tend to draw italic as a
+    /// color of their own, and without color only bold and reverse are safe.
+    pub fn adjust_style(&self, style: Style) -> Style {
+        let mut adjusted =
+            Style::new(self.adjust_color(style.color)).on(self.adjust_color(style.background));
+        for attribute in Style::ATTRIBUTES {
+            let supported = match self.color_support {
+                ColorSupport::None => matches!(attribute, Attribute::Bold | Attribute::Reverse),
+                ColorSupport::Basic => attribute != Attribute::Italic,
+                ColorSupport::Ansi256 | ColorSupport::TrueColor => true,
+            };
+            if supported && style.attributes.has(attribute) {
+                adjusted = adjusted.with(attribute);
+            }
+        }
+        adjusted
+    }
 }
 
 fn check_term_for_256() -> ColorSupport {
@@ -82,6 +186,138 @@ fn check_term_for_256() -> ColorSupport {
     ColorSupport::Basic
 }
 
+/// The closest entry of the xterm color cube or greyscale ramp.
+fn nearest_ansi256(r: u8, g: u8, b: u8) -> u8 {
+    let level = |v: u8| match v {
+        0..48 => 0,
+        48..115 => 1,
+        _ => (v - 35) / 40,
+    };
+    let (ri, gi, bi) = (level(r), level(g), level(b));
+    let cube = 16 + 36 * ri + 6 * gi + bi;
+    let cube_rgb = (
+        CUBE_LEVELS[ri as usize],
+        CUBE_LEVELS[gi as usize],
+        CUBE_LEVELS[bi as usize],
+    );
+
+    let average = (r as u16 + g as u16 + b as u16) / 3;
+    let step = (average.saturating_sub(3) / 10).min(23) as u8;
+    let grey = 8 + step * 10;
+
+    if distance((r, g, b), (grey, grey, grey)) < distance((r, g, b), cube_rgb) {
+        232 + step
+    } else {
+        cube
+    }
+}
+
+/// Squared "redmean" distance, a cheap approximation of how different two
+/// colors look.
+fn distance((r1, g1, b1): (u8, u8, u8), (r2, g2, b2): (u8, u8, u8)) -> i32 {
+    let mean_red = (r1 as i32 + r2 as i32) / 2;
+    let (dr, dg, db) = (
+        r1 as i32 - r2 as i32,
+        g1 as i32 - g2 as i32,
+        b1 as i32 - b2 as i32,
+    );
+    (((512 + mean_red) * dr * dr) >> 8) + 4 * dg * dg + (((767 - mean_red) * db * db) >> 8)
+}
+
+/// The ANSI color of the same hue and brightness. Palettes differ too much
+/// between terminals for a nearest match to work: it turns every muted
+/// color grey, where a hue keeps browns brown and greens green.
+fn nearest_basic(r: u8, g: u8, b: u8) -> Color {
+    let max = r.max(g).max(b);
+    let min = r.min(g).min(b);
+    let chroma = max - min;
+    let value = max as f32 / 255.0;
+
+    if value < 0.15 {
+        return Color::Black;
+    }
+    if (chroma as f32) < max as f32 * 0.2 {
+        return match value {
+            ..0.45 => Color::DarkGrey,
+            ..0.8 => Color::Grey,
+            _ => Color::White,
+        };
+    }
+
+    let (r, g, b) = (r as f32, g as f32, b as f32);
+    let chroma = chroma as f32;
+    let hue = if max == r as u8 {
+        60.0 * ((g - b) / chroma).rem_euclid(6.0)
+    } else if max == g as u8 {
+        60.0 * ((b - r) / chroma + 2.0)
+    } else {
+        60.0 * ((r - g) / chroma + 4.0)
+    };
+
+    // Red stops early so oranges and browns land on yellow, which most
+    // palettes draw brown in its dark variant
+    let bright = value >= 0.9;
+    let (dark, light) = match hue {
+        20.0..90.0 => (Color::DarkYellow, Color::Yellow),
+        90.0..150.0 => (Color::DarkGreen, Color::Green),
+        150.0..210.0 => (Color::DarkCyan, Color::Cyan),
+        210.0..270.0 => (Color::DarkBlue, Color::Blue),
+        270.0..340.0 => (Color::DarkMagenta, Color::Magenta),
+        _ => (Color::DarkRed, Color::Red),
+    };
+    if bright { light } else { dark }
+}
+
+/// The color as red, green and blue, using the xterm palette for named and
+/// 256-color values. `None` for the terminal's default color.
+pub fn color_rgb(color: Color) -> Option<(u8, u8, u8)> {
+    match color {
+        Color::Reset => None,
+        Color::Rgb { r, g, b } => Some((r, g, b)),
+        Color::AnsiValue(n) => Some(ansi_rgb(n)),
+        named => NAMED
+            .iter()
+            .position(|&c| c == named)
+            .map(|n| ansi_rgb(n as u8)),
+    }
+}
+
+fn ansi_rgb(n: u8) -> (u8, u8, u8) {
+    const BASIC: [(u8, u8, u8); 16] = [
+        (0, 0, 0),
+        (205, 0, 0),
+        (0, 205, 0),
+        (205, 205, 0),
+        (0, 0, 238),
+        (205, 0, 205),
+        (0, 205, 205),
+        (229, 229, 229),
+        (127, 127, 127),
+        (255, 0, 0),
+        (0, 255, 0),
+        (255, 255, 0),
+        (92, 92, 255),
+        (255, 0, 255),
+        (0, 255, 255),
+        (255, 255, 255),
+    ];
+    match n {
+        0..=15 => BASIC[n as usize],
+        16..=231 => {
+            let n = n - 16;
+            (
+                CUBE_LEVELS[(n / 36) as usize],
+                CUBE_LEVELS[((n / 6) % 6) as usize],
+                CUBE_LEVELS[(n % 6) as usize],
+            )
+        }
+        _ => {
+            let v = 8 + (n - 232) * 10;
+            (v, v, v)
+        }
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -109,8 +345,29 @@ mod tests {
         assert_eq!(caps.adjust_color(Color::Red), Color::Red);
         assert_eq!(
             caps.adjust_color(Color::Rgb { r: 255, g: 0, b: 0 }),
-            Color::White
+            Color::Red
         );
+        assert_eq!(caps.adjust_color(Color::AnsiValue(4)), Color::DarkBlue);
+        assert_eq!(caps.adjust_color(Color::AnsiValue(46)), Color::Green);
+    }
+
+    #[test]
+    fn test_basic_keeps_hues() {
+        let basic = |r, g, b| nearest_basic(r, g, b);
+        // House wood and door, window glow, leaves, night grass
+        assert_eq!(basic(210, 180, 140), Color::DarkYellow);
+        assert_eq!(basic(139, 69, 19), Color::DarkYellow);
+        assert_eq!(basic(255, 196, 87), Color::Yellow);
+        assert_eq!(basic(255, 165, 0), Color::Yellow);
+        assert_eq!(basic(205, 92, 92), Color::DarkRed);
+        assert_eq!(basic(0, 50, 0), Color::DarkGreen);
+        // Greys by brightness
+        assert_eq!(basic(10, 10, 10), Color::Black);
+        assert_eq!(basic(90, 90, 100), Color::DarkGrey);
+        assert_eq!(basic(170, 170, 170), Color::Grey);
+        assert_eq!(basic(250, 250, 245), Color::White);
+        assert_eq!(basic(100, 100, 255), Color::Blue);
+        assert_eq!(basic(128, 0, 128), Color::DarkMagenta);
     }
 
     #[test]
@@ -121,7 +378,28 @@ mod tests {
         };
         assert_eq!(caps.adjust_color(Color::Red), Color::Red);
         let rgb = Color::Rgb { r: 255, g: 0, b: 0 };
-        assert_eq!(caps.adjust_color(rgb), rgb);
+        assert_eq!(caps.adjust_color(rgb), Color::AnsiValue(196));
+    }
+
+    #[test]
+    fn test_ansi256_picks_cube_or_grey() {
+        // Exact cube and ramp entries map to themselves
+        for n in 16..=255 {
+            let (r, g, b) = ansi_rgb(n);
+            assert_eq!(ansi_rgb(nearest_ansi256(r, g, b)), (r, g, b), "{n}");
+        }
+        assert_eq!(nearest_ansi256(210, 180, 140), 180);
+        assert_eq!(nearest_ansi256(139, 69, 19), 94);
+        // Near-greys go to the finer ramp rather than the cube
+        assert_eq!(nearest_ansi256(128, 128, 128), 244);
+        assert_eq!(nearest_ansi256(0, 0, 0), 16);
+    }
+
+    #[test]
+    fn test_color_mode_from_str() {
+        assert_eq!("256".parse::<ColorMode>(), Ok(ColorMode::Ansi256));
+        assert_eq!("TrueColor".parse::<ColorMode>(), Ok(ColorMode::Truecolor));
+        assert!("8".parse::<ColorMode>().is_err());
     }
 
     #[test]
@@ -134,4 +412,50 @@ mod tests {
         let rgb = Color::Rgb { r: 255, g: 0, b: 0 };
         assert_eq!(caps.adjust_color(rgb), rgb);
     }
+
+    #[test]
+    fn test_adjust_style_drops_unsupported_attributes() {
+        let style = Style::new(Color::Rgb { r: 255, g: 0, b: 0 })
+            .on(Color::Blue)
+            .with(Attribute::Bold)
+            .with(Attribute::Italic)
+            .with(Attribute::Dim)
+            .with(Attribute::Underlined);
+
+        // Underline is not one of the attributes the renderer promises
+        let full = TerminalCapabilities::full().adjust_style(style);
+        assert_eq!(
+            full,
+            Style::new(Color::Rgb { r: 255, g: 0, b: 0 })
+                .on(Color::Blue)
+                .with(Attribute::Bold)
+                .with(Attribute::Italic)
+                .with(Attribute::Dim)
+        );
+
+        let basic = TerminalCapabilities {
+            color_support: ColorSupport::Basic,
+            is_tty: true,
+        }
+        .adjust_style(style);
+        assert_eq!(
+            basic,
+            Style::new(Color::Red)
+                .on(Color::Blue)
+                .with(Attribute::Bold)
+                .with(Attribute::Dim)
+        );
+
+        let none = TerminalCapabilities {
+            color_support: ColorSupport::None,
+            is_tty: true,
+        }
+        .adjust_style(style.with(Attribute::Reverse));
+        assert_eq!(
+            none,
+            Style::default()
+                .with(Attribute::Bold)
+                .with(Attribute::Reverse)
+        );
+    }
 }
@@ -1,43 +1,146 @@
+mod backend;
 mod capabilities;
 
+pub use backend::{Backend, MemoryBackend, StdoutBackend, terminal_active};
+pub use capabilities::{ColorMode, color_rgb};
+
 use crate::error::TerminalError;
+use crate::theme::Palette;
 use capabilities::TerminalCapabilities;
 use crossterm::{
-    cursor, execute, queue,
-    style::{Color, Print, ResetColor, SetForegroundColor},
-    terminal::{self, Clear, ClearType, EnterAlternateScreen, LeaveAlternateScreen},
+    style::{Attribute, Attributes, Color},
+    terminal,
 };
-use std::io::{self, BufWriter, IsTerminal, Stdout, Write};
+use std::any::Any;
+use std::io::{self, IsTerminal};
+
+// Below this even the compact layout has nowhere to put the house
+pub const MIN_TERMINAL_WIDTH: u16 = 40;
+pub const MIN_TERMINAL_HEIGHT: u16 = 12;
+
+/// A rectangle of the terminal, in cells.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct Rect {
+    pub x: u16,
+    pub y: u16,
+    pub width: u16,
+    pub height: u16,
+}
 
-const MIN_TERMINAL_WIDTH: u16 = 70;
-const MIN_TERMINAL_HEIGHT: u16 = 20;
+impl Rect {
+    pub fn new(x: u16, y: u16, width: u16, height: u16) -> Self {
+        Self {
+            x,
+            y,
+            width,
+            height,
+        }
+    }
 
-#[derive(Clone, Copy, PartialEq, Eq)]
-struct Cell {
-    character: char,
-    color: Color,
+    pub fn contains(&self, x: u16, y: u16) -> bool {
+        x >= self.x && x < self.x + self.width && y >= self.y && y < self.y + self.height
+    }
 }
 
-impl Default for Cell {
+/// How a character is drawn. A plain `Color` converts into a style with
+/// that foreground and nothing else.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Style {
+    pub color: Color,
+    pub background: Color,
+    /// Only `Style::ATTRIBUTES` are drawn.
+    pub attributes: Attributes,
+}
+
+impl Style {
+    /// The attributes terminals broadly agree on.
+    pub const ATTRIBUTES: [Attribute; 4] = [
+        Attribute::Bold,
+        Attribute::Dim,
+        Attribute::Italic,
+        Attribute::Reverse,
+    ];
+
+    pub fn new(color: Color) -> Self {
+        Self {
+            color,
+            background: Color::Reset,
+            attributes: Attributes::default(),
+        }
+    }
+
+    pub fn on(mut self, background: Color) -> Self {
+        self.background = background;
+        self
+    }
+
+    pub fn with(mut self, attribute: Attribute) -> Self {
+        self.attributes.set(attribute);
+        self
+    }
+}
+
+impl Default for Style {
     fn default() -> Self {
+        Self::new(Color::Reset)
+    }
+}
+
+impl From<Color> for Style {
+    fn from(color: Color) -> Self {
+        Self::new(color)
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Cell {
+    pub character: char,
+    pub color: Color,
+    pub background: Color,
+    pub attributes: Attributes,
+}
+
+impl Cell {
+    pub fn new(character: char, style: Style) -> Self {
         Self {
-            character: ' ',
-            color: Color::Reset,
+            character,
+            color: style.color,
+            background: style.background,
+            attributes: style.attributes,
+        }
+    }
+
+    pub fn style(&self) -> Style {
+        Style {
+            color: self.color,
+            background: self.background,
+            attributes: self.attributes,
         }
     }
 }
 
+impl Default for Cell {
+    fn default() -> Self {
+        Self::new(' ', Style::default())
+    }
+}
+
 pub struct TerminalRenderer {
-    stdout: BufWriter<Stdout>,
+    backend: Box<dyn Backend>,
+    changes: Vec<(u16, u16, Cell)>,
     width: u16,
     height: u16,
     buffer: Vec<Cell>,
     last_buffer: Vec<Cell>,
+    viewport: Rect,
     capabilities: TerminalCapabilities,
+    palette: Palette,
 }
 
 impl TerminalRenderer {
-    pub fn new() -> Result<Self, TerminalError> {
+    /// A renderer for the terminal on stdout, drawing with the colors it
+    /// supports or those `color_mode` asks for.
+    pub fn new(color_mode: ColorMode) -> Result<Self, TerminalError> {
         if !io::stdout().is_terminal() {
             return Err(TerminalError::NotATty);
         }
@@ -53,31 +156,45 @@ impl TerminalRenderer {
             });
         }
 
-        let stdout = BufWriter::new(io::stdout());
-        let buffer_size = (width as usize) * (height as usize);
-        let capabilities = TerminalCapabilities::detect();
+        let mut renderer = Self::with_backend(width, height, Box::new(StdoutBackend::new()));
+        renderer.capabilities = TerminalCapabilities::detect(color_mode);
+        Ok(renderer)
+    }
 
-        Ok(Self {
-            stdout,
+    /// A renderer drawing `width`×`height` frames to `backend`, without
+    /// checking for a terminal. Colors are kept as given.
+    pub fn with_backend(width: u16, height: u16, backend: Box<dyn Backend>) -> Self {
+        let buffer_size = (width as usize) * (height as usize);
+        Self {
+            backend,
+            changes: Vec::new(),
             width,
             height,
             buffer: vec![Cell::default(); buffer_size],
             last_buffer: vec![Cell::default(); buffer_size],
-            capabilities,
-        })
+            viewport: Rect::new(0, 0, width, height),
+            capabilities: TerminalCapabilities::full(),
+            palette: Palette::default(),
+        }
+    }
+
+    /// A renderer that keeps frames in a `MemoryBackend`.
+    pub fn headless(width: u16, height: u16) -> Self {
+        Self::with_backend(width, height, Box::new(MemoryBackend::new(width, height)))
+    }
+
+    /// The backend, if it is a `B`.
+    pub fn backend<B: Backend>(&self) -> Option<&B> {
+        let backend: &dyn Any = self.backend.as_ref();
+        backend.downcast_ref()
     }
 
     pub fn init(&mut self) -> Result<(), TerminalError> {
-        terminal::enable_raw_mode().map_err(TerminalError::RawModeError)?;
-        execute!(self.stdout, EnterAlternateScreen, cursor::Hide)
-            .map_err(TerminalError::InitError)?;
-        Ok(())
+        self.backend.init()
     }
 
     pub fn cleanup(&mut self) -> io::Result<()> {
-        execute!(self.stdout, LeaveAlternateScreen, cursor::Show, ResetColor)?;
-        terminal::disable_raw_mode()?;
-        Ok(())
+        self.backend.cleanup()
     }
 
     pub fn manual_resize(&mut self, width: u16, height: u16) -> io::Result<()> {
@@ -87,13 +204,57 @@ impl TerminalRenderer {
             let buffer_size = (width as usize) * (height as usize);
             self.buffer = vec![Cell::default(); buffer_size];
             self.last_buffer = vec![Cell::default(); buffer_size];
-            execute!(self.stdout, Clear(ClearType::All))?;
+            self.viewport = Rect::new(0, 0, width, height);
+            self.backend.resize(width, height)?;
         }
         Ok(())
     }
 
+    /// Size of the current viewport (the whole terminal unless one is set).
     pub fn get_size(&self) -> (u16, u16) {
-        (self.width, self.height)
+        (self.viewport.width, self.viewport.height)
+    }
+
+    /// Whether the current viewport is below the usable minimum.
+    pub fn is_too_small(&self) -> bool {
+        self.viewport.width < MIN_TERMINAL_WIDTH || self.viewport.height < MIN_TERMINAL_HEIGHT
+    }
+
+    /// Restrict drawing to `rect`. Coordinates passed to the render methods
+    /// become relative to its top-left corner and anything outside is clipped.
+    pub fn set_viewport(&mut self, rect: Rect) {
+        let x = rect.x.min(self.width);
+        let y = rect.y.min(self.height);
+        self.viewport = Rect::new(
+            x,
+            y,
+            rect.width.min(self.width - x),
+            rect.height.min(self.height - y),
+        );
+    }
+
+    pub fn reset_viewport(&mut self) {
+        self.viewport = Rect::new(0, 0, self.width, self.height);
+    }
+
+    /// The colors things are drawn with.
+    pub fn palette(&self) -> &Palette {
+        &self.palette
+    }
+
+    pub fn set_palette(&mut self, palette: &Palette) {
+        self.palette.clone_from(palette);
+    }
+
+    /// Buffer index for a viewport-relative position, or `None` if clipped.
+    fn cell_index(&self, x: u16, y: u16) -> Option<usize> {
+        if x >= self.viewport.width || y >= self.viewport.height {
+            return None;
+        }
+        let col = (self.viewport.x + x) as usize;
+        let row = (self.viewport.y + y) as usize;
+        let idx = row * (self.width as usize) + col;
+        (idx < self.buffer.len()).then_some(idx)
     }
 
     pub fn clear(&mut self) -> io::Result<()> {
@@ -105,30 +266,22 @@ impl TerminalRenderer {
         &mut self,
         lines: &[String],
         start_row: u16,
-        color: Color,
+        style: impl Into<Style>,
     ) -> io::Result<()> {
         let max_width = lines.iter().map(|l| l.len()).max().unwrap_or(0);
-        let start_col = if self.width as usize > max_width {
-            (self.width as usize - max_width) / 2
+        let start_col = if self.viewport.width as usize > max_width {
+            (self.viewport.width as usize - max_width) / 2
         } else {
             0
         };
-        let adjusted_color = self.capabilities.adjust_color(color);
+        let style = self.capabilities.adjust_style(style.into());
 
         for (idx, line) in lines.iter().enumerate() {
             let row = start_row + idx as u16;
-            if row < self.height {
-                for (char_idx, ch) in line.chars().enumerate() {
-                    let col = start_col as u16 + char_idx as u16;
-                    if col < self.width {
-                        let buffer_idx = (row as usize) * (self.width as usize) + (col as usize);
-                        if buffer_idx < self.buffer.len() {
-                            self.buffer[buffer_idx] = Cell {
-                                character: ch,
-                                color: adjusted_color,
-                            };
-                        }
-                    }
+            for (char_idx, ch) in line.chars().enumerate() {
+                let col = start_col as u16 + char_idx as u16;
+                if let Some(buffer_idx) = self.cell_index(col, row) {
+                    self.put(buffer_idx, ch, style);
                 }
             }
         }
@@ -141,89 +294,78 @@ impl TerminalRenderer {
         x: u16,
         y: u16,
         text: &str,
-        color: Color,
+        style: impl Into<Style>,
     ) -> io::Result<()> {
-        if y >= self.height {
+        if y >= self.viewport.height {
             return Ok(());
         }
-        let adjusted_color = self.capabilities.adjust_color(color);
+        let style = self.capabilities.adjust_style(style.into());
 
         for (idx, ch) in text.chars().enumerate() {
-            let col = x + idx as u16;
-            if col < self.width {
-                let buffer_idx = (y as usize) * (self.width as usize) + (col as usize);
-                if buffer_idx < self.buffer.len() {
-                    self.buffer[buffer_idx] = Cell {
-                        character: ch,
-                        color: adjusted_color,
-                    };
-                }
+            let col = x.saturating_add(idx as u16);
+            if let Some(buffer_idx) = self.cell_index(col, y) {
+                self.put(buffer_idx, ch, style);
             }
         }
         Ok(())
     }
 
-    pub fn render_char(&mut self, x: u16, y: u16, ch: char, color: Color) -> io::Result<()> {
-        if x < self.width && y < self.height {
-            let buffer_idx = (y as usize) * (self.width as usize) + (x as usize);
-            if buffer_idx < self.buffer.len() {
-                self.buffer[buffer_idx] = Cell {
-                    character: ch,
-                    color: self.capabilities.adjust_color(color),
-                };
-            }
+    pub fn render_char(
+        &mut self,
+        x: u16,
+        y: u16,
+        ch: char,
+        style: impl Into<Style>,
+    ) -> io::Result<()> {
+        if let Some(buffer_idx) = self.cell_index(x, y) {
+            let style = self.capabilities.adjust_style(style.into());
+            self.put(buffer_idx, ch, style);
         }
         Ok(())
     }
 
-    pub fn flash_screen(&mut self) -> io::Result<()> {
-        let flash_color = self.capabilities.adjust_color(Color::White);
-        for cell in &mut self.buffer {
-            cell.color = flash_color;
+    /// Draw a character, keeping the background already there (the sky) when
+    /// `style` has none of its own.
+    fn put(&mut self, idx: usize, ch: char, style: Style) {
+        let mut cell = Cell::new(ch, style);
+        if style.background == Color::Reset {
+            cell.background = self.buffer[idx].background;
         }
-        Ok(())
+        self.buffer[idx] = cell;
     }
 
-    pub fn flush(&mut self) -> io::Result<()> {
-        let mut current_color = Color::Reset;
-        let mut last_pos: Option<(u16, u16)> = None;
-
-        for y in 0..self.height {
-            for x in 0..self.width {
-                let idx = (y as usize) * (self.width as usize) + (x as usize);
-
-                if idx >= self.buffer.len() || idx >= self.last_buffer.len() {
-                    continue;
-                }
-
-                let cell = self.buffer[idx];
-                let last_cell = self.last_buffer[idx];
-
-                if cell != last_cell {
-                    let expected_pos = last_pos.map(|(lx, ly)| (lx + 1, ly));
-                    if expected_pos != Some((x, y)) {
-                        queue!(self.stdout, cursor::MoveTo(x, y))?;
-                    }
-
-                    if cell.color != current_color {
-                        queue!(self.stdout, SetForegroundColor(cell.color))?;
-                        current_color = cell.color;
-                    }
-
-                    queue!(self.stdout, Print(cell.character))?;
-                    last_pos = Some((x, y));
+    pub fn flash_screen(&mut self) -> io::Result<()> {
+        let flash_color = self.capabilities.adjust_color(self.palette.flash);
+        for y in 0..self.viewport.height {
+            for x in 0..self.viewport.width {
+                if let Some(idx) = self.cell_index(x, y) {
+                    self.buffer[idx].color = flash_color;
                 }
             }
         }
+        Ok(())
+    }
 
-        if current_color != Color::Reset {
-            queue!(self.stdout, ResetColor)?;
+    /// Send the cells that changed since the last flush to the backend.
+    pub fn flush(&mut self) -> io::Result<()> {
+        self.changes.clear();
+        for (idx, (cell, last_cell)) in self.buffer.iter().zip(&self.last_buffer).enumerate() {
+            if cell != last_cell {
+                let x = (idx % self.width as usize) as u16;
+                let y = (idx / self.width as usize) as u16;
+                self.changes.push((x, y, *cell));
+            }
         }
 
-        self.stdout.flush()?;
+        self.backend.draw(&self.changes)?;
         self.last_buffer.copy_from_slice(&self.buffer);
         Ok(())
     }
+
+    /// Bytes the last `flush` sent to the terminal.
+    pub fn last_flush_bytes(&self) -> usize {
+        self.backend.last_draw_bytes()
+    }
 }
 
 impl Drop for TerminalRenderer {
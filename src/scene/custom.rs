@@ -0,0 +1,542 @@
+use crate::error::ConfigError;
+use crate::render::TerminalRenderer;
+use crate::scene::ground::Ground;
+use crate::scene::{Scene, SceneAnchors, SceneFrame, WorldScene};
+use crossterm::style::Color;
+use serde::Deserialize;
+use std::collections::HashMap;
+use std::fs;
+use std::io;
+use std::ops::Range;
+use std::path::Path;
+use toml::Spanned;
+
+#[derive(Deserialize)]
+struct SceneBundle {
+    #[serde(default = "default_ground_height")]
+    ground_height: u16,
+    #[serde(default = "default_grass")]
+    grass: bool,
+    #[serde(default)]
+    palette: HashMap<String, Spanned<String>>,
+    #[serde(default)]
+    layers: Vec<LayerSpec>,
+}
+
+fn default_ground_height() -> u16 {
+    WorldScene::GROUND_HEIGHT
+}
+
+fn default_grass() -> bool {
+    true
+}
+
+fn default_frame_ticks() -> u64 {
+    10
+}
+
+fn default_layer_color() -> Spanned<String> {
+    Spanned::new(0..0, "white".to_string())
+}
+
+#[derive(Deserialize, Debug, Default, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+enum Align {
+    Left,
+    #[default]
+    Center,
+    Right,
+}
+
+#[derive(Deserialize)]
+struct LayerSpec {
+    name: String,
+    #[serde(default)]
+    align: Align,
+    #[serde(default)]
+    x: i32,
+    #[serde(default)]
+    y: i32,
+    art: Option<Spanned<String>>,
+    #[serde(default)]
+    frames: Vec<Spanned<String>>,
+    #[serde(default = "default_frame_ticks")]
+    frame_ticks: u64,
+    colors: Option<Spanned<String>>,
+    night_colors: Option<Spanned<String>>,
+    #[serde(default = "default_layer_color")]
+    color: Spanned<String>,
+    night_color: Option<Spanned<String>>,
+    #[serde(default)]
+    emitters: Vec<Spanned<(u16, u16)>>,
+}
+
+type ColorMap = Vec<Vec<Option<Color>>>;
+
+struct Layer {
+    align: Align,
+    x: i32,
+    y: i32,
+    frames: Vec<Vec<Vec<char>>>,
+    frame_ticks: u64,
+    width: u16,
+    height: u16,
+    day_colors: ColorMap,
+    night_colors: ColorMap,
+    color: Color,
+    night_color: Color,
+    emitters: Vec<(u16, u16)>,
+}
+
+impl Layer {
+    fn origin(&self, width: u16, horizon_y: u16) -> (i32, i32) {
+        let base_x = match self.align {
+            Align::Left => 0,
+            Align::Center => (width as i32 - self.width as i32) / 2,
+            Align::Right => width as i32 - self.width as i32,
+        };
+        let top_y = horizon_y as i32 - self.y - self.height as i32;
+        (base_x + self.x, top_y)
+    }
+
+    /// Whether the whole art is on screen in a scene `width` columns wide.
+    fn fits(&self, width: u16) -> bool {
+        let (left, _) = self.origin(width, 0);
+        left >= 0 && left + self.width as i32 <= width as i32
+    }
+}
+
+/// A scene drawn entirely from a user-supplied bundle in the config directory.
+pub struct CustomScene {
+    ground_height: u16,
+    grass: bool,
+    ground: Ground,
+    layers: Vec<Layer>,
+}
+
+impl CustomScene {
+    pub fn load(path: &Path, term_width: u16) -> Result<Self, ConfigError> {
+        let source = fs::read_to_string(path).map_err(|e| ConfigError::ReadError {
+            path: path.display().to_string(),
+            source: e,
+        })?;
+        Self::parse(&source, &path.display().to_string(), term_width)
+    }
+
+    pub fn parse(source: &str, path: &str, term_width: u16) -> Result<Self, ConfigError> {
+        let err = |span: Range<usize>, message: String| ConfigError::InvalidScene {
+            path: path.to_string(),
+            line: line_of(source, span.start),
+            message,
+        };
+
+        let bundle: SceneBundle = toml::from_str(source)
+            .map_err(|e| err(e.span().unwrap_or(0..0), e.message().to_string()))?;
+
+        let mut palette = HashMap::new();
+        for (key, value) in &bundle.palette {
+            let mut chars = key.chars();
+            let (Some(ch), None) = (chars.next(), chars.next()) else {
+                return Err(err(
+                    value.span(),
+                    format!("palette key {key:?} must be a single character"),
+                ));
+            };
+            let color = parse_color(value.get_ref())
+                .ok_or_else(|| err(value.span(), format!("unknown color {:?}", value.get_ref())))?;
+            palette.insert(ch, color);
+        }
+
+        let mut layers = Vec::with_capacity(bundle.layers.len());
+        for spec in &bundle.layers {
+            layers.push(Self::build_layer(source, path, spec, &palette, term_width)?);
+        }
+
+        Ok(Self {
+            ground_height: bundle.ground_height,
+            grass: bundle.grass,
+            ground: Ground,
+            layers,
+        })
+    }
+
+    fn build_layer(
+        source: &str,
+        path: &str,
+        spec: &LayerSpec,
+        palette: &HashMap<char, Color>,
+        term_width: u16,
+    ) -> Result<Layer, ConfigError> {
+        let err = |line: usize, message: String| ConfigError::InvalidScene {
+            path: path.to_string(),
+            line,
+            message: format!("layer '{}': {}", spec.name, message),
+        };
+
+        let sources: Vec<&Spanned<String>> = spec.art.iter().chain(&spec.frames).collect();
+        let Some(first) = sources.first() else {
+            return Err(err(
+                line_of(source, spec.color.span().start),
+                "needs `art` or `frames`".to_string(),
+            ));
+        };
+
+        let frames: Vec<Vec<Vec<char>>> = sources
+            .iter()
+            .map(|art| art.get_ref().lines().map(|l| l.chars().collect()).collect())
+            .collect();
+
+        let width = frames
+            .iter()
+            .flatten()
+            .map(|line| line.len())
+            .max()
+            .unwrap_or(0);
+        let height = frames.iter().map(|f| f.len()).max().unwrap_or(0);
+
+        let (widest_frame, widest_row) = frames
+            .iter()
+            .enumerate()
+            .flat_map(|(f, lines)| lines.iter().enumerate().map(move |(r, l)| (f, r, l.len())))
+            .max_by_key(|&(_, _, len)| len)
+            .map(|(f, r, _)| (f, r))
+            .unwrap_or((0, 0));
+        let widest_line = content_line(source, sources[widest_frame].span(), widest_row);
+
+        if width > term_width as usize {
+            return Err(err(
+                widest_line,
+                format!("art is {width} columns wide but the terminal is only {term_width}"),
+            ));
+        }
+
+        let layer_color = |value: &Spanned<String>| {
+            parse_color(value.get_ref()).ok_or_else(|| {
+                err(
+                    line_of(source, value.span().start),
+                    format!("unknown color {:?}", value.get_ref()),
+                )
+            })
+        };
+        let color = layer_color(&spec.color)?;
+        let night_color = match &spec.night_color {
+            Some(value) => layer_color(value)?,
+            None => color,
+        };
+
+        let color_map = |map: &Option<Spanned<String>>| -> Result<ColorMap, ConfigError> {
+            let Some(map) = map else {
+                return Ok(Vec::new());
+            };
+            let lines: Vec<&str> = map.get_ref().lines().collect();
+            if lines.len() > height {
+                return Err(err(
+                    content_line(source, map.span(), height),
+                    format!(
+                        "color map has {} rows but the art has {height}",
+                        lines.len()
+                    ),
+                ));
+            }
+
+            let mut rows = Vec::with_capacity(lines.len());
+            for (row, line) in lines.iter().enumerate() {
+                if line.chars().count() > width {
+                    return Err(err(
+                        content_line(source, map.span(), row),
+                        format!("color map row is wider than the art ({width} columns)"),
+                    ));
+                }
+                let mut cells = Vec::with_capacity(width);
+                for ch in line.chars() {
+                    if ch == ' ' {
+                        cells.push(None);
+                        continue;
+                    }
+                    let color = palette.get(&ch).copied().ok_or_else(|| {
+                        err(
+                            content_line(source, map.span(), row),
+                            format!("color map uses {ch:?} which is not in [palette]"),
+                        )
+                    })?;
+                    cells.push(Some(color));
+                }
+                rows.push(cells);
+            }
+            Ok(rows)
+        };
+
+        let day_colors = color_map(&spec.colors)?;
+        let night_colors = if spec.night_colors.is_some() {
+            color_map(&spec.night_colors)?
+        } else {
+            day_colors.clone()
+        };
+
+        let mut emitters = Vec::with_capacity(spec.emitters.len());
+        for emitter in &spec.emitters {
+            let (ex, ey) = *emitter.get_ref();
+            if ex as usize >= width || ey as usize >= height {
+                return Err(err(
+                    line_of(source, emitter.span().start),
+                    format!("emitter ({ex}, {ey}) is outside the {width}x{height} art"),
+                ));
+            }
+            emitters.push((ex, ey));
+        }
+
+        let layer = Layer {
+            align: spec.align,
+            x: spec.x,
+            y: spec.y,
+            frames,
+            frame_ticks: spec.frame_ticks.max(1),
+            width: width as u16,
+            height: height as u16,
+            day_colors,
+            night_colors,
+            color,
+            night_color,
+            emitters,
+        };
+
+        if !layer.fits(term_width) {
+            return Err(err(
+                content_line(source, first.span(), 0),
+                format!(
+                    "art does not fit in a {term_width} column terminal at x = {}",
+                    spec.x
+                ),
+            ));
+        }
+
+        Ok(layer)
+    }
+
+    fn horizon_y(&self, height: u16) -> u16 {
+        height.saturating_sub(self.ground_height)
+    }
+}
+
+impl Scene for CustomScene {
+    fn fits(&self, width: u16) -> bool {
+        self.layers.iter().all(|layer| layer.fits(width))
+    }
+
+    fn render(&mut self, renderer: &mut TerminalRenderer, frame: &SceneFrame) -> io::Result<()> {
+        let horizon_y = self.horizon_y(frame.height);
+        let is_day = frame.conditions.is_day;
+
+        if self.grass {
+            self.ground
+                .render(renderer, frame.width, self.ground_height, horizon_y)?;
+        }
+
+        for layer in &self.layers {
+            let (origin_x, origin_y) = layer.origin(frame.width, horizon_y);
+            let art = &layer.frames[(frame.tick / layer.frame_ticks) as usize % layer.frames.len()];
+            let (colors, default_color) = if is_day {
+                (&layer.day_colors, layer.color)
+            } else {
+                (&layer.night_colors, layer.night_color)
+            };
+
+            for (row, line) in art.iter().enumerate() {
+                let y = origin_y + row as i32;
+                if y < 0 || y >= frame.height as i32 {
+                    continue;
+                }
+                for (col, &ch) in line.iter().enumerate() {
+                    let x = origin_x + col as i32;
+                    if ch == ' ' || x < 0 || x >= frame.width as i32 {
+                        continue;
+                    }
+                    let color = colors
+                        .get(row)
+                        .and_then(|cells| cells.get(col))
+                        .copied()
+                        .flatten()
+                        .unwrap_or(default_color);
+                    renderer.render_char(x as u16, y as u16, ch, color)?;
+                }
+            }
+        }
+
+        Ok(())
+    }
+
+    fn anchors(&self, width: u16, height: u16) -> SceneAnchors {
+        let horizon_y = self.horizon_y(height);
+        let mut smoke_emitters = Vec::new();
+
+        for layer in &self.layers {
+            let (origin_x, origin_y) = layer.origin(width, horizon_y);
+            for &(ex, ey) in &layer.emitters {
+                let x = origin_x + ex as i32;
+                let y = origin_y + ey as i32;
+                if x >= 0 && y >= 0 && x < width as i32 {
+                    smoke_emitters.push((x as u16, y as u16));
+                }
+            }
+        }
+
+        SceneAnchors {
+            horizon_y,
+            smoke_emitters,
+        }
+    }
+}
+
+/// Parse a color name (`"dark_red"`, `"grey"`, ...) or a `"#rrggbb"` hex value.
+pub fn parse_color(value: &str) -> Option<Color> {
+    let value = value.trim();
+    if let Some(hex) = value.strip_prefix('#') {
+        // Checked before slicing: a multi-byte character would split mid-char
+        if hex.len() != 6 || !hex.is_ascii() {
+            return None;
+        }
+        let channel = |i: usize| u8::from_str_radix(&hex[i..i + 2], 16).ok();
+        return Some(Color::Rgb {
+            r: channel(0)?,
+            g: channel(2)?,
+            b: channel(4)?,
+        });
+    }
+
+    let color = match value.to_lowercase().replace('-', "_").as_str() {
+        "reset" | "default" => Color::Reset,
+        "black" => Color::Black,
+        "dark_grey" | "dark_gray" => Color::DarkGrey,
+        "red" => Color::Red,
+        "dark_red" => Color::DarkRed,
+        "green" => Color::Green,
+        "dark_green" => Color::DarkGreen,
+        "yellow" => Color::Yellow,
+        "dark_yellow" => Color::DarkYellow,
+        "blue" => Color::Blue,
+        "dark_blue" => Color::DarkBlue,
+        "magenta" => Color::Magenta,
+        "dark_magenta" => Color::DarkMagenta,
+        "cyan" => Color::Cyan,
+        "dark_cyan" => Color::DarkCyan,
+        "white" => Color::White,
+        "grey" | "gray" => Color::Grey,
+        _ => return None,
+    };
+    Some(color)
+}
+
+fn line_of(source: &str, offset: usize) -> usize {
+    source[..offset.min(source.len())].matches('\n').count() + 1
+}
+
+/// Line number of `row` within a (possibly multi-line) string value.
+fn content_line(source: &str, span: Range<usize>, row: usize) -> usize {
+    let start = span.start.min(source.len());
+    let raw = &source[start..];
+    // TOML trims the newline that directly follows an opening triple quote
+    let skips_newline = (raw.starts_with("'''") || raw.starts_with("\"\"\""))
+        && (raw[3..].starts_with('\n') || raw[3..].starts_with("\r\n"));
+    line_of(source, start) + row + skips_newline as usize
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const VALID: &str = r##"
+[palette]
+w = "#d2b48c"
+r = "dark_red"
+
+[[layers]]
+name = "hut"
+align = "center"
+art = '''
+  /\
+ /  \
+ |[]|
+'''
+colors = '''
+  rr
+ rrrr
+ w  w
+'''
+emitters = [[2, 0]]
+"##;
+
+    fn error_line(result: Result<CustomScene, ConfigError>) -> usize {
+        match result {
+            Err(ConfigError::InvalidScene { line, .. }) => line,
+            Err(e) => panic!("unexpected error: {e}"),
+            Ok(_) => panic!("expected an error"),
+        }
+    }
+
+    #[test]
+    fn test_parse_valid_bundle() {
+        let scene = CustomScene::parse(VALID, "hut.toml", 80).unwrap();
+        assert_eq!(scene.layers.len(), 1);
+        assert_eq!(scene.layers[0].width, 5);
+        assert_eq!(scene.layers[0].height, 3);
+
+        let anchors = scene.anchors(80, 30);
+        assert_eq!(anchors.horizon_y, 30 - WorldScene::GROUND_HEIGHT);
+        // Centered 5-wide art starts at column 37; emitter is 2 columns in
+        assert_eq!(anchors.smoke_emitters, vec![(39, anchors.horizon_y - 3)]);
+    }
+
+    #[test]
+    fn test_fits_follows_width() {
+        let source = VALID.replace("align = \"center\"", "align = \"left\"\nx = 30");
+        let scene = CustomScene::parse(&source, "hut.toml", 80).unwrap();
+        assert!(scene.fits(80));
+        assert!(scene.fits(35));
+        assert!(!scene.fits(34));
+    }
+
+    #[test]
+    fn test_unknown_palette_char_reports_line() {
+        let source = VALID.replace(" w  w", " w  x");
+        assert_eq!(error_line(CustomScene::parse(&source, "hut.toml", 80)), 17);
+    }
+
+    #[test]
+    fn test_art_wider_than_terminal() {
+        let source = VALID.replace(" /  \\", " /                    \\");
+        assert_eq!(error_line(CustomScene::parse(&source, "hut.toml", 10)), 11);
+    }
+
+    #[test]
+    fn test_emitter_outside_art() {
+        let source = VALID.replace("[[2, 0]]", "[[9, 0]]");
+        assert_eq!(error_line(CustomScene::parse(&source, "hut.toml", 80)), 19);
+    }
+
+    #[test]
+    fn test_toml_syntax_error_reports_line() {
+        let source = "ground_height = 7\n[[layers]\n";
+        assert_eq!(error_line(CustomScene::parse(source, "bad.toml", 80)), 2);
+    }
+
+    #[test]
+    fn test_parse_color() {
+        assert_eq!(parse_color("dark_red"), Some(Color::DarkRed));
+        assert_eq!(
+            parse_color("#ff8000"),
+            Some(Color::Rgb {
+                r: 255,
+                g: 128,
+                b: 0
+            })
+        );
+        assert_eq!(parse_color("#ff80"), None);
+        assert_eq!(parse_color("chartreuse"), None);
+    }
+
+    #[test]
+    fn test_parse_color_rejects_non_ascii_hex() {
+        assert_eq!(parse_color("#aébcd"), None);
+        assert_eq!(parse_color("#ff00é"), None);
+    }
+}
@@ -1,4 +1,5 @@
 use crate::render::TerminalRenderer;
+use crate::theme::Palette;
 use crossterm::style::Color;
 use std::io;
 
@@ -10,7 +11,6 @@ pub struct DecorationRenderConfig {
     pub house_x: u16,
     pub house_width: u16,
     pub width: u16,
-    pub is_day: bool,
 }
 
 impl Decorations {
@@ -24,7 +24,7 @@ impl Decorations {
         config: &DecorationRenderConfig,
     ) -> io::Result<()> {
         // Render Tree (Left of house)
-        let (tree_lines, tree_color) = self.get_tree(config.is_day);
+        let (tree_lines, tree_color) = self.get_tree(renderer.palette());
         let tree_height = tree_lines.len() as u16;
         let tree_y = config.horizon_y.saturating_sub(tree_height);
         let tree_x = config.house_x.saturating_sub(20);
@@ -45,7 +45,7 @@ impl Decorations {
         }
 
         // Render Fence (Right of house)
-        let (fence_lines, fence_color) = self.get_fence(config.is_day);
+        let (fence_lines, fence_color) = self.get_fence(renderer.palette());
         let fence_height = fence_lines.len() as u16;
         let fence_y = config.horizon_y.saturating_sub(fence_height); // Sitting on ground
         let fence_x = config.house_x + config.house_width + 2; // Slight gap
@@ -66,7 +66,7 @@ impl Decorations {
         }
 
         // Render Mailbox (On ground top level, left of tree)
-        let (mailbox_lines, mailbox_color) = self.get_mailbox(config.is_day);
+        let (mailbox_lines, mailbox_color) = self.get_mailbox(renderer.palette());
         let mailbox_height = mailbox_lines.len() as u16;
         let mailbox_x = tree_x.saturating_sub(10); // Left of tree
         let mailbox_y = config.horizon_y.saturating_sub(mailbox_height); // On ground top
@@ -88,7 +88,7 @@ impl Decorations {
 
         // Render Second Tree (Right of house, only if terminal is wide enough)
         if config.width > 120 {
-            let (pine_lines, pine_color) = self.get_pine_tree(config.is_day);
+            let (pine_lines, pine_color) = self.get_pine_tree(renderer.palette());
             let pine_height = pine_lines.len() as u16;
             let pine_x = config.house_x + config.house_width + 18; // 18 chars right of house
             let pine_y = config.horizon_y.saturating_sub(pine_height);
@@ -113,7 +113,7 @@ impl Decorations {
         Ok(())
     }
 
-    fn get_tree(&self, is_day: bool) -> (Vec<&'static str>, Color) {
+    pub fn get_tree(&self, palette: &Palette) -> (Vec<&'static str>, Color) {
         (
             vec![
                 "      ####      ",
@@ -122,29 +122,19 @@ impl Decorations {
                 "    ########    ",
                 "      _||_      ",
             ],
-            if is_day {
-                Color::DarkGreen
-            } else {
-                Color::Rgb { r: 0, g: 50, b: 0 }
-            },
+            palette.foliage,
         )
     }
 
-    fn get_fence(&self, is_day: bool) -> (Vec<&'static str>, Color) {
-        (
-            vec!["|--|--|--|--|", "|  |  |  |  |"],
-            if is_day { Color::White } else { Color::Grey },
-        )
+    pub fn get_fence(&self, palette: &Palette) -> (Vec<&'static str>, Color) {
+        (vec!["|--|--|--|--|", "|  |  |  |  |"], palette.fence)
     }
 
-    fn get_mailbox(&self, is_day: bool) -> (Vec<&'static str>, Color) {
-        (
-            vec![" ___ ", "|___|", "  |  "],
-            if is_day { Color::Blue } else { Color::DarkBlue },
-        )
+    fn get_mailbox(&self, palette: &Palette) -> (Vec<&'static str>, Color) {
+        (vec![" ___ ", "|___|", "  |  "], palette.mailbox)
     }
 
-    fn get_pine_tree(&self, is_day: bool) -> (Vec<&'static str>, Color) {
+    pub fn get_pine_tree(&self, palette: &Palette) -> (Vec<&'static str>, Color) {
         (
             vec![
                 "    *    ",
@@ -153,11 +143,7 @@ impl Decorations {
                 " ******* ",
                 "   |||   ",
             ],
-            if is_day {
-                Color::DarkGreen
-            } else {
-                Color::Rgb { r: 0, g: 50, b: 0 }
-            },
+            palette.foliage,
         )
     }
 }
@@ -1,5 +1,4 @@
 use crate::render::TerminalRenderer;
-use crossterm::style::Color;
 use std::io;
 
 #[derive(Default)]
@@ -12,41 +11,14 @@ impl Ground {
         width: u16,
         height: u16,
         y_start: u16,
-        is_day: bool,
     ) -> io::Result<()> {
         let width = width as usize;
         let height = height as usize;
 
-        let grass_colors = if is_day {
-            [Color::Green, Color::DarkGreen]
-        } else {
-            [Color::DarkGreen, Color::Rgb { r: 0, g: 50, b: 0 }]
-        };
-
-        let flower_colors = if is_day {
-            vec![Color::Magenta, Color::Red, Color::Cyan, Color::Yellow]
-        } else {
-            vec![
-                Color::DarkMagenta,
-                Color::DarkRed,
-                Color::Blue,
-                Color::DarkYellow,
-            ]
-        };
-
-        let soil_color = if is_day {
-            Color::Rgb {
-                r: 101,
-                g: 67,
-                b: 33,
-            }
-        } else {
-            Color::Rgb {
-                r: 60,
-                g: 40,
-                b: 20,
-            }
-        };
+        let palette = renderer.palette();
+        let grass_colors = [palette.grass, palette.grass_dark];
+        let flower_colors = palette.flowers;
+        let soil_color = palette.soil;
 
         // Simple seeded RNG for deterministic decoration positions
         fn pseudo_rand(x: usize, y: usize) -> u32 {
@@ -1,17 +1,112 @@
-use crate::render::TerminalRenderer;
+use crate::render::{Rect, TerminalRenderer};
+use crate::scene::SceneClock;
+use crate::theme::Palette;
 use crossterm::style::Color;
 use std::io;
 
-const WOOD_COLOR: Color = Color::Rgb {
-    r: 210,
-    g: 180,
-    b: 140,
-};
-const DOOR_COLOR: Color = Color::Rgb {
-    r: 139,
-    g: 69,
-    b: 19,
-};
+// Minutes after midnight (wrapping past 24:00) at which each window goes dark,
+// indexed in reading order. Staggered so lights go out one room at a time.
+const LIGHTS_OUT_MINUTES: [u32; 8] = [
+    23 * 60 + 40,
+    22 * 60 + 30,
+    24 * 60 + 15,
+    21 * 60 + 45,
+    23 * 60,
+    22 * 60 + 50,
+    24 * 60 + 50,
+    22 * 60 + 10,
+];
+const EVENING_START_MINUTE: u32 = 12 * 60;
+const MINUTES_PER_DAY: u32 = 24 * 60;
+const FLICKER_CHANCE: u32 = 400;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum WindowState {
+    Lit,
+    Flicker,
+    Dark,
+}
+
+/// Snapshot of the house lighting for a single frame.
+#[derive(Debug, Clone, Copy)]
+pub struct HouseLights {
+    minute_of_day: u32,
+    tick: u64,
+    /// Set when the user has flipped the lights by hand.
+    switch: Option<bool>,
+}
+
+impl HouseLights {
+    pub fn new(minute_of_day: u32, tick: u64) -> Self {
+        Self {
+            minute_of_day: minute_of_day % MINUTES_PER_DAY,
+            tick,
+            switch: None,
+        }
+    }
+
+    /// Force every window on or off instead of following the clock.
+    pub fn switched(mut self, on: Option<bool>) -> Self {
+        self.switch = on;
+        self
+    }
+
+    /// Whether any window is lit by the evening schedule.
+    pub fn any_lit(&self) -> bool {
+        (0..LIGHTS_OUT_MINUTES.len()).any(|i| self.window_state(i) != WindowState::Dark)
+    }
+
+    /// The lights for `clock`'s time of day.
+    pub fn at(clock: SceneClock, tick: u64) -> Self {
+        Self::new(clock.minute_of_day(), tick)
+    }
+
+    pub fn window_state(&self, index: usize) -> WindowState {
+        match self.switch {
+            Some(false) => return WindowState::Dark,
+            Some(true) => return WindowState::Lit,
+            None => {}
+        }
+
+        // Treat the small hours as a continuation of the previous evening
+        let night_minute = if self.minute_of_day < EVENING_START_MINUTE {
+            self.minute_of_day + MINUTES_PER_DAY
+        } else {
+            self.minute_of_day
+        };
+
+        let lights_out = LIGHTS_OUT_MINUTES[index % LIGHTS_OUT_MINUTES.len()];
+        if night_minute >= lights_out {
+            return WindowState::Dark;
+        }
+
+        if flicker_roll(index, self.tick) == 0 {
+            WindowState::Flicker
+        } else {
+            WindowState::Lit
+        }
+    }
+
+    pub fn window_color(&self, index: usize, is_day: bool, palette: &Palette) -> Color {
+        if is_day && self.switch != Some(true) {
+            return palette.window_day;
+        }
+        match self.window_state(index) {
+            WindowState::Lit => palette.window_lit,
+            WindowState::Flicker => palette.window_flicker,
+            WindowState::Dark => palette.window_dark,
+        }
+    }
+}
+
+fn flicker_roll(index: usize, tick: u64) -> u32 {
+    // Cheap hash so flicker is stable for a few frames instead of strobing
+    let bucket = tick / 3;
+    let mut h =
+        bucket.wrapping_mul(0x9E37_79B9_7F4A_7C15) ^ (index as u64).wrapping_mul(0xC2B2_AE35);
+    h ^= h >> 29;
+    (h % FLICKER_CHANCE as u64) as u32
+}
 
 #[derive(Default)]
 pub struct House;
@@ -29,6 +124,12 @@ impl House {
         Self::WIDTH
     }
 
+    /// The walls and roof of a house drawn at (x, y), without the smoke
+    /// above it or the fence beside it.
+    pub fn body(x: u16, y: u16) -> Rect {
+        Rect::new(x + 3, y + 3, 30, 9)
+    }
+
     pub fn get_ascii(&self) -> Vec<&'static str> {
         vec![
             "          (                  ",
@@ -53,24 +154,15 @@ impl House {
         x: u16,
         y: u16,
         is_day: bool,
+        lights: &HouseLights,
     ) -> io::Result<()> {
         let ascii = self.get_ascii();
+        let mut window_index = 0;
 
-        let wood_color = if is_day {
-            WOOD_COLOR
-        } else {
-            Color::Rgb {
-                r: 100,
-                g: 70,
-                b: 50,
-            }
-        };
-        let roof_color = if is_day {
-            Color::DarkRed
-        } else {
-            Color::DarkMagenta
-        };
-        let window_color = if is_day { Color::Cyan } else { Color::Yellow };
+        let palette = renderer.palette();
+        let (wood_color, roof_color, door_color) = (palette.wood, palette.roof, palette.door);
+        let (stone, trim, grass) = (palette.stone, palette.trim, palette.grass);
+        let accent = palette.accent;
 
         for (i, line) in ascii.iter().enumerate() {
             let row = y + i as u16;
@@ -81,9 +173,9 @@ impl House {
                         if ch != ' ' {
                             let col = x + j as u16;
                             let color = if i < 4 && (ch == '(' || ch == ')' || ch == '_') {
-                                Color::DarkGrey
+                                trim
                             } else if i < 4 {
-                                Color::Grey
+                                stone
                             } else {
                                 roof_color
                             };
@@ -95,22 +187,30 @@ impl House {
                     renderer.render_line_colored(x, row, line, roof_color)?;
                 }
                 8..=10 => {
+                    let mut in_window = false;
                     for (j, ch) in line.chars().enumerate() {
+                        if ch == '[' {
+                            in_window = true;
+                        }
                         if ch != ' ' {
                             let col = x + j as u16;
-                            let color = if ch == '[' || ch == ']' {
-                                window_color
+                            let color = if in_window {
+                                lights.window_color(window_index, is_day, renderer.palette())
                             } else if ch == '|' || ch == '.' || ch == '_' {
                                 wood_color
                             } else if ch == '(' || ch == ')' {
-                                DOOR_COLOR
+                                door_color
                             } else if ch == '=' {
-                                Color::DarkGrey
+                                trim
                             } else {
                                 wood_color
                             };
                             renderer.render_char(col, row, ch, color)?;
                         }
+                        if ch == ']' {
+                            in_window = false;
+                            window_index += 1;
+                        }
                     }
                 }
                 11 => {
@@ -118,9 +218,9 @@ impl House {
                         if ch != ' ' {
                             let col = x + j as u16;
                             let color = if ch == '=' || ch == '|' {
-                                Color::DarkGrey
+                                trim
                             } else if ch == '(' || ch == ')' {
-                                DOOR_COLOR
+                                door_color
                             } else {
                                 wood_color
                             };
@@ -133,13 +233,9 @@ impl House {
                         if ch != ' ' {
                             let col = x + j as u16;
                             let color = if ch == '^' {
-                                if is_day {
-                                    Color::Green
-                                } else {
-                                    Color::DarkGreen
-                                }
+                                grass
                             } else if ch == '=' {
-                                Color::DarkGrey
+                                trim
                             } else {
                                 Color::Reset
                             };
@@ -148,10 +244,153 @@ impl House {
                     }
                 }
                 _ => {
-                    renderer.render_line_colored(x, row, line, Color::Yellow)?;
+                    renderer.render_line_colored(x, row, line, accent)?;
+                }
+            }
+        }
+        Ok(())
+    }
+}
+
+/// A small single-storey house used in compact layouts and as a neighbour
+/// in wide ones.
+#[derive(Default)]
+pub struct Cottage;
+
+impl Cottage {
+    pub const WIDTH: u16 = 16;
+    pub const HEIGHT: u16 = 6;
+    pub const CHIMNEY_X_OFFSET: u16 = 3;
+    const ROOF_ROWS: usize = 3;
+
+    pub fn get_ascii(&self) -> [&'static str; 6] {
+        [
+            "  _||_________  ",
+            " /~~~~~~~~~~~~\\ ",
+            "/~~~~~~~~~~~~~~\\",
+            " | []  __  [] | ",
+            " |     | .|   | ",
+            " |_____|__|___| ",
+        ]
+    }
+
+    /// Draw the cottage. `first_window` offsets the lighting schedule so that
+    /// neighbouring cottages don't switch off in lockstep.
+    pub fn render(
+        &self,
+        renderer: &mut TerminalRenderer,
+        x: u16,
+        y: u16,
+        is_day: bool,
+        lights: &HouseLights,
+        first_window: usize,
+    ) -> io::Result<()> {
+        let palette = renderer.palette();
+        let (wood_color, roof_color, door_color) = (palette.wood, palette.roof, palette.door);
+        let trim = palette.trim;
+
+        let mut window_index = first_window;
+        for (i, line) in self.get_ascii().iter().enumerate() {
+            let row = y + i as u16;
+            let mut in_window = false;
+
+            for (j, ch) in line.chars().enumerate() {
+                if ch == '[' {
+                    in_window = true;
+                }
+                if ch != ' ' {
+                    let color = if in_window {
+                        lights.window_color(window_index, is_day, renderer.palette())
+                    } else if i == 0 && ch == '|' {
+                        trim
+                    } else if i < Self::ROOF_ROWS {
+                        roof_color
+                    } else if i >= Self::ROOF_ROWS && (7..=10).contains(&j) {
+                        door_color
+                    } else {
+                        wood_color
+                    };
+                    renderer.render_char(x + j as u16, row, ch, color)?;
+                }
+                if ch == ']' {
+                    in_window = false;
+                    window_index += 1;
                 }
             }
         }
+
         Ok(())
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn lit_count(lights: &HouseLights) -> usize {
+        (0..LIGHTS_OUT_MINUTES.len())
+            .filter(|&i| lights.window_state(i) != WindowState::Dark)
+            .count()
+    }
+
+    #[test]
+    fn test_all_windows_lit_in_early_evening() {
+        let lights = HouseLights::new(19 * 60, 1);
+        assert_eq!(lit_count(&lights), LIGHTS_OUT_MINUTES.len());
+    }
+
+    #[test]
+    fn test_windows_switch_off_one_by_one() {
+        let mut previous = LIGHTS_OUT_MINUTES.len();
+        for minute in (21 * 60..25 * 60).step_by(5) {
+            let count = lit_count(&HouseLights::new(minute, 1));
+            assert!(count <= previous, "lights came back on at minute {minute}");
+            assert!(previous - count <= 1, "several lights went out at once");
+            previous = count;
+        }
+        assert_eq!(previous, 0);
+    }
+
+    #[test]
+    fn test_switch_overrides_schedule() {
+        let night = HouseLights::new(4 * 60, 1);
+        assert!(!night.any_lit());
+        assert_eq!(
+            lit_count(&night.switched(Some(true))),
+            LIGHTS_OUT_MINUTES.len()
+        );
+        assert_eq!(
+            lit_count(&HouseLights::new(19 * 60, 1).switched(Some(false))),
+            0
+        );
+    }
+
+    #[test]
+    fn test_windows_dark_before_dawn() {
+        let lights = HouseLights::new(4 * 60, 1);
+        assert_eq!(lit_count(&lights), 0);
+    }
+
+    #[test]
+    fn test_get_ascii_window_count_matches_schedule() {
+        let windows: usize = House
+            .get_ascii()
+            .iter()
+            .map(|line| line.matches('[').count())
+            .sum();
+        assert_eq!(windows, LIGHTS_OUT_MINUTES.len());
+    }
+
+    #[test]
+    fn test_cottage_ascii_matches_dimensions() {
+        let ascii = Cottage.get_ascii();
+        assert_eq!(ascii.len() as u16, Cottage::HEIGHT);
+        for line in ascii {
+            assert_eq!(line.chars().count() as u16, Cottage::WIDTH);
+        }
+        assert_eq!(
+            ascii[0].chars().nth(Cottage::CHIMNEY_X_OFFSET as usize),
+            Some('|')
+        );
+    }
+}
@@ -1,81 +1,282 @@
+pub mod custom;
 pub mod decorations;
 pub mod ground;
 pub mod house;
+pub mod themes;
 
-use crate::render::TerminalRenderer;
+use crate::render::{Rect, TerminalRenderer};
 use crate::weather::WeatherConditions;
+use crossterm::style::Color;
+use house::HouseLights;
 use std::io;
 
+pub use themes::SceneTheme;
+
+/// Points in the scene that the animation layer attaches effects to.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct SceneAnchors {
+    /// First row of the ground; sky effects stay above it.
+    pub horizon_y: u16,
+    /// Positions where chimney-style smoke is emitted.
+    pub smoke_emitters: Vec<(u16, u16)>,
+}
+
+/// How much scenery a terminal of a given size can hold.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum LayoutTier {
+    /// Small panes: a cottage instead of the full house and a shallow ground.
+    Compact,
+    Normal,
+    /// Very wide terminals: extra houses and trees fill the sides.
+    Wide,
+}
+
+impl LayoutTier {
+    const NORMAL_MIN_WIDTH: u16 = 70;
+    const NORMAL_MIN_HEIGHT: u16 = 20;
+    const WIDE_MIN_WIDTH: u16 = 160;
+    const COMPACT_GROUND_HEIGHT: u16 = 3;
+
+    pub fn for_size(width: u16, height: u16) -> Self {
+        if width < Self::NORMAL_MIN_WIDTH || height < Self::NORMAL_MIN_HEIGHT {
+            Self::Compact
+        } else if width >= Self::WIDE_MIN_WIDTH {
+            Self::Wide
+        } else {
+            Self::Normal
+        }
+    }
+
+    pub fn ground_height(self) -> u16 {
+        match self {
+            Self::Compact => Self::COMPACT_GROUND_HEIGHT,
+            Self::Normal | Self::Wide => WorldScene::GROUND_HEIGHT,
+        }
+    }
+
+    pub fn horizon_y(self, height: u16) -> u16 {
+        height.saturating_sub(self.ground_height())
+    }
+}
+
+/// Where a scene reads the time of day that drives the house lights.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum SceneClock {
+    /// The system's local time.
+    #[default]
+    Local,
+    /// A fixed number of minutes after midnight. Seeded runs use this so the
+    /// same seed draws the same frames whenever and wherever it runs.
+    Fixed(u32),
+}
+
+impl SceneClock {
+    /// An evening where every window is still lit.
+    pub const SEEDED: Self = Self::Fixed(20 * 60);
+
+    pub fn minute_of_day(self) -> u32 {
+        match self {
+            Self::Local => {
+                use chrono::{Local, Timelike};
+
+                let now = Local::now();
+                now.hour() * 60 + now.minute()
+            }
+            Self::Fixed(minute) => minute,
+        }
+    }
+}
+
+/// Per-frame inputs shared by every scene theme.
+pub struct SceneFrame<'a> {
+    pub width: u16,
+    pub height: u16,
+    pub tier: LayoutTier,
+    pub conditions: &'a WeatherConditions,
+    pub lights: HouseLights,
+    pub tick: u64,
+}
+
+impl SceneFrame<'_> {
+    pub fn horizon_y(&self) -> u16 {
+        self.tier.horizon_y(self.height)
+    }
+
+    pub fn ground_height(&self) -> u16 {
+        self.tier.ground_height()
+    }
+}
+
+pub trait Scene {
+    fn render(&mut self, renderer: &mut TerminalRenderer, frame: &SceneFrame) -> io::Result<()>;
+
+    fn anchors(&self, width: u16, height: u16) -> SceneAnchors;
+
+    /// Whether the scene can be drawn `width` columns wide. Built-in themes
+    /// adapt to any size; custom art has a fixed width.
+    fn fits(&self, _width: u16) -> bool {
+        true
+    }
+
+    /// Areas (buildings with lit windows) that switch the lights when clicked.
+    fn light_switches(&self, _width: u16, _height: u16) -> Vec<Rect> {
+        Vec::new()
+    }
+}
+
 pub struct WorldScene {
-    house: house::House,
-    ground: ground::Ground,
-    decorations: decorations::Decorations,
+    theme: Box<dyn Scene>,
     width: u16,
     height: u16,
+    frame: u64,
+    clock: SceneClock,
+    lights_switch: Option<bool>,
 }
 
 impl WorldScene {
     pub const GROUND_HEIGHT: u16 = 7;
 
-    pub fn new(width: u16, height: u16) -> Self {
-        let house = house::House;
-        let ground = ground::Ground;
-        let decorations = decorations::Decorations::new();
-
+    pub fn new(width: u16, height: u16, theme: Box<dyn Scene>) -> Self {
         Self {
-            house,
-            ground,
-            decorations,
+            theme,
             width,
             height,
+            frame: 0,
+            clock: SceneClock::Local,
+            lights_switch: None,
         }
     }
 
+    /// Read the time of day for the house lights from `clock`.
+    pub fn with_clock(mut self, clock: SceneClock) -> Self {
+        self.clock = clock;
+        self
+    }
+
     pub fn update_size(&mut self, width: u16, height: u16) {
         self.width = width;
         self.height = height;
     }
 
+    pub fn anchors(&self) -> SceneAnchors {
+        self.theme.anchors(self.width, self.height)
+    }
+
+    /// Whether the scene fits its current size.
+    pub fn fits(&self) -> bool {
+        self.theme.fits(self.width)
+    }
+
+    /// Handle a click at (x, y), relative to the scene. Returns true if it
+    /// hit something, which for now means flipping the house lights.
+    pub fn click(&mut self, x: u16, y: u16) -> bool {
+        let hit = self
+            .theme
+            .light_switches(self.width, self.height)
+            .iter()
+            .any(|rect| rect.contains(x, y));
+        if hit {
+            let lit = match self.lights_switch {
+                Some(on) => on,
+                None => HouseLights::at(self.clock, self.frame).any_lit(),
+            };
+            self.lights_switch = Some(!lit);
+        }
+        hit
+    }
+
+    /// Move scene animations (waves, flickering windows, ...) forward.
+    pub fn advance(&mut self, ticks: u32) {
+        self.frame = self.frame.wrapping_add(ticks as u64);
+    }
+
     pub fn render(
-        &self,
+        &mut self,
         renderer: &mut TerminalRenderer,
         conditions: &WeatherConditions,
     ) -> io::Result<()> {
-        let horizon_y = self.height.saturating_sub(Self::GROUND_HEIGHT);
-
-        // House position
-        let house_width = self.house.width();
-        let house_height = self.house.height();
-        let house_x = (self.width / 2).saturating_sub(house_width / 2);
-        let house_y = horizon_y.saturating_sub(house_height);
-
-        // Door/Path alignment
-
-        // Render Ground
-        self.ground.render(
-            renderer,
-            self.width,
-            Self::GROUND_HEIGHT,
-            horizon_y,
-            conditions.is_day,
-        )?;
-
-        // Render House
-        self.house
-            .render(renderer, house_x, house_y, conditions.is_day)?;
-
-        // Render Decorations
-        self.decorations.render(
-            renderer,
-            &crate::scene::decorations::DecorationRenderConfig {
-                horizon_y,
-                house_x,
-                house_width,
-                width: self.width,
-                is_day: conditions.is_day,
-            },
-        )?;
-
-        Ok(())
+        let frame = SceneFrame {
+            width: self.width,
+            height: self.height,
+            tier: LayoutTier::for_size(self.width, self.height),
+            conditions,
+            lights: HouseLights::at(self.clock, self.frame).switched(self.lights_switch),
+            tick: self.frame,
+        };
+
+        self.theme.render(renderer, &frame)
+    }
+}
+
+/// Draw a block of ASCII art with a single color, treating spaces as transparent.
+pub(crate) fn render_art(
+    renderer: &mut TerminalRenderer,
+    x: u16,
+    y: u16,
+    lines: &[&str],
+    color: Color,
+) -> io::Result<()> {
+    for (i, line) in lines.iter().enumerate() {
+        for (j, ch) in line.chars().enumerate() {
+            if ch != ' ' {
+                renderer.render_char(x + j as u16, y + i as u16, ch, color)?;
+            }
+        }
+    }
+    Ok(())
+}
+
+/// Simple hash for deterministic scenery placement.
+pub(crate) fn pseudo_rand(a: u64, b: u64) -> u32 {
+    let mut h = a.wrapping_mul(0x9E37_79B9_7F4A_7C15) ^ b.wrapping_mul(0xC2B2_AE3D_27D4_EB4F);
+    h ^= h >> 31;
+    (h % 1000) as u32
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_layout_tier_for_size() {
+        assert_eq!(LayoutTier::for_size(40, 12), LayoutTier::Compact);
+        assert_eq!(LayoutTier::for_size(120, 18), LayoutTier::Compact);
+        assert_eq!(LayoutTier::for_size(70, 20), LayoutTier::Normal);
+        assert_eq!(LayoutTier::for_size(159, 40), LayoutTier::Normal);
+        assert_eq!(LayoutTier::for_size(160, 40), LayoutTier::Wide);
+    }
+
+    #[test]
+    fn test_click_on_house_flips_lights() {
+        let mut scene = WorldScene::new(100, 30, Box::new(themes::house::HouseScene::new()));
+        assert!(!scene.click(1, 1));
+        assert_eq!(scene.lights_switch, None);
+
+        assert!(scene.click(50, 20));
+        let first = scene.lights_switch.unwrap();
+        scene.click(50, 20);
+        assert_eq!(scene.lights_switch, Some(!first));
+    }
+
+    #[test]
+    fn test_fixed_clock_sets_lights() {
+        let switched_at = |minute| {
+            let mut scene = WorldScene::new(100, 30, Box::new(themes::house::HouseScene::new()))
+                .with_clock(SceneClock::Fixed(minute));
+            scene.click(50, 20);
+            scene.lights_switch
+        };
+        // Clicking flips whatever the clock says, so the result follows the clock
+        assert_eq!(switched_at(20 * 60), Some(false));
+        assert_eq!(switched_at(4 * 60), Some(true));
+    }
+
+    #[test]
+    fn test_compact_ground_is_shallower() {
+        assert!(LayoutTier::Compact.ground_height() < LayoutTier::Normal.ground_height());
+        assert_eq!(
+            LayoutTier::Wide.horizon_y(50),
+            50 - WorldScene::GROUND_HEIGHT
+        );
     }
 }
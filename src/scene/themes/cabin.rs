@@ -0,0 +1,196 @@
+use crate::render::TerminalRenderer;
+use crate::scene::decorations::Decorations;
+use crate::scene::ground::Ground;
+use crate::scene::{LayoutTier, Scene, SceneAnchors, SceneFrame, pseudo_rand, render_art};
+use std::io;
+
+const CABIN: [&str; 8] = [
+    "     ||                   ",
+    "   __||________________   ",
+    "  /~~~~~~~~~~~~~~~~~~~~\\  ",
+    " /~~~~~~~~~~~~~~~~~~~~~~\\ ",
+    "/~~~~~~~~~~~~~~~~~~~~~~~~\\",
+    "|=[]=====|  |=======[]===|",
+    "|========|  |============|",
+    "|========|__|============|",
+];
+const CABIN_CHIMNEY_X_OFFSET: u16 = 5;
+const CABIN_ROOF_ROWS: usize = 5;
+const SNOWLINE: u16 = 8;
+
+/// A log cabin in front of a snow-capped mountain range.
+#[derive(Default)]
+pub struct CabinScene {
+    ground: Ground,
+    decorations: Decorations,
+}
+
+impl CabinScene {
+    pub fn new() -> Self {
+        Self {
+            ground: Ground,
+            decorations: Decorations::new(),
+        }
+    }
+
+    fn cabin_position(width: u16, horizon_y: u16) -> (u16, u16) {
+        let cabin_width = CABIN[0].len() as u16;
+        let x = (width / 2).saturating_sub(cabin_width / 2);
+        let y = horizon_y.saturating_sub(CABIN.len() as u16);
+        (x, y)
+    }
+
+    fn ridge_height(x: u16, peaks: &[(u16, u16)]) -> u16 {
+        peaks
+            .iter()
+            .map(|&(px, ph)| ph.saturating_sub(px.abs_diff(x)))
+            .max()
+            .unwrap_or(0)
+    }
+
+    fn render_mountains(
+        renderer: &mut TerminalRenderer,
+        width: u16,
+        horizon_y: u16,
+    ) -> io::Result<()> {
+        let max_height = horizon_y.saturating_sub(4).min(14);
+        if max_height < 3 {
+            return Ok(());
+        }
+
+        let mut peaks = Vec::new();
+        let mut x = 0;
+        let mut i = 0u64;
+        while x < width + 10 {
+            let height = 3 + (pseudo_rand(i, 11) as u16 % (max_height - 2));
+            peaks.push((x, height));
+            x += 14 + (pseudo_rand(i, 12) % 12) as u16;
+            i += 1;
+        }
+
+        let palette = renderer.palette();
+        let (rock_color, snow_color) = (palette.rock, palette.snowcap);
+
+        for x in 0..width {
+            let h = Self::ridge_height(x, &peaks);
+            if h == 0 {
+                continue;
+            }
+            let left = Self::ridge_height(x.saturating_sub(1), &peaks);
+            let right = Self::ridge_height(x + 1, &peaks);
+
+            let ch = if left < h && right < h {
+                '^'
+            } else if right > h {
+                '/'
+            } else if left > h {
+                '\\'
+            } else {
+                '_'
+            };
+            let color = if h >= SNOWLINE {
+                snow_color
+            } else {
+                rock_color
+            };
+            renderer.render_char(x, horizon_y - h, ch, color)?;
+        }
+
+        Ok(())
+    }
+
+    fn render_cabin(
+        renderer: &mut TerminalRenderer,
+        x: u16,
+        y: u16,
+        frame: &SceneFrame,
+    ) -> io::Result<()> {
+        let is_day = frame.conditions.is_day;
+        let palette = renderer.palette();
+        let (roof_color, log_color) = (palette.roof, palette.log);
+        let (trim, snow) = (palette.trim, palette.snow_near);
+
+        let mut window_index = 0;
+        for (i, line) in CABIN.iter().enumerate() {
+            let row = y + i as u16;
+            let mut in_window = false;
+
+            for (j, ch) in line.chars().enumerate() {
+                if ch == '[' {
+                    in_window = true;
+                }
+                if ch != ' ' {
+                    let color = if in_window {
+                        frame
+                            .lights
+                            .window_color(window_index, is_day, renderer.palette())
+                    } else if i < 2 && ch == '|' {
+                        trim
+                    } else if i < CABIN_ROOF_ROWS {
+                        if frame.conditions.is_snowing && ch == '~' {
+                            snow
+                        } else {
+                            roof_color
+                        }
+                    } else if ch == '=' {
+                        log_color
+                    } else {
+                        trim
+                    };
+                    renderer.render_char(x + j as u16, row, ch, color)?;
+                }
+                if ch == ']' {
+                    in_window = false;
+                    window_index += 1;
+                }
+            }
+        }
+
+        Ok(())
+    }
+}
+
+impl Scene for CabinScene {
+    fn render(&mut self, renderer: &mut TerminalRenderer, frame: &SceneFrame) -> io::Result<()> {
+        let horizon_y = frame.horizon_y();
+
+        Self::render_mountains(renderer, frame.width, horizon_y)?;
+        self.ground
+            .render(renderer, frame.width, frame.ground_height(), horizon_y)?;
+
+        let (cabin_x, cabin_y) = Self::cabin_position(frame.width, horizon_y);
+        Self::render_cabin(renderer, cabin_x, cabin_y, frame)?;
+
+        let (pine_lines, pine_color) = self.decorations.get_pine_tree(renderer.palette());
+        let pine_y = horizon_y.saturating_sub(pine_lines.len() as u16);
+        let cabin_width = CABIN[0].len() as u16;
+        // Wide terminals get a whole forest instead of two pairs of pines
+        let offsets: &[u16] = match frame.tier {
+            LayoutTier::Compact => &[12],
+            LayoutTier::Normal => &[12, 22],
+            LayoutTier::Wide => &[12, 22, 30, 41, 50, 62, 71, 83],
+        };
+        for &offset in offsets {
+            let left_x = cabin_x.saturating_sub(offset);
+            if left_x > 0 {
+                render_art(renderer, left_x, pine_y, &pine_lines, pine_color)?;
+            }
+            let right_x = cabin_x + cabin_width + offset - 8;
+            if right_x + 10 < frame.width {
+                render_art(renderer, right_x, pine_y, &pine_lines, pine_color)?;
+            }
+        }
+
+        Ok(())
+    }
+
+    fn anchors(&self, width: u16, height: u16) -> SceneAnchors {
+        let horizon_y = LayoutTier::for_size(width, height).horizon_y(height);
+        let (cabin_x, cabin_y) = Self::cabin_position(width, horizon_y);
+
+        SceneAnchors {
+            horizon_y,
+            smoke_emitters: vec![(cabin_x + CABIN_CHIMNEY_X_OFFSET, cabin_y)],
+        }
+    }
+}
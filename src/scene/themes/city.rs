@@ -0,0 +1,190 @@
+use crate::render::TerminalRenderer;
+use crate::scene::{LayoutTier, Scene, SceneAnchors, SceneFrame, pseudo_rand, render_art};
+use std::io;
+
+const MAX_BUILDING_HEIGHT: u16 = 20;
+const LAMP_SPACING: u16 = 22;
+
+struct Building {
+    x: u16,
+    width: u16,
+    height: u16,
+    has_vent: bool,
+}
+
+impl Building {
+    fn vent_position(&self, horizon_y: u16) -> (u16, u16) {
+        let top_y = horizon_y.saturating_sub(self.height);
+        (self.x + self.width - 3, top_y.saturating_sub(1))
+    }
+}
+
+/// A downtown skyline whose office windows light up after dark.
+#[derive(Default)]
+pub struct CityScene;
+
+impl CityScene {
+    pub fn new() -> Self {
+        Self
+    }
+
+    fn layout(width: u16, horizon_y: u16) -> Vec<Building> {
+        let max_height = horizon_y.saturating_sub(3).min(MAX_BUILDING_HEIGHT);
+        let mut buildings = Vec::new();
+        if max_height < 4 {
+            return buildings;
+        }
+
+        let mut x = 1;
+        let mut i = 0u64;
+        loop {
+            let building_width = 8 + (pseudo_rand(i, 1) % 7) as u16;
+            if x + building_width >= width {
+                break;
+            }
+            let height = 4 + (pseudo_rand(i, 2) as u16 % (max_height - 3));
+
+            buildings.push(Building {
+                x,
+                width: building_width,
+                height,
+                has_vent: pseudo_rand(i, 3) % 3 == 0,
+            });
+
+            x += building_width + (pseudo_rand(i, 4) % 3) as u16;
+            i += 1;
+        }
+
+        buildings
+    }
+
+    fn render_building(
+        renderer: &mut TerminalRenderer,
+        building: &Building,
+        horizon_y: u16,
+        frame: &SceneFrame,
+        window_index: &mut usize,
+    ) -> io::Result<()> {
+        let is_day = frame.conditions.is_day;
+        let wall_color = renderer.palette().concrete;
+        let top_y = horizon_y.saturating_sub(building.height);
+        let right = building.x + building.width - 1;
+
+        for col in building.x + 1..right {
+            renderer.render_char(col, top_y, '_', wall_color)?;
+        }
+
+        for row in 1..building.height {
+            let y = top_y + row;
+            renderer.render_char(building.x, y, '|', wall_color)?;
+            renderer.render_char(right, y, '|', wall_color)?;
+            // Walls are opaque so stars and clouds don't show through
+            for col in building.x + 1..right {
+                renderer.render_char(col, y, ' ', wall_color)?;
+            }
+
+            if row == building.height - 1 {
+                for col in building.x + 1..right {
+                    renderer.render_char(col, y, '_', wall_color)?;
+                }
+                continue;
+            }
+
+            if row % 2 == 1 {
+                let mut col = building.x + 2;
+                while col + 1 < right {
+                    // Some offices sit empty all night
+                    let color = if !is_day && pseudo_rand(*window_index as u64, 7) % 4 == 0 {
+                        renderer.palette().window_dark
+                    } else {
+                        frame
+                            .lights
+                            .window_color(*window_index, is_day, renderer.palette())
+                    };
+                    renderer.render_char(col, y, '[', color)?;
+                    renderer.render_char(col + 1, y, ']', color)?;
+                    *window_index += 1;
+                    col += 3;
+                }
+            }
+        }
+
+        if building.has_vent {
+            let (vent_x, vent_y) = building.vent_position(horizon_y);
+            renderer.render_char(vent_x, vent_y, '|', wall_color)?;
+        }
+
+        Ok(())
+    }
+
+    fn render_street(
+        renderer: &mut TerminalRenderer,
+        width: u16,
+        horizon_y: u16,
+        ground_height: u16,
+    ) -> io::Result<()> {
+        let palette = renderer.palette();
+        let (curb_color, marking_color) = (palette.concrete, palette.road_marking);
+        let gravel_color = palette.trim;
+
+        let center_row = ground_height / 2;
+        for row in 0..ground_height {
+            let y = horizon_y + row;
+            for x in 0..width {
+                let (ch, color) = match row {
+                    0 => ('=', curb_color),
+                    _ if row == ground_height - 1 => ('=', curb_color),
+                    _ if row == center_row && x % 6 < 3 => ('-', marking_color),
+                    _ if pseudo_rand(x as u64, row as u64) < 60 => ('.', gravel_color),
+                    _ => continue,
+                };
+                renderer.render_char(x, y, ch, color)?;
+            }
+        }
+
+        Ok(())
+    }
+
+    fn render_lamps(renderer: &mut TerminalRenderer, width: u16, horizon_y: u16) -> io::Result<()> {
+        let palette = renderer.palette();
+        let (head_color, post_color) = (palette.lamp, palette.trim);
+        let lamp_y = horizon_y.saturating_sub(4);
+
+        let mut x = LAMP_SPACING / 3;
+        while x + 3 < width {
+            render_art(renderer, x, lamp_y, &[" o "], head_color)?;
+            render_art(renderer, x, lamp_y + 1, &[" | ", " | ", "_|_"], post_color)?;
+            x += LAMP_SPACING;
+        }
+
+        Ok(())
+    }
+}
+
+impl Scene for CityScene {
+    fn render(&mut self, renderer: &mut TerminalRenderer, frame: &SceneFrame) -> io::Result<()> {
+        let horizon_y = frame.horizon_y();
+
+        Self::render_street(renderer, frame.width, horizon_y, frame.ground_height())?;
+
+        let mut window_index = 0;
+        for building in Self::layout(frame.width, horizon_y) {
+            Self::render_building(renderer, &building, horizon_y, frame, &mut window_index)?;
+        }
+
+        Self::render_lamps(renderer, frame.width, horizon_y)
+    }
+
+    fn anchors(&self, width: u16, height: u16) -> SceneAnchors {
+        let horizon_y = LayoutTier::for_size(width, height).horizon_y(height);
+
+        SceneAnchors {
+            horizon_y,
+            smoke_emitters: Self::layout(width, horizon_y)
+                .iter()
+                .filter(|b| b.has_vent)
+                .map(|b| b.vent_position(horizon_y))
+                .collect(),
+        }
+    }
+}
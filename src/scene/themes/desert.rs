@@ -0,0 +1,112 @@
+use crate::render::TerminalRenderer;
+use crate::scene::{LayoutTier, Scene, SceneAnchors, SceneFrame, pseudo_rand, render_art};
+use crossterm::style::Color;
+use std::io;
+
+const CACTUS: [&str; 7] = [
+    "    _    ",
+    " _ | |   ",
+    "| || | _ ",
+    "| || || |",
+    " \\_  __/ ",
+    "   | |   ",
+    "   | |   ",
+];
+
+/// Rolling dunes, distant pyramids and a few saguaro cacti.
+#[derive(Default)]
+pub struct DesertScene;
+
+impl DesertScene {
+    pub fn new() -> Self {
+        Self
+    }
+
+    fn render_dunes(
+        renderer: &mut TerminalRenderer,
+        width: u16,
+        horizon_y: u16,
+        ground_height: u16,
+    ) -> io::Result<()> {
+        let palette = renderer.palette();
+        let (sand, shadow) = (palette.sand, palette.dune_shadow);
+
+        for row in 0..ground_height {
+            for x in 0..width {
+                let r = pseudo_rand(x as u64, row as u64);
+                let (ch, color) = if row == 0 {
+                    // Gentle ripples along the dune crest
+                    if (x / 6) % 2 == 0 {
+                        ('~', sand)
+                    } else {
+                        ('_', shadow)
+                    }
+                } else if r < 120 {
+                    ('.', sand)
+                } else if r < 150 {
+                    ('~', shadow)
+                } else {
+                    continue;
+                };
+                renderer.render_char(x, horizon_y + row, ch, color)?;
+            }
+        }
+
+        Ok(())
+    }
+
+    fn render_pyramid(
+        renderer: &mut TerminalRenderer,
+        center_x: u16,
+        horizon_y: u16,
+        height: u16,
+        color: Color,
+    ) -> io::Result<()> {
+        for level in 0..height {
+            let y = horizon_y - height + level;
+            let left = center_x.saturating_sub(level + 1);
+            let right = center_x + level;
+            renderer.render_char(left, y, '/', color)?;
+            renderer.render_char(right, y, '\\', color)?;
+            if level == height - 1 {
+                for x in left + 1..right {
+                    renderer.render_char(x, y, '_', color)?;
+                }
+            }
+        }
+        Ok(())
+    }
+}
+
+impl Scene for DesertScene {
+    fn render(&mut self, renderer: &mut TerminalRenderer, frame: &SceneFrame) -> io::Result<()> {
+        let horizon_y = frame.horizon_y();
+        let width = frame.width;
+
+        let pyramid_color = renderer.palette().sandstone;
+        if horizon_y > 8 {
+            Self::render_pyramid(renderer, width / 4, horizon_y, 6, pyramid_color)?;
+            Self::render_pyramid(renderer, width / 4 + 11, horizon_y, 4, pyramid_color)?;
+        }
+
+        Self::render_dunes(renderer, width, horizon_y, frame.ground_height())?;
+
+        let cactus_color = renderer.palette().grass;
+        let cactus_y = horizon_y.saturating_sub(CACTUS.len() as u16);
+        for fraction in [2, 3, 4] {
+            let x = width / 5 * fraction;
+            if x + CACTUS[0].len() as u16 <= width {
+                render_art(renderer, x, cactus_y, &CACTUS, cactus_color)?;
+            }
+        }
+
+        Ok(())
+    }
+
+    fn anchors(&self, width: u16, height: u16) -> SceneAnchors {
+        SceneAnchors {
+            horizon_y: LayoutTier::for_size(width, height).horizon_y(height),
+            smoke_emitters: Vec::new(),
+        }
+    }
+}
@@ -0,0 +1,292 @@
+use crate::render::{Rect, TerminalRenderer};
+use crate::scene::decorations::{DecorationRenderConfig, Decorations};
+use crate::scene::ground::Ground;
+use crate::scene::house::{Cottage, House};
+use crate::scene::{LayoutTier, Scene, SceneAnchors, SceneFrame, pseudo_rand, render_art};
+use std::io;
+
+// Distance from the house to the outermost normal-layout decorations
+// (mailbox on the left, pine on the right).
+const MAILBOX_OFFSET: u16 = 30;
+const PINE_END_OFFSET: u16 = House::WIDTH + 27;
+const TREE_WIDTH: u16 = 16;
+const PINE_WIDTH: u16 = 9;
+
+/// Extra scenery placed along the sides in wide layouts.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Prop {
+    Cottage,
+    Tree,
+    Pine,
+}
+
+impl Prop {
+    fn width(self) -> u16 {
+        match self {
+            Self::Cottage => Cottage::WIDTH,
+            Self::Tree => TREE_WIDTH,
+            Self::Pine => PINE_WIDTH,
+        }
+    }
+
+    fn pick(index: u64, side: u64) -> Self {
+        match pseudo_rand(index, side) % 3 {
+            0 => Self::Cottage,
+            1 => Self::Tree,
+            _ => Self::Pine,
+        }
+    }
+}
+
+/// The original scene: a house with a garden, trees and a fence.
+#[derive(Default)]
+pub struct HouseScene {
+    house: House,
+    cottage: Cottage,
+    ground: Ground,
+    decorations: Decorations,
+}
+
+impl HouseScene {
+    pub fn new() -> Self {
+        Self {
+            house: House,
+            cottage: Cottage,
+            ground: Ground,
+            decorations: Decorations::new(),
+        }
+    }
+
+    fn house_position(&self, width: u16, horizon_y: u16) -> (u16, u16) {
+        let house_x = (width / 2).saturating_sub(self.house.width() / 2);
+        let house_y = horizon_y.saturating_sub(self.house.height());
+        (house_x, house_y)
+    }
+
+    fn cottage_position(width: u16, horizon_y: u16) -> (u16, u16) {
+        let x = (width / 2).saturating_sub(Cottage::WIDTH / 2);
+        let y = horizon_y.saturating_sub(Cottage::HEIGHT);
+        (x, y)
+    }
+
+    /// Props filling the space outside the normal layout, working outwards
+    /// from the house on each side until the terminal edge.
+    fn wide_props(width: u16, house_x: u16) -> Vec<(Prop, u16)> {
+        let mut props = Vec::new();
+
+        let mut x = house_x + PINE_END_OFFSET;
+        let mut i = 0u64;
+        loop {
+            let prop = Prop::pick(i, 1);
+            let start = x + 3 + (pseudo_rand(i, 2) % 4) as u16;
+            if start + prop.width() >= width {
+                break;
+            }
+            props.push((prop, start));
+            x = start + prop.width();
+            i += 1;
+        }
+
+        let mut x = house_x.saturating_sub(MAILBOX_OFFSET);
+        let mut i = 0u64;
+        loop {
+            let prop = Prop::pick(i, 3);
+            let gap = 3 + (pseudo_rand(i, 4) % 4) as u16;
+            let Some(start) = x.checked_sub(gap + prop.width()) else {
+                break;
+            };
+            if start == 0 {
+                break;
+            }
+            props.push((prop, start));
+            x = start;
+            i += 1;
+        }
+
+        props
+    }
+
+    fn render_compact(
+        &self,
+        renderer: &mut TerminalRenderer,
+        frame: &SceneFrame,
+        horizon_y: u16,
+    ) -> io::Result<()> {
+        let is_day = frame.conditions.is_day;
+        let (cottage_x, cottage_y) = Self::cottage_position(frame.width, horizon_y);
+        self.cottage
+            .render(renderer, cottage_x, cottage_y, is_day, &frame.lights, 0)?;
+
+        let (pine_lines, pine_color) = self.decorations.get_pine_tree(renderer.palette());
+        if cottage_x > PINE_WIDTH + 2 {
+            let pine_y = horizon_y.saturating_sub(pine_lines.len() as u16);
+            render_art(
+                renderer,
+                cottage_x - PINE_WIDTH - 2,
+                pine_y,
+                &pine_lines,
+                pine_color,
+            )?;
+        }
+
+        let (fence_lines, fence_color) = self.decorations.get_fence(renderer.palette());
+        let fence_x = cottage_x + Cottage::WIDTH + 1;
+        if fence_x + fence_lines[0].len() as u16 <= frame.width {
+            let fence_y = horizon_y.saturating_sub(fence_lines.len() as u16);
+            render_art(renderer, fence_x, fence_y, &fence_lines, fence_color)?;
+        }
+
+        Ok(())
+    }
+
+    fn render_props(
+        &self,
+        renderer: &mut TerminalRenderer,
+        frame: &SceneFrame,
+        house_x: u16,
+        horizon_y: u16,
+    ) -> io::Result<()> {
+        let is_day = frame.conditions.is_day;
+        let mut cottages = 0;
+
+        for (prop, x) in Self::wide_props(frame.width, house_x) {
+            let (lines, color) = match prop {
+                Prop::Cottage => {
+                    cottages += 1;
+                    let y = horizon_y.saturating_sub(Cottage::HEIGHT);
+                    self.cottage
+                        .render(renderer, x, y, is_day, &frame.lights, cottages * 3)?;
+                    continue;
+                }
+                Prop::Tree => self.decorations.get_tree(renderer.palette()),
+                Prop::Pine => self.decorations.get_pine_tree(renderer.palette()),
+            };
+            let y = horizon_y.saturating_sub(lines.len() as u16);
+            render_art(renderer, x, y, &lines, color)?;
+        }
+
+        Ok(())
+    }
+}
+
+impl Scene for HouseScene {
+    fn render(&mut self, renderer: &mut TerminalRenderer, frame: &SceneFrame) -> io::Result<()> {
+        let horizon_y = frame.horizon_y();
+        let is_day = frame.conditions.is_day;
+
+        self.ground
+            .render(renderer, frame.width, frame.ground_height(), horizon_y)?;
+
+        if frame.tier == LayoutTier::Compact {
+            return self.render_compact(renderer, frame, horizon_y);
+        }
+
+        let (house_x, house_y) = self.house_position(frame.width, horizon_y);
+        self.house
+            .render(renderer, house_x, house_y, is_day, &frame.lights)?;
+
+        self.decorations.render(
+            renderer,
+            &DecorationRenderConfig {
+                horizon_y,
+                house_x,
+                house_width: self.house.width(),
+                width: frame.width,
+            },
+        )?;
+
+        if frame.tier == LayoutTier::Wide {
+            self.render_props(renderer, frame, house_x, horizon_y)?;
+        }
+
+        Ok(())
+    }
+
+    fn anchors(&self, width: u16, height: u16) -> SceneAnchors {
+        let tier = LayoutTier::for_size(width, height);
+        let horizon_y = tier.horizon_y(height);
+
+        if tier == LayoutTier::Compact {
+            let (cottage_x, cottage_y) = Self::cottage_position(width, horizon_y);
+            return SceneAnchors {
+                horizon_y,
+                smoke_emitters: vec![(cottage_x + Cottage::CHIMNEY_X_OFFSET, cottage_y)],
+            };
+        }
+
+        let (house_x, house_y) = self.house_position(width, horizon_y);
+        let mut smoke_emitters = vec![(house_x + House::CHIMNEY_X_OFFSET, house_y)];
+
+        if tier == LayoutTier::Wide {
+            let cottage_y = horizon_y.saturating_sub(Cottage::HEIGHT);
+            smoke_emitters.extend(
+                Self::wide_props(width, house_x)
+                    .into_iter()
+                    .filter(|&(prop, _)| prop == Prop::Cottage)
+                    .map(|(_, x)| (x + Cottage::CHIMNEY_X_OFFSET, cottage_y)),
+            );
+        }
+
+        SceneAnchors {
+            horizon_y,
+            smoke_emitters,
+        }
+    }
+
+    fn light_switches(&self, width: u16, height: u16) -> Vec<Rect> {
+        let tier = LayoutTier::for_size(width, height);
+        let horizon_y = tier.horizon_y(height);
+
+        if tier == LayoutTier::Compact {
+            let (x, y) = Self::cottage_position(width, horizon_y);
+            return vec![Rect::new(x, y, Cottage::WIDTH, Cottage::HEIGHT)];
+        }
+
+        let (house_x, house_y) = self.house_position(width, horizon_y);
+        let mut switches = vec![House::body(house_x, house_y)];
+        if tier == LayoutTier::Wide {
+            let cottage_y = horizon_y.saturating_sub(Cottage::HEIGHT);
+            switches.extend(
+                Self::wide_props(width, house_x)
+                    .into_iter()
+                    .filter(|&(prop, _)| prop == Prop::Cottage)
+                    .map(|(_, x)| Rect::new(x, cottage_y, Cottage::WIDTH, Cottage::HEIGHT)),
+            );
+        }
+        switches
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_wide_props_stay_clear_of_house_and_edges() {
+        for width in [160, 200, 300] {
+            let house_x = width / 2 - House::WIDTH / 2;
+            let props = HouseScene::wide_props(width, house_x);
+            for (prop, x) in props {
+                assert!(x > 0 && x + prop.width() < width, "{prop:?} off screen");
+                let overlaps_core =
+                    x + prop.width() > house_x - MAILBOX_OFFSET && x < house_x + PINE_END_OFFSET;
+                assert!(!overlaps_core, "{prop:?} at {x} overlaps the garden");
+            }
+        }
+    }
+
+    #[test]
+    fn test_light_switch_covers_house() {
+        let scene = HouseScene::new();
+        let switches = scene.light_switches(100, 30);
+        let house = switches[0];
+        assert!(house.contains(50, 20));
+        assert!(!house.contains(2, 2));
+    }
+
+    #[test]
+    fn test_wide_layout_adds_neighbour_chimneys() {
+        let anchors = HouseScene::new().anchors(300, 50);
+        assert!(anchors.smoke_emitters.len() > 1);
+    }
+}
@@ -0,0 +1,92 @@
+pub mod cabin;
+pub mod city;
+pub mod desert;
+pub mod house;
+pub mod seaside;
+
+use super::Scene;
+use serde::Deserialize;
+
+#[derive(Deserialize, Debug, Default, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum SceneTheme {
+    #[default]
+    House,
+    City,
+    Seaside,
+    #[serde(alias = "mountain")]
+    Cabin,
+    Desert,
+}
+
+impl SceneTheme {
+    pub fn build(self) -> Box<dyn Scene> {
+        match self {
+            Self::House => Box::new(house::HouseScene::new()),
+            Self::City => Box::new(city::CityScene::new()),
+            Self::Seaside => Box::new(seaside::SeasideScene::new()),
+            Self::Cabin => Box::new(cabin::CabinScene::new()),
+            Self::Desert => Box::new(desert::DesertScene::new()),
+        }
+    }
+}
+
+impl std::str::FromStr for SceneTheme {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_lowercase().as_str() {
+            "house" => Ok(Self::House),
+            "city" => Ok(Self::City),
+            "seaside" => Ok(Self::Seaside),
+            "cabin" | "mountain" => Ok(Self::Cabin),
+            "desert" => Ok(Self::Desert),
+            _ => Err(format!(
+                "Unknown scene: '{}'. Valid options: house, city, seaside, cabin, desert",
+                s
+            )),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const ALL_THEMES: [SceneTheme; 5] = [
+        SceneTheme::House,
+        SceneTheme::City,
+        SceneTheme::Seaside,
+        SceneTheme::Cabin,
+        SceneTheme::Desert,
+    ];
+
+    #[test]
+    fn test_scene_theme_from_str() {
+        assert_eq!("city".parse::<SceneTheme>(), Ok(SceneTheme::City));
+        assert_eq!("Seaside".parse::<SceneTheme>(), Ok(SceneTheme::Seaside));
+        assert_eq!("mountain".parse::<SceneTheme>(), Ok(SceneTheme::Cabin));
+        assert!("castle".parse::<SceneTheme>().is_err());
+    }
+
+    #[test]
+    fn test_anchors_inside_terminal() {
+        for theme in ALL_THEMES {
+            let scene = theme.build();
+            for (width, height) in [(40, 12), (70, 20), (120, 40), (240, 70)] {
+                let anchors = scene.anchors(width, height);
+                assert!(anchors.horizon_y < height, "{theme:?} horizon off screen");
+                for &(x, y) in &anchors.smoke_emitters {
+                    assert!(x < width, "{theme:?} emitter x off screen");
+                    assert!(y < anchors.horizon_y, "{theme:?} emitter below horizon");
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_house_theme_has_chimney() {
+        let anchors = SceneTheme::House.build().anchors(120, 40);
+        assert_eq!(anchors.smoke_emitters.len(), 1);
+    }
+}
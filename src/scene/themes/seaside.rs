@@ -0,0 +1,177 @@
+use crate::render::TerminalRenderer;
+use crate::scene::{LayoutTier, Scene, SceneAnchors, SceneFrame, pseudo_rand, render_art};
+use std::io;
+
+const SEA_ROWS: u16 = 3;
+const WAVE_PATTERN: &[char] = &['~', '~', '-', '~', '^', '~', '-', '~', '~', '_'];
+const BEAM_LENGTH: u16 = 14;
+
+const LIGHTHOUSE: [&str; 9] = [
+    "   __   ",
+    "  /  \\  ",
+    "  |()|  ",
+    " _|__|_ ",
+    "  |  |  ",
+    "  |##|  ",
+    "  |  |  ",
+    "  |##|  ",
+    " /____\\ ",
+];
+const LIGHTHOUSE_LAMP_ROW: u16 = 2;
+
+const SAILBOAT: [&str; 4] = ["   |\\   ", "   | \\  ", "  _|__\\_", "  \\____/"];
+
+/// A beach with rolling waves, a lighthouse and a passing sailboat.
+#[derive(Default)]
+pub struct SeasideScene;
+
+impl SeasideScene {
+    pub fn new() -> Self {
+        Self
+    }
+
+    fn render_sea(
+        renderer: &mut TerminalRenderer,
+        width: u16,
+        horizon_y: u16,
+        tick: u64,
+    ) -> io::Result<()> {
+        let palette = renderer.palette();
+        let (far, near, foam) = (palette.water_far, palette.water, palette.foam);
+
+        for row in 0..SEA_ROWS {
+            // Nearer rows roll in faster
+            let speed = (SEA_ROWS - row) as u64 * 2;
+            let offset = tick / speed + row as u64 * 3;
+            let color = if row == 0 { far } else { near };
+
+            for x in 0..width {
+                let idx = (x as u64 + offset) as usize % WAVE_PATTERN.len();
+                renderer.render_char(x, horizon_y + row, WAVE_PATTERN[idx], color)?;
+            }
+        }
+
+        // Foam line where the waves meet the sand
+        let foam_y = horizon_y + SEA_ROWS;
+        let foam_shift = (tick / 10) % 4;
+        for x in 0..width {
+            let ch = if (x as u64 + foam_shift) % 4 == 0 {
+                ','
+            } else {
+                '.'
+            };
+            renderer.render_char(x, foam_y, ch, foam)?;
+        }
+
+        Ok(())
+    }
+
+    fn render_beach(
+        renderer: &mut TerminalRenderer,
+        width: u16,
+        horizon_y: u16,
+        ground_height: u16,
+    ) -> io::Result<()> {
+        let sand_color = renderer.palette().beach;
+
+        for row in SEA_ROWS + 1..ground_height {
+            for x in 0..width {
+                let r = pseudo_rand(x as u64, row as u64);
+                let ch = if r < 150 {
+                    '.'
+                } else if r < 190 {
+                    ':'
+                } else {
+                    ' '
+                };
+                renderer.render_char(x, horizon_y + row, ch, sand_color)?;
+            }
+        }
+
+        Ok(())
+    }
+
+    fn render_lighthouse(
+        renderer: &mut TerminalRenderer,
+        width: u16,
+        horizon_y: u16,
+        tick: u64,
+        is_day: bool,
+    ) -> io::Result<()> {
+        let x = (width / 4 * 3).min(width.saturating_sub(12));
+        let y = (horizon_y + 1).saturating_sub(LIGHTHOUSE.len() as u16);
+
+        let palette = renderer.palette();
+        let (body_color, stripe_color) = (palette.lighthouse, palette.lighthouse_stripe);
+        let (lamp_color, beam_color) = (palette.lamp, palette.beam);
+        render_art(renderer, x, y, &LIGHTHOUSE, body_color)?;
+        render_art(renderer, x, y + 5, &["   ##   "], stripe_color)?;
+        render_art(renderer, x, y + 7, &["   ##   "], stripe_color)?;
+
+        if is_day {
+            return Ok(());
+        }
+
+        let lamp_y = y + LIGHTHOUSE_LAMP_ROW;
+        render_art(renderer, x, lamp_y, &["   ()   "], lamp_color)?;
+
+        // The beam sweeps to one side, then the other, with a dark pause between
+        match (tick / 20) % 4 {
+            0 => {
+                for i in 1..=BEAM_LENGTH {
+                    renderer.render_char(x + 5 + i, lamp_y, '-', beam_color)?;
+                }
+            }
+            2 => {
+                for i in 1..=BEAM_LENGTH.min(x + 2) {
+                    renderer.render_char(x + 2 - i, lamp_y, '-', beam_color)?;
+                }
+            }
+            _ => {}
+        }
+
+        Ok(())
+    }
+
+    fn render_sailboat(
+        renderer: &mut TerminalRenderer,
+        width: u16,
+        horizon_y: u16,
+        tick: u64,
+    ) -> io::Result<()> {
+        let boat_width = SAILBOAT[0].len() as u64;
+        let travel = width as u64 + boat_width;
+        let pos = (tick / 6) % travel;
+        if pos < boat_width {
+            return Ok(());
+        }
+
+        let x = (pos - boat_width) as u16;
+        if x + boat_width as u16 >= width {
+            return Ok(());
+        }
+
+        let y = (horizon_y + 2).saturating_sub(SAILBOAT.len() as u16);
+        let color = renderer.palette().sail;
+        render_art(renderer, x, y, &SAILBOAT, color)
+    }
+}
+
+impl Scene for SeasideScene {
+    fn render(&mut self, renderer: &mut TerminalRenderer, frame: &SceneFrame) -> io::Result<()> {
+        let horizon_y = frame.horizon_y();
+        let is_day = frame.conditions.is_day;
+
+        Self::render_sea(renderer, frame.width, horizon_y, frame.tick)?;
+        Self::render_beach(renderer, frame.width, horizon_y, frame.ground_height())?;
+        Self::render_sailboat(renderer, frame.width, horizon_y, frame.tick)?;
+        Self::render_lighthouse(renderer, frame.width, horizon_y, frame.tick, is_day)
+    }
+
+    fn anchors(&self, width: u16, height: u16) -> SceneAnchors {
+        SceneAnchors {
+            horizon_y: LayoutTier::for_size(width, height).horizon_y(height),
+            smoke_emitters: Vec::new(),
+        }
+    }
+}
@@ -0,0 +1,367 @@
+use crate::app::App;
+use crate::render::{Cell, MemoryBackend, Style, TerminalRenderer, color_rgb};
+use clap::ValueEnum;
+use crossterm::queue;
+use crossterm::style::{
+    Attribute, Color, Print, ResetColor, SetAttribute, SetBackgroundColor, SetForegroundColor,
+};
+use std::io;
+
+/// Size of one cell in the SVG output, in pixels.
+const SVG_CELL_WIDTH: usize = 8;
+const SVG_CELL_HEIGHT: usize = 16;
+const BACKGROUND: &str = "#000000";
+const FOREGROUND: &str = "#c0c0c0";
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum SnapshotFormat {
+    /// Text with ANSI color escapes, for `cat` and MOTD files
+    Ansi,
+    /// Plain text without colors
+    Txt,
+    /// A standalone HTML `<pre>` block
+    Html,
+    /// A standalone SVG image
+    Svg,
+}
+
+/// Run `app` for `frames` frames off-screen at `width`×`height` and encode
+/// the last one.
+pub fn run(
+    app: &mut App,
+    (width, height): (u16, u16),
+    frames: u32,
+    format: SnapshotFormat,
+) -> io::Result<String> {
+    let mut renderer = TerminalRenderer::headless(width, height);
+    for _ in 0..frames.max(1) {
+        app.draw(&mut renderer)?;
+        app.update_animations(app.frame_duration());
+    }
+    renderer.flush()?;
+
+    let screen = renderer
+        .backend::<MemoryBackend>()
+        .expect("headless renderer keeps frames in memory");
+    Ok(encode(screen, format))
+}
+
+pub fn encode(screen: &MemoryBackend, format: SnapshotFormat) -> String {
+    let rows: Vec<&[Cell]> = (0..screen.height()).map(|y| screen.row(y)).collect();
+    let rows = rows.as_slice();
+    match format {
+        SnapshotFormat::Ansi => encode_ansi(rows),
+        SnapshotFormat::Txt => screen.text(),
+        SnapshotFormat::Html => encode_html(rows),
+        SnapshotFormat::Svg => encode_svg(rows),
+    }
+}
+
+/// Consecutive cells of one style within a row, as (first column, style, text).
+fn runs(row: &[Cell]) -> Vec<(usize, Style, String)> {
+    let mut runs: Vec<(usize, Style, String)> = Vec::new();
+    for (x, cell) in row.iter().enumerate() {
+        // Blanks take whatever style is current as long as neither shows on
+        // a blank, so they don't split runs
+        let style = match runs.last() {
+            Some((_, style, _))
+                if cell.character == ' ' && plain(cell.style()) && plain(*style) =>
+            {
+                *style
+            }
+            _ => cell.style(),
+        };
+        match runs.last_mut() {
+            Some((_, last, text)) if *last == style => text.push(cell.character),
+            _ => runs.push((x, style, cell.character.to_string())),
+        }
+    }
+    runs
+}
+
+/// Whether a blank drawn in `style` looks like any other blank.
+fn plain(style: Style) -> bool {
+    style.background == Color::Reset && !style.attributes.has(Attribute::Reverse)
+}
+
+fn encode_ansi(rows: &[&[Cell]]) -> String {
+    let mut out = Vec::new();
+    for row in rows {
+        let mut runs = runs(row);
+        if let Some((_, style, text)) = runs.last_mut()
+            && plain(*style)
+        {
+            text.truncate(text.trim_end().len());
+        }
+        let mut current = Style::default();
+        for (_, style, text) in runs {
+            if text.is_empty() {
+                continue;
+            }
+            // Writing to a Vec cannot fail. Attributes can only be turned
+            // off together, so a change of background or attributes starts
+            // from a reset.
+            if (style.background, style.attributes) != (current.background, current.attributes) {
+                let _ = queue!(out, ResetColor);
+                if style.background != Color::Reset {
+                    let _ = queue!(out, SetBackgroundColor(style.background));
+                }
+                for attribute in Style::ATTRIBUTES {
+                    if style.attributes.has(attribute) {
+                        let _ = queue!(out, SetAttribute(attribute));
+                    }
+                }
+            }
+            let _ = queue!(out, SetForegroundColor(style.color), Print(text));
+            current = style;
+        }
+        let _ = queue!(out, ResetColor, Print('\n'));
+    }
+    String::from_utf8_lossy(&out).into_owned()
+}
+
+/// Foreground and background as `#rrggbb`, with reverse applied. `None`
+/// where the default colors show.
+fn hex_colors(style: Style) -> (Option<String>, Option<String>) {
+    let (color, background) = (hex(style.color), hex(style.background));
+    if style.attributes.has(Attribute::Reverse) {
+        (
+            Some(background.unwrap_or_else(|| BACKGROUND.to_string())),
+            Some(color.unwrap_or_else(|| FOREGROUND.to_string())),
+        )
+    } else {
+        (color, background)
+    }
+}
+
+fn encode_html(rows: &[&[Cell]]) -> String {
+    let mut html = format!(
+        "<pre style=\"background:{BACKGROUND};color:{FOREGROUND};font-family:monospace;line-height:1.2;padding:1em\">"
+    );
+    for row in rows {
+        for (_, style, text) in runs(row) {
+            let (color, background) = hex_colors(style);
+            let mut css = Vec::new();
+            if let Some(color) = color {
+                css.push(format!("color:{color}"));
+            }
+            if let Some(background) = background {
+                css.push(format!("background:{background}"));
+            }
+            if style.attributes.has(Attribute::Bold) {
+                css.push("font-weight:bold".to_string());
+            }
+            if style.attributes.has(Attribute::Italic) {
+                css.push("font-style:italic".to_string());
+            }
+            if style.attributes.has(Attribute::Dim) {
+                css.push("opacity:0.6".to_string());
+            }
+
+            if css.is_empty() {
+                html.push_str(&escape(&text));
+            } else {
+                html.push_str(&format!(
+                    "<span style=\"{}\">{}</span>",
+                    css.join(";"),
+                    escape(&text)
+                ));
+            }
+        }
+        html.push('\n');
+    }
+    html.push_str("</pre>\n");
+    html
+}
+
+fn encode_svg(rows: &[&[Cell]]) -> String {
+    let width = rows.first().map_or(0, |row| row.len()) * SVG_CELL_WIDTH;
+    let height = rows.len() * SVG_CELL_HEIGHT;
+    let mut svg = format!(
+        "<svg xmlns=\"http://www.w3.org/2000/svg\" width=\"{width}\" height=\"{height}\" viewBox=\"0 0 {width} {height}\">\n\
+         <rect width=\"100%\" height=\"100%\" fill=\"{BACKGROUND}\"/>\n\
+         <g font-family=\"monospace\" font-size=\"{}\" xml:space=\"preserve\">\n",
+        SVG_CELL_HEIGHT - 2
+    );
+    for (y, row) in rows.iter().enumerate() {
+        let top = y * SVG_CELL_HEIGHT;
+        let baseline = top + SVG_CELL_HEIGHT - 4;
+        for (x, style, text) in runs(row) {
+            let (color, background) = hex_colors(style);
+            let length = text.chars().count() * SVG_CELL_WIDTH;
+            if let Some(background) = background {
+                svg.push_str(&format!(
+                    "<rect x=\"{}\" y=\"{top}\" width=\"{length}\" height=\"{SVG_CELL_HEIGHT}\" fill=\"{background}\"/>\n",
+                    x * SVG_CELL_WIDTH
+                ));
+            }
+            if text.trim().is_empty() {
+                continue;
+            }
+
+            let mut attributes = String::new();
+            if style.attributes.has(Attribute::Bold) {
+                attributes.push_str(" font-weight=\"bold\"");
+            }
+            if style.attributes.has(Attribute::Italic) {
+                attributes.push_str(" font-style=\"italic\"");
+            }
+            if style.attributes.has(Attribute::Dim) {
+                attributes.push_str(" opacity=\"0.6\"");
+            }
+            svg.push_str(&format!(
+                "<text x=\"{}\" y=\"{baseline}\" fill=\"{}\" textLength=\"{length}\"{attributes}>{}</text>\n",
+                x * SVG_CELL_WIDTH,
+                color.unwrap_or_else(|| FOREGROUND.to_string()),
+                escape(&text)
+            ));
+        }
+    }
+    svg.push_str("</g>\n</svg>\n");
+    svg
+}
+
+fn escape(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
+
+/// The color as `#rrggbb`, using the xterm palette for named and 256-color
+/// values. `None` for the terminal's default color.
+fn hex(color: Color) -> Option<String> {
+    color_rgb(color).map(|(r, g, b)| format!("#{r:02x}{g:02x}{b:02x}"))
+}
+
+#[cfg(test)]
+pub(crate) mod tests {
+    use super::*;
+    use crate::config::Config;
+    use crate::render::Backend;
+
+    pub fn test_app(condition: &str, (width, height): (u16, u16), seed: u64) -> App {
+        let config = Config::default();
+        let weather = crate::app::simulated_weather(condition, false);
+        let scenes = vec![config.scene.build()];
+        App::new(
+            &config,
+            scenes,
+            Some(weather),
+            Some(seed),
+            false,
+            width,
+            height,
+        )
+    }
+
+    fn cells(text: &str, color: Color) -> Vec<Cell> {
+        text.chars()
+            .map(|character| Cell::new(character, color.into()))
+            .collect()
+    }
+
+    fn encode(rows: &[&[Cell]], format: SnapshotFormat) -> String {
+        let width = rows[0].len() as u16;
+        let mut screen = MemoryBackend::new(width, rows.len() as u16);
+        let changes: Vec<_> = rows
+            .iter()
+            .enumerate()
+            .flat_map(|(y, row)| {
+                row.iter()
+                    .enumerate()
+                    .map(move |(x, cell)| (x as u16, y as u16, *cell))
+            })
+            .collect();
+        screen.draw(&changes).unwrap();
+        super::encode(&screen, format)
+    }
+
+    #[test]
+    fn test_txt_trims_trailing_space() {
+        let row = cells("ab  ", Color::Red);
+        assert_eq!(encode(&[&row], SnapshotFormat::Txt), "ab\n");
+    }
+
+    #[test]
+    fn test_html_escapes_and_colors() {
+        let mut row = cells("<a", Color::Rgb { r: 1, g: 2, b: 3 });
+        row.extend(cells(" b", Color::Reset));
+        let html = encode(&[&row], SnapshotFormat::Html);
+        assert!(html.contains("<span style=\"color:#010203\">&lt;a </span>b\n"));
+    }
+
+    #[test]
+    fn test_svg_skips_blank_runs() {
+        let mut row = cells("    ", Color::Reset);
+        row.extend(cells("x", Color::AnsiValue(196)));
+        let svg = encode(&[&row], SnapshotFormat::Svg);
+        assert_eq!(svg.matches("<text").count(), 1);
+        assert!(svg.contains("<text x=\"32\" y=\"12\" fill=\"#ff0000\""));
+    }
+
+    #[test]
+    fn test_ansi_resets_each_row() {
+        let row = cells("hi", Color::Blue);
+        let ansi = encode(&[&row, &row], SnapshotFormat::Ansi);
+        assert_eq!(ansi.matches("\u{1b}[0m\n").count(), 2);
+        assert!(ansi.contains("hi"));
+    }
+
+    #[test]
+    fn test_backgrounds_and_attributes() {
+        let lit = Style::new(Color::Black)
+            .on(Color::Rgb {
+                r: 255,
+                g: 200,
+                b: 0,
+            })
+            .with(Attribute::Bold);
+        let mut row = cells("a", Color::Reset);
+        row.extend("  ".chars().map(|character| Cell::new(character, lit)));
+        row.push(Cell::new('!', Style::default().with(Attribute::Reverse)));
+        row.extend(cells("  ", Color::Reset));
+
+        let html = encode(&[&row], SnapshotFormat::Html);
+        assert!(html.contains(
+            "a<span style=\"color:#000000;background:#ffc800;font-weight:bold\">  </span>"
+        ));
+        assert!(html.contains("<span style=\"color:#000000;background:#c0c0c0\">!</span>"));
+
+        let svg = encode(&[&row], SnapshotFormat::Svg);
+        assert!(
+            svg.contains("<rect x=\"8\" y=\"0\" width=\"16\" height=\"16\" fill=\"#ffc800\"/>")
+        );
+        assert_eq!(svg.matches("<text").count(), 2);
+
+        // Painted blanks survive trimming, the reset before `!` drops the bold
+        let ansi = encode(&[&row], SnapshotFormat::Ansi);
+        assert_eq!(
+            ansi,
+            "\u{1b}[39ma\u{1b}[0m\u{1b}[48;2;255;200;0m\u{1b}[1m\u{1b}[38;5;0m  \u{1b}[0m\u{1b}[7m\u{1b}[39m!\u{1b}[0m\n"
+        );
+    }
+
+    #[test]
+    fn test_simulated_snapshot() {
+        let text = run(
+            &mut test_app("clear", (80, 24), 7),
+            (80, 24),
+            5,
+            SnapshotFormat::Txt,
+        )
+        .unwrap();
+        assert_eq!(text.lines().count(), 24);
+        assert!(text.contains("Weather: Clear"));
+    }
+
+    #[test]
+    fn test_same_seed_same_frames() {
+        let frame = |seed| {
+            let mut app = test_app("thunderstorm", (80, 24), seed);
+            run(&mut app, (80, 24), 40, SnapshotFormat::Ansi).unwrap()
+        };
+        assert_eq!(frame(1), frame(1));
+        assert_ne!(frame(1), frame(2));
+    }
+}
@@ -0,0 +1,694 @@
+use crossterm::style::Color;
+use serde::Deserialize;
+
+/// The colors everything is drawn with, by what they are used for. A theme
+/// has one palette for the day and one for the night.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Palette {
+    // Interface
+    pub hud: Color,
+    /// Pane separators and overlay frames.
+    pub border: Color,
+    /// Overlay text.
+    pub text: Color,
+    /// Key names and highlights in overlays.
+    pub accent: Color,
+
+    // Sky
+    /// Background above the horizon. `Color::Reset` leaves the terminal's own.
+    pub sky: Color,
+    pub sun: Color,
+    pub moon: Color,
+    pub star: Color,
+    pub star_dim: Color,
+    pub cloud: Color,
+    /// Clouds on overcast days.
+    pub cloud_dark: Color,
+    pub lightning: Color,
+    /// Lightning while the screen flashes.
+    pub flash: Color,
+    pub bird: Color,
+    pub airplane: Color,
+    pub airplane_window: Color,
+    pub airplane_tail: Color,
+
+    // Weather
+    /// Near raindrops in light rain and storms, and splashes.
+    pub rain_near: Color,
+    /// Near raindrops in drizzle and heavy rain.
+    pub rain_tint: Color,
+    pub rain_far: Color,
+    pub snow_near: Color,
+    pub snow_far: Color,
+    pub fog: [Color; 3],
+    /// Smoke from young to old.
+    pub smoke: [Color; 3],
+    /// Fireflies from brightest to dimmest.
+    pub fireflies: [Color; 4],
+    pub leaves: [Color; 6],
+
+    // Ground
+    pub grass: Color,
+    pub grass_dark: Color,
+    pub flowers: [Color; 4],
+    pub soil: Color,
+    /// Trees and pines.
+    pub foliage: Color,
+    pub fence: Color,
+    pub mailbox: Color,
+
+    // Buildings
+    pub wood: Color,
+    pub roof: Color,
+    pub door: Color,
+    /// Chimney stacks and the airplane's belly.
+    pub stone: Color,
+    /// Pipes, ledges, posts and other dark details.
+    pub trim: Color,
+    /// Windows in daylight.
+    pub window_day: Color,
+    pub window_lit: Color,
+    pub window_flicker: Color,
+    pub window_dark: Color,
+
+    // Scene themes
+    /// Mountains in the cabin scene.
+    pub rock: Color,
+    pub snowcap: Color,
+    pub log: Color,
+    /// Waves near the shore.
+    pub water: Color,
+    pub water_far: Color,
+    pub foam: Color,
+    pub beach: Color,
+    /// Desert dunes.
+    pub sand: Color,
+    pub dune_shadow: Color,
+    /// Pyramids.
+    pub sandstone: Color,
+    /// Walls of city buildings and curbs.
+    pub concrete: Color,
+    pub road_marking: Color,
+    /// Street lamps and the lighthouse lamp.
+    pub lamp: Color,
+    pub beam: Color,
+    pub lighthouse: Color,
+    pub lighthouse_stripe: Color,
+    pub sail: Color,
+}
+
+impl Palette {
+    /// Names of the roles, as used in the `[theme]` config table.
+    pub const ROLES: [&'static str; 59] = [
+        "hud",
+        "border",
+        "text",
+        "accent",
+        "sky",
+        "sun",
+        "moon",
+        "star",
+        "star_dim",
+        "cloud",
+        "cloud_dark",
+        "lightning",
+        "flash",
+        "bird",
+        "airplane",
+        "airplane_window",
+        "airplane_tail",
+        "rain_near",
+        "rain_tint",
+        "rain_far",
+        "snow_near",
+        "snow_far",
+        "fog",
+        "smoke",
+        "fireflies",
+        "leaves",
+        "grass",
+        "grass_dark",
+        "flowers",
+        "soil",
+        "foliage",
+        "fence",
+        "mailbox",
+        "wood",
+        "roof",
+        "door",
+        "stone",
+        "trim",
+        "window_day",
+        "window_lit",
+        "window_flicker",
+        "window_dark",
+        "rock",
+        "snowcap",
+        "log",
+        "water",
+        "water_far",
+        "foam",
+        "beach",
+        "sand",
+        "dune_shadow",
+        "sandstone",
+        "concrete",
+        "road_marking",
+        "lamp",
+        "beam",
+        "lighthouse",
+        "lighthouse_stripe",
+        "sail",
+    ];
+
+    /// The colors of `role`: one for most roles, several for the ones
+    /// cycling through a list.
+    pub fn role_mut(&mut self, role: &str) -> Option<&mut [Color]> {
+        use std::slice::from_mut;
+        let colors: &mut [Color] = match role {
+            "hud" => from_mut(&mut self.hud),
+            "border" => from_mut(&mut self.border),
+            "text" => from_mut(&mut self.text),
+            "accent" => from_mut(&mut self.accent),
+            "sky" => from_mut(&mut self.sky),
+            "sun" => from_mut(&mut self.sun),
+            "moon" => from_mut(&mut self.moon),
+            "star" => from_mut(&mut self.star),
+            "star_dim" => from_mut(&mut self.star_dim),
+            "cloud" => from_mut(&mut self.cloud),
+            "cloud_dark" => from_mut(&mut self.cloud_dark),
+            "lightning" => from_mut(&mut self.lightning),
+            "flash" => from_mut(&mut self.flash),
+            "bird" => from_mut(&mut self.bird),
+            "airplane" => from_mut(&mut self.airplane),
+            "airplane_window" => from_mut(&mut self.airplane_window),
+            "airplane_tail" => from_mut(&mut self.airplane_tail),
+            "rain_near" => from_mut(&mut self.rain_near),
+            "rain_tint" => from_mut(&mut self.rain_tint),
+            "rain_far" => from_mut(&mut self.rain_far),
+            "snow_near" => from_mut(&mut self.snow_near),
+            "snow_far" => from_mut(&mut self.snow_far),
+            "fog" => &mut self.fog,
+            "smoke" => &mut self.smoke,
+            "fireflies" => &mut self.fireflies,
+            "leaves" => &mut self.leaves,
+            "grass" => from_mut(&mut self.grass),
+            "grass_dark" => from_mut(&mut self.grass_dark),
+            "flowers" => &mut self.flowers,
+            "soil" => from_mut(&mut self.soil),
+            "foliage" => from_mut(&mut self.foliage),
+            "fence" => from_mut(&mut self.fence),
+            "mailbox" => from_mut(&mut self.mailbox),
+            "wood" => from_mut(&mut self.wood),
+            "roof" => from_mut(&mut self.roof),
+            "door" => from_mut(&mut self.door),
+            "stone" => from_mut(&mut self.stone),
+            "trim" => from_mut(&mut self.trim),
+            "window_day" => from_mut(&mut self.window_day),
+            "window_lit" => from_mut(&mut self.window_lit),
+            "window_flicker" => from_mut(&mut self.window_flicker),
+            "window_dark" => from_mut(&mut self.window_dark),
+            "rock" => from_mut(&mut self.rock),
+            "snowcap" => from_mut(&mut self.snowcap),
+            "log" => from_mut(&mut self.log),
+            "water" => from_mut(&mut self.water),
+            "water_far" => from_mut(&mut self.water_far),
+            "foam" => from_mut(&mut self.foam),
+            "beach" => from_mut(&mut self.beach),
+            "sand" => from_mut(&mut self.sand),
+            "dune_shadow" => from_mut(&mut self.dune_shadow),
+            "sandstone" => from_mut(&mut self.sandstone),
+            "concrete" => from_mut(&mut self.concrete),
+            "road_marking" => from_mut(&mut self.road_marking),
+            "lamp" => from_mut(&mut self.lamp),
+            "beam" => from_mut(&mut self.beam),
+            "lighthouse" => from_mut(&mut self.lighthouse),
+            "lighthouse_stripe" => from_mut(&mut self.lighthouse_stripe),
+            "sail" => from_mut(&mut self.sail),
+            _ => return None,
+        };
+        Some(colors)
+    }
+}
+
+impl Default for Palette {
+    fn default() -> Self {
+        Theme::default().day
+    }
+}
+
+/// The built-in themes.
+#[derive(Deserialize, Debug, Default, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "kebab-case")]
+pub enum ThemeName {
+    #[default]
+    Default,
+    Solarized,
+    Gruvbox,
+    HighContrast,
+    Monochrome,
+}
+
+impl ThemeName {
+    pub fn build(self) -> Theme {
+        match self {
+            Self::Default => Theme::default(),
+            Self::Solarized => Theme::from_scheme(&SOLARIZED),
+            Self::Gruvbox => Theme::from_scheme(&GRUVBOX),
+            Self::HighContrast => Theme::from_scheme(&HIGH_CONTRAST),
+            Self::Monochrome => Theme::from_scheme(&MONOCHROME),
+        }
+    }
+}
+
+impl std::str::FromStr for ThemeName {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_lowercase().replace('_', "-").as_str() {
+            "default" => Ok(Self::Default),
+            "solarized" => Ok(Self::Solarized),
+            "gruvbox" => Ok(Self::Gruvbox),
+            "high-contrast" => Ok(Self::HighContrast),
+            "monochrome" => Ok(Self::Monochrome),
+            _ => Err(format!(
+                "Unknown theme: '{}'. Valid options: default, solarized, gruvbox, high-contrast, monochrome",
+                s
+            )),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Theme {
+    pub day: Palette,
+    pub night: Palette,
+}
+
+impl Theme {
+    pub fn palette(&self, is_day: bool) -> &Palette {
+        if is_day { &self.day } else { &self.night }
+    }
+
+    fn from_scheme(scheme: &Scheme) -> Self {
+        Self {
+            day: scheme.palette(false),
+            night: scheme.palette(true),
+        }
+    }
+}
+
+impl Default for Theme {
+    fn default() -> Self {
+        let day = Palette {
+            hud: Color::Cyan,
+            border: Color::DarkGrey,
+            text: Color::White,
+            accent: Color::Yellow,
+
+            sky: Color::Reset,
+            sun: Color::Yellow,
+            moon: Color::White,
+            star: Color::White,
+            star_dim: Color::DarkGrey,
+            cloud: Color::White,
+            cloud_dark: Color::DarkGrey,
+            lightning: Color::Yellow,
+            flash: Color::White,
+            bird: Color::Yellow,
+            airplane: Color::White,
+            airplane_window: Color::Cyan,
+            airplane_tail: Color::Blue,
+
+            rain_near: Color::White,
+            rain_tint: Color::Cyan,
+            rain_far: Color::DarkGrey,
+            snow_near: Color::White,
+            snow_far: Color::DarkGrey,
+            fog: [Color::Grey, Color::DarkGrey, rgb(120, 120, 120)],
+            smoke: [Color::White, Color::Grey, Color::DarkGrey],
+            fireflies: [
+                Color::Yellow,
+                rgb(200, 255, 100),
+                rgb(150, 200, 80),
+                Color::DarkGrey,
+            ],
+            leaves: [
+                rgb(255, 165, 0),
+                rgb(218, 165, 32),
+                rgb(184, 134, 11),
+                rgb(205, 92, 92),
+                rgb(160, 82, 45),
+                rgb(139, 69, 19),
+            ],
+
+            grass: Color::Green,
+            grass_dark: Color::DarkGreen,
+            flowers: [Color::Magenta, Color::Red, Color::Cyan, Color::Yellow],
+            soil: rgb(101, 67, 33),
+            foliage: Color::DarkGreen,
+            fence: Color::White,
+            mailbox: Color::Blue,
+
+            wood: rgb(210, 180, 140),
+            roof: Color::DarkRed,
+            door: rgb(139, 69, 19),
+            stone: Color::Grey,
+            trim: Color::DarkGrey,
+            window_day: Color::Cyan,
+            window_lit: rgb(255, 196, 87),
+            window_flicker: rgb(170, 120, 50),
+            window_dark: Color::DarkGrey,
+
+            rock: Color::Grey,
+            snowcap: Color::White,
+            log: rgb(139, 90, 43),
+            water: Color::Cyan,
+            water_far: Color::Blue,
+            foam: Color::White,
+            beach: rgb(194, 178, 128),
+            sand: rgb(222, 184, 135),
+            dune_shadow: rgb(190, 150, 100),
+            sandstone: Color::DarkYellow,
+            concrete: Color::Grey,
+            road_marking: Color::Yellow,
+            lamp: Color::Grey,
+            beam: rgb(255, 240, 150),
+            lighthouse: Color::White,
+            lighthouse_stripe: Color::Red,
+            sail: Color::White,
+        };
+
+        let night = Palette {
+            grass: Color::DarkGreen,
+            grass_dark: rgb(0, 50, 0),
+            flowers: [
+                Color::DarkMagenta,
+                Color::DarkRed,
+                Color::Blue,
+                Color::DarkYellow,
+            ],
+            soil: rgb(60, 40, 20),
+            foliage: rgb(0, 50, 0),
+            fence: Color::Grey,
+            mailbox: Color::DarkBlue,
+
+            wood: rgb(100, 70, 50),
+            roof: Color::DarkMagenta,
+
+            rock: Color::DarkGrey,
+            snowcap: Color::Grey,
+            log: rgb(80, 55, 30),
+            water: Color::Blue,
+            water_far: Color::DarkBlue,
+            beach: rgb(110, 100, 70),
+            sand: rgb(120, 95, 65),
+            dune_shadow: rgb(90, 70, 45),
+            sandstone: Color::DarkGrey,
+            concrete: Color::DarkGrey,
+            road_marking: Color::DarkYellow,
+            lamp: Color::Yellow,
+            lighthouse: Color::Grey,
+            sail: Color::Grey,
+            ..day.clone()
+        };
+
+        Self { day, night }
+    }
+}
+
+const fn rgb(r: u8, g: u8, b: u8) -> Color {
+    Color::Rgb { r, g, b }
+}
+
+/// The handful of colors a built-in theme is made from.
+struct Scheme {
+    sky: Color,
+    /// Brightest: text, stars, snow.
+    bright: Color,
+    foreground: Color,
+    grey: Color,
+    dim: Color,
+    red: Color,
+    orange: Color,
+    yellow: Color,
+    green: Color,
+    cyan: Color,
+    blue: Color,
+    magenta: Color,
+    brown: Color,
+    sand: Color,
+    /// Whether the scenery darkens at night. Lights never do.
+    dims_at_night: bool,
+}
+
+impl Scheme {
+    fn palette(&self, night: bool) -> Palette {
+        let d = |color: Color| {
+            if night && self.dims_at_night {
+                darken(color)
+            } else {
+                color
+            }
+        };
+
+        Palette {
+            hud: self.cyan,
+            border: self.dim,
+            text: self.bright,
+            accent: self.yellow,
+
+            sky: d(self.sky),
+            sun: self.yellow,
+            moon: self.bright,
+            star: self.bright,
+            star_dim: self.dim,
+            cloud: d(self.foreground),
+            cloud_dark: self.dim,
+            lightning: self.yellow,
+            flash: self.bright,
+            bird: self.yellow,
+            airplane: self.foreground,
+            airplane_window: self.cyan,
+            airplane_tail: self.blue,
+
+            rain_near: self.foreground,
+            rain_tint: self.cyan,
+            rain_far: self.dim,
+            snow_near: self.bright,
+            snow_far: self.dim,
+            fog: [self.grey, self.dim, d(self.grey)],
+            smoke: [self.foreground, self.grey, self.dim],
+            fireflies: [self.yellow, self.green, darken(self.green), self.dim],
+            leaves: [
+                d(self.orange),
+                d(self.yellow),
+                darken(d(self.yellow)),
+                d(self.red),
+                d(self.brown),
+                darken(d(self.brown)),
+            ],
+
+            grass: d(self.green),
+            grass_dark: darken(d(self.green)),
+            flowers: [d(self.magenta), d(self.red), d(self.cyan), d(self.yellow)],
+            soil: darken(d(self.brown)),
+            foliage: darken(d(self.green)),
+            fence: d(self.foreground),
+            mailbox: d(self.blue),
+
+            wood: d(self.sand),
+            roof: d(self.red),
+            door: d(self.brown),
+            stone: d(self.grey),
+            trim: self.dim,
+            window_day: self.cyan,
+            window_lit: self.yellow,
+            window_flicker: self.orange,
+            window_dark: self.dim,
+
+            rock: d(self.grey),
+            snowcap: d(self.bright),
+            log: d(self.brown),
+            water: d(self.cyan),
+            water_far: d(self.blue),
+            foam: d(self.bright),
+            beach: d(self.sand),
+            sand: d(self.sand),
+            dune_shadow: d(self.orange),
+            sandstone: d(self.yellow),
+            concrete: d(self.grey),
+            road_marking: d(self.yellow),
+            lamp: if night { self.yellow } else { self.grey },
+            beam: self.yellow,
+            lighthouse: d(self.bright),
+            lighthouse_stripe: d(self.red),
+            sail: d(self.foreground),
+        }
+    }
+}
+
+/// A darker shade: RGB colors are scaled down and bright named colors give
+/// way to their dark variants.
+fn darken(color: Color) -> Color {
+    match color {
+        Color::Rgb { r, g, b } => {
+            let scale = |v: u8| (v as u16 * 11 / 20) as u8;
+            rgb(scale(r), scale(g), scale(b))
+        }
+        Color::White => Color::Grey,
+        Color::Grey => Color::DarkGrey,
+        Color::Red => Color::DarkRed,
+        Color::Green => Color::DarkGreen,
+        Color::Yellow => Color::DarkYellow,
+        Color::Blue => Color::DarkBlue,
+        Color::Magenta => Color::DarkMagenta,
+        Color::Cyan => Color::DarkCyan,
+        other => other,
+    }
+}
+
+/// Solarized dark, by Ethan Schoonover.
+const SOLARIZED: Scheme = Scheme {
+    sky: rgb(0, 43, 54),
+    bright: rgb(238, 232, 213),
+    foreground: rgb(147, 161, 161),
+    grey: rgb(131, 148, 150),
+    dim: rgb(88, 110, 117),
+    red: rgb(220, 50, 47),
+    orange: rgb(203, 75, 22),
+    yellow: rgb(181, 137, 0),
+    green: rgb(133, 153, 0),
+    cyan: rgb(42, 161, 152),
+    blue: rgb(38, 139, 210),
+    magenta: rgb(211, 54, 130),
+    brown: rgb(147, 90, 40),
+    sand: rgb(211, 190, 140),
+    dims_at_night: true,
+};
+
+/// Gruvbox dark, by Pavel Pertsev.
+const GRUVBOX: Scheme = Scheme {
+    sky: rgb(40, 40, 40),
+    bright: rgb(251, 241, 199),
+    foreground: rgb(235, 219, 178),
+    grey: rgb(168, 153, 132),
+    dim: rgb(102, 92, 84),
+    red: rgb(251, 73, 52),
+    orange: rgb(254, 128, 25),
+    yellow: rgb(250, 189, 47),
+    green: rgb(184, 187, 38),
+    cyan: rgb(142, 192, 124),
+    blue: rgb(131, 165, 152),
+    magenta: rgb(211, 134, 155),
+    brown: rgb(175, 58, 3),
+    sand: rgb(213, 196, 161),
+    dims_at_night: true,
+};
+
+/// Bright named colors only, the same day and night, readable on any
+/// dark background.
+const HIGH_CONTRAST: Scheme = Scheme {
+    sky: Color::Reset,
+    bright: Color::White,
+    foreground: Color::White,
+    grey: Color::White,
+    dim: Color::Grey,
+    red: Color::Red,
+    orange: Color::Yellow,
+    yellow: Color::Yellow,
+    green: Color::Green,
+    cyan: Color::Cyan,
+    blue: Color::Cyan,
+    magenta: Color::Magenta,
+    brown: Color::Yellow,
+    sand: Color::Yellow,
+    dims_at_night: false,
+};
+
+/// Shades of grey.
+const MONOCHROME: Scheme = Scheme {
+    sky: Color::Reset,
+    bright: Color::White,
+    foreground: Color::White,
+    grey: Color::Grey,
+    dim: Color::DarkGrey,
+    red: Color::Grey,
+    orange: Color::Grey,
+    yellow: Color::White,
+    green: Color::Grey,
+    cyan: Color::White,
+    blue: Color::Grey,
+    magenta: Color::Grey,
+    brown: Color::DarkGrey,
+    sand: Color::Grey,
+    dims_at_night: true,
+};
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const ALL_THEMES: [ThemeName; 5] = [
+        ThemeName::Default,
+        ThemeName::Solarized,
+        ThemeName::Gruvbox,
+        ThemeName::HighContrast,
+        ThemeName::Monochrome,
+    ];
+
+    #[test]
+    fn test_every_role_resolves() {
+        let mut palette = Palette::default();
+        for role in Palette::ROLES {
+            assert!(palette.role_mut(role).is_some(), "{role}");
+        }
+        assert_eq!(palette.role_mut("flowers").unwrap().len(), 4);
+        assert_eq!(palette.role_mut("roof").unwrap().len(), 1);
+        assert!(palette.role_mut("castle").is_none());
+    }
+
+    #[test]
+    fn test_theme_name_from_str() {
+        assert_eq!("gruvbox".parse::<ThemeName>(), Ok(ThemeName::Gruvbox));
+        assert_eq!(
+            "High_Contrast".parse::<ThemeName>(),
+            Ok(ThemeName::HighContrast)
+        );
+        assert!("neon".parse::<ThemeName>().is_err());
+    }
+
+    #[test]
+    fn test_night_is_darker() {
+        for name in ALL_THEMES {
+            let theme = name.build();
+            if name != ThemeName::HighContrast {
+                assert_ne!(theme.day.grass, theme.night.grass, "{name:?}");
+            }
+            // Lights stay lit
+            assert_eq!(theme.day.window_lit, theme.night.window_lit, "{name:?}");
+        }
+    }
+
+    #[test]
+    fn test_monochrome_has_no_hues() {
+        let theme = ThemeName::Monochrome.build();
+        for mut palette in [theme.day, theme.night] {
+            for role in Palette::ROLES {
+                for &color in palette.role_mut(role).unwrap().iter() {
+                    assert!(
+                        matches!(
+                            color,
+                            Color::Reset
+                                | Color::White
+                                | Color::Grey
+                                | Color::DarkGrey
+                                | Color::Black
+                        ),
+                        "{role} is {color:?}"
+                    );
+                }
+            }
+        }
+    }
+}
@@ -3,6 +3,8 @@ use crate::error::WeatherError;
 use crate::weather::normalizer::WeatherNormalizer;
 use crate::weather::provider::WeatherProvider;
 use crate::weather::types::{WeatherData, WeatherLocation, WeatherUnits};
+use serde::Serialize;
+use std::collections::HashMap;
 use std::sync::Arc;
 use std::time::{Duration, Instant};
 use tokio::sync::RwLock;
@@ -10,70 +12,164 @@ use tokio::sync::RwLock;
 #[derive(Clone)]
 pub struct WeatherClient {
     provider: Arc<dyn WeatherProvider>,
-    cache: Arc<RwLock<Option<CachedWeather>>>,
+    cache: Arc<RwLock<HashMap<String, CachedWeather>>>,
     cache_duration: Duration,
+    disk_cache: bool,
 }
 
 struct CachedWeather {
     data: WeatherData,
     fetched_at: Instant,
+    source: DataSource,
+    /// How old the data already was when it was put in this cache.
+    age_when_stored: Duration,
+}
+
+/// Where a piece of weather data came from.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum DataSource {
+    Network,
+    MemoryCache,
+    DiskCache,
+    Simulated,
+}
+
+/// Weather together with where it came from and how old it is.
+#[derive(Debug, Clone)]
+pub struct FetchedWeather {
+    pub data: WeatherData,
+    pub source: DataSource,
+    pub age: Duration,
 }
 
 impl WeatherClient {
     pub fn new(provider: Arc<dyn WeatherProvider>, cache_duration: Duration) -> Self {
         Self {
             provider,
-            cache: Arc::new(RwLock::new(None)),
+            cache: Arc::new(RwLock::new(HashMap::new())),
             cache_duration,
+            disk_cache: true,
         }
     }
 
+    /// Keep the cache in memory only, leaving the on-disk weather cache
+    /// alone. Used with mock providers so tests don't see or overwrite
+    /// real data.
+    #[cfg(any(test, feature = "test-util"))]
+    pub fn without_disk_cache(mut self) -> Self {
+        self.disk_cache = false;
+        self
+    }
+
     pub async fn get_current_weather(
         &self,
         location: &WeatherLocation,
         units: &WeatherUnits,
     ) -> Result<WeatherData, WeatherError> {
+        self.fetch_current_weather(location, units)
+            .await
+            .map(|fetched| fetched.data)
+    }
+
+    /// Like `get_current_weather`, but also reports which cache (if any)
+    /// answered and how old the data is.
+    pub async fn fetch_current_weather(
+        &self,
+        location: &WeatherLocation,
+        units: &WeatherUnits,
+    ) -> Result<FetchedWeather, WeatherError> {
+        let key = cache::make_location_key(location.latitude, location.longitude);
         {
             let cache = self.cache.read().await;
-            if let Some(cached) = cache.as_ref()
+            if let Some(cached) = cache.get(&key)
                 && cached.fetched_at.elapsed() < self.cache_duration
             {
-                return Ok(cached.data.clone());
+                let source = match cached.source {
+                    DataSource::Network => DataSource::MemoryCache,
+                    source => source,
+                };
+                return Ok(FetchedWeather {
+                    data: cached.data.clone(),
+                    source,
+                    age: cached.age_when_stored + cached.fetched_at.elapsed(),
+                });
             }
         }
 
-        if let Some(cached_data) =
-            cache::load_cached_weather(location.latitude, location.longitude).await
+        if self.disk_cache
+            && let Some((cached_data, age)) =
+                cache::load_cached_weather(location.latitude, location.longitude).await
         {
             let mut cache = self.cache.write().await;
-            *cache = Some(CachedWeather {
-                data: cached_data.clone(),
-                fetched_at: Instant::now(),
+            cache.insert(
+                key,
+                CachedWeather {
+                    data: cached_data.clone(),
+                    fetched_at: Instant::now(),
+                    source: DataSource::DiskCache,
+                    age_when_stored: age,
+                },
+            );
+            return Ok(FetchedWeather {
+                data: cached_data,
+                source: DataSource::DiskCache,
+                age,
             });
-            return Ok(cached_data);
         }
 
+        let data = self.refresh_weather(location, units).await?;
+        Ok(FetchedWeather {
+            data,
+            source: DataSource::Network,
+            age: Duration::ZERO,
+        })
+    }
+
+    /// Fetch from the provider, skipping both the in-memory and disk caches.
+    /// The result still replaces what they hold.
+    pub async fn refresh_weather(
+        &self,
+        location: &WeatherLocation,
+        units: &WeatherUnits,
+    ) -> Result<WeatherData, WeatherError> {
         let response = self.provider.get_current_weather(location, units).await?;
 
         let data = WeatherNormalizer::normalize(response);
 
         {
+            let key = cache::make_location_key(location.latitude, location.longitude);
             let mut cache = self.cache.write().await;
-            *cache = Some(CachedWeather {
-                data: data.clone(),
-                fetched_at: Instant::now(),
-            });
+            cache.insert(
+                key,
+                CachedWeather {
+                    data: data.clone(),
+                    fetched_at: Instant::now(),
+                    source: DataSource::Network,
+                    age_when_stored: Duration::ZERO,
+                },
+            );
         }
 
-        cache::save_weather_cache(&data, location.latitude, location.longitude);
+        if self.disk_cache {
+            cache::save_weather_cache(&data, location.latitude, location.longitude).await;
+        }
 
         Ok(data)
     }
 
+    /// The last weather fetched for `location`, however old, without
+    /// touching the network.
+    pub async fn cached_weather(&self, location: &WeatherLocation) -> Option<WeatherData> {
+        let key = cache::make_location_key(location.latitude, location.longitude);
+        let cache = self.cache.read().await;
+        cache.get(&key).map(|cached| cached.data.clone())
+    }
+
     #[allow(dead_code)]
     pub async fn invalidate_cache(&self) {
         let mut cache = self.cache.write().await;
-        *cache = None;
+        cache.clear();
     }
 }
 
@@ -91,6 +187,116 @@ mod tests {
         client.invalidate_cache().await;
 
         let cache = client.cache.read().await;
-        assert!(cache.is_none());
+        assert!(cache.is_empty());
+    }
+
+    struct EchoProvider;
+
+    #[async_trait::async_trait]
+    impl WeatherProvider for EchoProvider {
+        async fn get_current_weather(
+            &self,
+            location: &WeatherLocation,
+            _units: &WeatherUnits,
+        ) -> Result<crate::weather::provider::WeatherProviderResponse, WeatherError> {
+            Ok(crate::weather::provider::WeatherProviderResponse {
+                weather_code: 0,
+                temperature: location.latitude,
+                apparent_temperature: location.latitude,
+                humidity: 50.0,
+                precipitation: 0.0,
+                wind_speed: 0.0,
+                wind_direction: 0.0,
+                cloud_cover: 0.0,
+                pressure: 1013.0,
+                visibility: None,
+                is_day: 1,
+                moon_phase: None,
+                timestamp: "echo".to_string(),
+            })
+        }
+    }
+
+    #[tokio::test]
+    async fn test_cache_is_per_location() {
+        let client = WeatherClient::new(Arc::new(EchoProvider), Duration::from_secs(60))
+            .without_disk_cache();
+        let units = WeatherUnits::default();
+        let first = WeatherLocation {
+            latitude: -71.25,
+            longitude: 12.5,
+            elevation: None,
+        };
+        let second = WeatherLocation {
+            latitude: -72.75,
+            longitude: 12.5,
+            elevation: None,
+        };
+
+        let a = client.get_current_weather(&first, &units).await.unwrap();
+        let b = client.get_current_weather(&second, &units).await.unwrap();
+        assert_eq!(a.temperature, -71.25);
+        assert_eq!(b.temperature, -72.75);
+
+        let cached = client.cached_weather(&first).await.unwrap();
+        assert_eq!(cached.temperature, -71.25);
+    }
+
+    struct CountingProvider(std::sync::atomic::AtomicU32);
+
+    #[async_trait::async_trait]
+    impl WeatherProvider for CountingProvider {
+        async fn get_current_weather(
+            &self,
+            location: &WeatherLocation,
+            units: &WeatherUnits,
+        ) -> Result<crate::weather::provider::WeatherProviderResponse, WeatherError> {
+            let count = self.0.fetch_add(1, std::sync::atomic::Ordering::SeqCst) + 1;
+            let mut response = EchoProvider.get_current_weather(location, units).await?;
+            response.temperature = count as f64;
+            Ok(response)
+        }
+    }
+
+    #[tokio::test]
+    async fn test_refresh_bypasses_cache() {
+        let provider = Arc::new(CountingProvider(std::sync::atomic::AtomicU32::new(0)));
+        let client = WeatherClient::new(provider, Duration::from_secs(60)).without_disk_cache();
+        let units = WeatherUnits::default();
+        let location = WeatherLocation {
+            latitude: -70.5,
+            longitude: 33.0,
+            elevation: None,
+        };
+
+        let first = client.refresh_weather(&location, &units).await.unwrap();
+        let cached = client.get_current_weather(&location, &units).await.unwrap();
+        assert_eq!(first.temperature, 1.0);
+        assert_eq!(cached.temperature, 1.0);
+
+        let refreshed = client.refresh_weather(&location, &units).await.unwrap();
+        assert_eq!(refreshed.temperature, 2.0);
+        let cached = client.get_current_weather(&location, &units).await.unwrap();
+        assert_eq!(cached.temperature, 2.0);
+    }
+
+    #[tokio::test]
+    async fn test_fetch_reports_source() {
+        let client = WeatherClient::new(Arc::new(EchoProvider), Duration::from_secs(60))
+            .without_disk_cache();
+        let units = WeatherUnits::default();
+        let location = WeatherLocation {
+            latitude: -69.25,
+            longitude: 40.0,
+            elevation: None,
+        };
+
+        client.refresh_weather(&location, &units).await.unwrap();
+        let fetched = client
+            .fetch_current_weather(&location, &units)
+            .await
+            .unwrap();
+        assert_eq!(fetched.source, DataSource::MemoryCache);
+        assert!(fetched.age < Duration::from_secs(60));
     }
 }
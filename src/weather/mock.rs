@@ -0,0 +1,220 @@
+use crate::error::{NetworkError, WeatherError};
+use crate::weather::provider::{WeatherProvider, WeatherProviderResponse};
+use crate::weather::types::{WeatherLocation, WeatherUnits};
+use async_trait::async_trait;
+use std::collections::VecDeque;
+use std::sync::Mutex;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::time::Duration;
+
+/// URL reported by the errors these providers make up.
+const MOCK_URL: &str = "mock://weather";
+
+/// A mild, clear afternoon.
+pub fn sample_response() -> WeatherProviderResponse {
+    WeatherProviderResponse {
+        weather_code: 0,
+        temperature: 18.5,
+        apparent_temperature: 17.9,
+        humidity: 55.0,
+        precipitation: 0.0,
+        wind_speed: 9.0,
+        wind_direction: 240.0,
+        cloud_cover: 10.0,
+        pressure: 1015.0,
+        visibility: Some(24_000.0),
+        is_day: 1,
+        moon_phase: Some(0.5),
+        timestamp: "2024-06-01T14:00+00:00".to_string(),
+    }
+}
+
+/// Answers every request with the same response.
+pub struct MockProvider {
+    response: WeatherProviderResponse,
+    delay: Duration,
+    calls: AtomicUsize,
+}
+
+impl MockProvider {
+    pub fn new(response: WeatherProviderResponse) -> Self {
+        Self {
+            response,
+            delay: Duration::ZERO,
+            calls: AtomicUsize::new(0),
+        }
+    }
+
+    /// Wait this long before answering.
+    pub fn with_delay(mut self, delay: Duration) -> Self {
+        self.delay = delay;
+        self
+    }
+
+    /// How many requests have been made so far.
+    pub fn calls(&self) -> usize {
+        self.calls.load(Ordering::SeqCst)
+    }
+}
+
+impl Default for MockProvider {
+    fn default() -> Self {
+        Self::new(sample_response())
+    }
+}
+
+#[async_trait]
+impl WeatherProvider for MockProvider {
+    async fn get_current_weather(
+        &self,
+        _location: &WeatherLocation,
+        _units: &WeatherUnits,
+    ) -> Result<WeatherProviderResponse, WeatherError> {
+        self.calls.fetch_add(1, Ordering::SeqCst);
+        tokio::time::sleep(self.delay).await;
+        Ok(self.response.clone())
+    }
+}
+
+struct Step {
+    delay: Duration,
+    result: Result<WeatherProviderResponse, WeatherError>,
+}
+
+/// Replays a fixed sequence of responses and errors, one per request.
+/// Requests after the script has run out fail with `ConnectionRefused`.
+#[derive(Default)]
+pub struct ScriptedProvider {
+    steps: Mutex<VecDeque<Step>>,
+    calls: AtomicUsize,
+}
+
+impl ScriptedProvider {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Answer the next request with `response`.
+    pub fn respond(self, response: WeatherProviderResponse) -> Self {
+        self.push(Ok(response))
+    }
+
+    /// Fail the next request with `error`.
+    pub fn fail(self, error: WeatherError) -> Self {
+        self.push(Err(error))
+    }
+
+    /// Hold back the most recently added step by `delay`.
+    pub fn delayed(self, delay: Duration) -> Self {
+        if let Some(step) = self.lock().back_mut() {
+            step.delay = delay;
+        }
+        self
+    }
+
+    /// How many requests have been made so far.
+    pub fn calls(&self) -> usize {
+        self.calls.load(Ordering::SeqCst)
+    }
+
+    /// How many scripted steps have not been used yet.
+    pub fn remaining(&self) -> usize {
+        self.lock().len()
+    }
+
+    fn push(self, result: Result<WeatherProviderResponse, WeatherError>) -> Self {
+        self.lock().push_back(Step {
+            delay: Duration::ZERO,
+            result,
+        });
+        self
+    }
+
+    fn lock(&self) -> std::sync::MutexGuard<'_, VecDeque<Step>> {
+        self.steps.lock().unwrap_or_else(|e| e.into_inner())
+    }
+}
+
+#[async_trait]
+impl WeatherProvider for ScriptedProvider {
+    async fn get_current_weather(
+        &self,
+        _location: &WeatherLocation,
+        _units: &WeatherUnits,
+    ) -> Result<WeatherProviderResponse, WeatherError> {
+        self.calls.fetch_add(1, Ordering::SeqCst);
+        let step = self.lock().pop_front();
+        let Some(step) = step else {
+            return Err(WeatherError::Network(NetworkError::ConnectionRefused {
+                url: MOCK_URL.to_string(),
+            }));
+        };
+        tokio::time::sleep(step.delay).await;
+        step.result
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const LOCATION: WeatherLocation = WeatherLocation {
+        latitude: 52.52,
+        longitude: 13.41,
+        elevation: None,
+    };
+
+    #[tokio::test]
+    async fn test_script_plays_in_order() {
+        let mut rainy = sample_response();
+        rainy.weather_code = 61;
+        let provider = ScriptedProvider::new()
+            .respond(sample_response())
+            .fail(WeatherError::Network(NetworkError::Timeout {
+                url: MOCK_URL.to_string(),
+                timeout_secs: 30,
+            }))
+            .respond(rainy);
+        let units = WeatherUnits::default();
+
+        let first = provider.get_current_weather(&LOCATION, &units).await;
+        assert_eq!(first.unwrap().weather_code, 0);
+        let second = provider.get_current_weather(&LOCATION, &units).await;
+        assert!(matches!(
+            second,
+            Err(WeatherError::Network(NetworkError::Timeout { .. }))
+        ));
+        let third = provider.get_current_weather(&LOCATION, &units).await;
+        assert_eq!(third.unwrap().weather_code, 61);
+
+        let exhausted = provider.get_current_weather(&LOCATION, &units).await;
+        assert!(matches!(
+            exhausted,
+            Err(WeatherError::Network(
+                NetworkError::ConnectionRefused { .. }
+            ))
+        ));
+        assert_eq!(provider.calls(), 4);
+    }
+
+    #[tokio::test]
+    async fn test_delay_applies_to_last_step() {
+        let provider = ScriptedProvider::new()
+            .respond(sample_response())
+            .respond(sample_response())
+            .delayed(Duration::from_millis(50));
+        let units = WeatherUnits::default();
+
+        let start = std::time::Instant::now();
+        provider
+            .get_current_weather(&LOCATION, &units)
+            .await
+            .unwrap();
+        assert!(start.elapsed() < Duration::from_millis(50));
+        provider
+            .get_current_weather(&LOCATION, &units)
+            .await
+            .unwrap();
+        assert!(start.elapsed() >= Duration::from_millis(50));
+    }
+}
@@ -1,14 +1,20 @@
 pub mod client;
+#[cfg(any(test, feature = "test-util"))]
+pub mod mock;
 pub mod normalizer;
 pub mod open_meteo;
 pub mod provider;
 pub mod types;
 pub mod units;
 
-pub use client::WeatherClient;
+pub use client::{DataSource, FetchedWeather, WeatherClient};
+#[cfg(any(test, feature = "test-util"))]
+pub use mock::{MockProvider, ScriptedProvider};
 pub use open_meteo::OpenMeteoProvider;
 pub use types::{
     FogIntensity, RainIntensity, SnowIntensity, WeatherCondition, WeatherConditions, WeatherData,
     WeatherLocation, WeatherUnits,
 };
-pub use units::{format_precipitation, format_temperature, format_wind_speed};
+pub use units::{
+    format_precipitation, format_temperature, format_visibility, format_wind_speed, wind_compass,
+};
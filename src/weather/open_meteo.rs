@@ -9,15 +9,19 @@ use serde::Deserialize;
 use std::time::Duration;
 
 const OPEN_METEO_BASE_URL: &str = "https://api.open-meteo.com/v1/forecast";
+const REQUEST_TIMEOUT: Duration = Duration::from_secs(30);
 
 pub struct OpenMeteoProvider {
     client: reqwest::Client,
     base_url: String,
+    timeout: Duration,
 }
 
 #[derive(Debug, Deserialize)]
 struct OpenMeteoResponse {
     current: CurrentWeather,
+    #[serde(default)]
+    utc_offset_seconds: i32,
 }
 
 #[derive(Debug, Deserialize)]
@@ -39,9 +43,15 @@ struct CurrentWeather {
 
 impl OpenMeteoProvider {
     pub fn new() -> Self {
+        Self::with_base_url(OPEN_METEO_BASE_URL, REQUEST_TIMEOUT)
+    }
+
+    /// Talk to an Open-Meteo compatible API at `base_url` instead, giving up
+    /// on a request after `timeout`.
+    pub fn with_base_url(base_url: &str, timeout: Duration) -> Self {
         let client = reqwest::Client::builder()
-            .timeout(Duration::from_secs(30))
-            .connect_timeout(Duration::from_secs(10))
+            .timeout(timeout)
+            .connect_timeout(timeout.min(Duration::from_secs(10)))
             .build()
             .unwrap_or_else(|e| {
                 eprintln!("Warning: Failed to create custom HTTP client: {}", e);
@@ -51,7 +61,8 @@ impl OpenMeteoProvider {
 
         Self {
             client,
-            base_url: OPEN_METEO_BASE_URL.to_string(),
+            base_url: base_url.to_string(),
+            timeout,
         }
     }
 
@@ -78,6 +89,20 @@ impl OpenMeteoProvider {
         }
     }
 
+    /// Times come back in the location's own timezone; attach the offset so
+    /// the age of the data can be worked out from anywhere.
+    fn timestamp_with_offset(time: &str, offset_seconds: i32) -> String {
+        let sign = if offset_seconds < 0 { '-' } else { '+' };
+        let offset = offset_seconds.unsigned_abs();
+        format!(
+            "{}{}{:02}:{:02}",
+            time,
+            sign,
+            offset / 3600,
+            offset % 3600 / 60
+        )
+    }
+
     fn build_url(&self, location: &WeatherLocation, units: &WeatherUnits) -> String {
         format!(
             "{}?latitude={}&longitude={}&current=temperature_2m,relative_humidity_2m,apparent_temperature,is_day,precipitation,weather_code,cloud_cover,surface_pressure,wind_speed_10m,wind_direction_10m,visibility&temperature_unit={}&wind_speed_unit={}&precipitation_unit={}&timezone=auto",
@@ -105,17 +130,17 @@ impl WeatherProvider for OpenMeteoProvider {
         units: &WeatherUnits,
     ) -> Result<WeatherProviderResponse, WeatherError> {
         let url = self.build_url(location, units);
+        let network_error =
+            |e| WeatherError::Network(NetworkError::from_reqwest(e, &url, self.timeout.as_secs()));
         let response = self
             .client
             .get(&url)
             .send()
             .await
-            .map_err(|e| WeatherError::Network(NetworkError::from_reqwest(e, &url, 30)))?;
+            .and_then(|response| response.error_for_status())
+            .map_err(network_error)?;
 
-        let data: OpenMeteoResponse = response
-            .json()
-            .await
-            .map_err(|e| WeatherError::Network(NetworkError::from_reqwest(e, &url, 30)))?;
+        let data: OpenMeteoResponse = response.json().await.map_err(network_error)?;
 
         let moon_phase = Some(0.5);
 
@@ -135,7 +160,7 @@ impl WeatherProvider for OpenMeteoProvider {
             visibility: data.current.visibility,
             is_day: data.current.is_day,
             moon_phase,
-            timestamp: data.current.time,
+            timestamp: Self::timestamp_with_offset(&data.current.time, data.utc_offset_seconds),
         })
     }
 }
@@ -167,4 +192,16 @@ mod tests {
             "mm"
         );
     }
+
+    #[test]
+    fn test_timestamp_with_offset() {
+        assert_eq!(
+            OpenMeteoProvider::timestamp_with_offset("2024-01-01T12:00", 3600),
+            "2024-01-01T12:00+01:00"
+        );
+        assert_eq!(
+            OpenMeteoProvider::timestamp_with_offset("2024-01-01T12:00", -16200),
+            "2024-01-01T12:00-04:30"
+        );
+    }
 }
@@ -166,7 +166,7 @@ pub struct WeatherData {
     pub timestamp: String,
 }
 
-#[derive(Debug, Clone, Copy, serde::Deserialize)]
+#[derive(Debug, Clone, Copy, serde::Serialize, serde::Deserialize)]
 #[serde(default)]
 pub struct WeatherUnits {
     pub temperature: TemperatureUnit,
@@ -198,7 +198,7 @@ impl Default for WeatherUnits {
     }
 }
 
-#[derive(Debug, Clone, Copy)]
+#[derive(Debug, Clone, Copy, PartialEq)]
 #[allow(dead_code)]
 pub struct WeatherLocation {
     pub latitude: f64,
@@ -63,6 +63,29 @@ pub fn format_precipitation(mm: f64, unit: PrecipitationUnit) -> (f64, &'static
     }
 }
 
+/// Visibility in km, or miles alongside imperial precipitation.
+pub fn format_visibility(meters: f64, unit: PrecipitationUnit) -> (f64, &'static str) {
+    match unit {
+        PrecipitationUnit::Mm => (meters / 1000.0, "km"),
+        PrecipitationUnit::Inch => (meters / 1609.344, "mi"),
+    }
+}
+
+/// An arrow pointing where the wind blows to and the 16-point compass name
+/// of where it comes from, for a meteorological direction in degrees.
+pub fn wind_compass(degrees: f64) -> (char, &'static str) {
+    const ARROWS: [char; 8] = ['↑', '↗', '→', '↘', '↓', '↙', '←', '↖'];
+    const POINTS: [&str; 16] = [
+        "N", "NNE", "NE", "ENE", "E", "ESE", "SE", "SSE", "S", "SSW", "SW", "WSW", "W", "WNW",
+        "NW", "NNW",
+    ];
+
+    let degrees = degrees.rem_euclid(360.0);
+    let arrow = ARROWS[(((degrees + 180.0) / 45.0).round() as usize) % 8];
+    let point = POINTS[((degrees / 22.5).round() as usize) % 16];
+    (arrow, point)
+}
+
 pub fn normalize_temperature(value: f64, unit: TemperatureUnit) -> f64 {
     match unit {
         TemperatureUnit::Celsius => value,
@@ -1,4 +1,4 @@
-use weathr::error::{ConfigError, GeolocationError, NetworkError, TerminalError};
+use weathr::error::{ConfigError, GeolocationError, NetworkError, TerminalError, WeatherError};
 
 #[test]
 fn test_config_error_kind() {
@@ -122,3 +122,21 @@ fn test_geolocation_error_all_network_variants() {
     assert!(!msg.contains("Network error:"));
     assert!(!msg.contains("Cannot auto-detect location:"));
 }
+
+#[test]
+fn test_weather_error_exit_codes() {
+    let timeout = WeatherError::Network(NetworkError::Timeout {
+        url: "https://api.open-meteo.com".to_string(),
+        timeout_secs: 30,
+    });
+    assert_eq!(timeout.exit_code(), 75);
+
+    let config = WeatherError::Config(ConfigError::NoConfigDir);
+    assert_eq!(config.exit_code(), 78);
+
+    let geolocation = WeatherError::Geolocation(GeolocationError::RetriesExhausted { attempts: 3 });
+    assert_eq!(geolocation.exit_code(), 69);
+
+    let terminal = WeatherError::Terminal(TerminalError::NotATty);
+    assert_ne!(terminal.exit_code(), 0);
+}
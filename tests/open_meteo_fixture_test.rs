@@ -0,0 +1,175 @@
+use std::io::{Read, Write};
+use std::net::TcpListener;
+use std::thread;
+use std::time::Duration;
+use weathr::error::{NetworkError, WeatherError};
+use weathr::weather::provider::{WeatherProvider, WeatherProviderResponse};
+use weathr::weather::{OpenMeteoProvider, WeatherLocation, WeatherUnits};
+
+const LOCATION: WeatherLocation = WeatherLocation {
+    latitude: 52.52,
+    longitude: 13.41,
+    elevation: None,
+};
+
+const FORECAST: &str = r#"{
+    "latitude": 52.52,
+    "longitude": 13.419998,
+    "utc_offset_seconds": 7200,
+    "current": {
+        "time": "2024-06-01T14:00",
+        "interval": 900,
+        "temperature_2m": 21.4,
+        "relative_humidity_2m": 48,
+        "apparent_temperature": 20.1,
+        "is_day": 1,
+        "precipitation": 0.0,
+        "weather_code": 3,
+        "cloud_cover": 87,
+        "surface_pressure": 1008.2,
+        "wind_speed_10m": 14.3,
+        "wind_direction_10m": 250,
+        "visibility": 24140.0
+    }
+}"#;
+
+/// Serve one canned HTTP response on a local port, after waiting `delay`,
+/// and return the base URL to point the provider at.
+fn serve(status: &str, body: &'static str, delay: Duration) -> String {
+    let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+    let address = listener.local_addr().unwrap();
+    let status = status.to_string();
+    thread::spawn(move || {
+        let Ok((mut stream, _)) = listener.accept() else {
+            return;
+        };
+        let mut request = Vec::new();
+        let mut buffer = [0; 1024];
+        while !request.windows(4).any(|w| w == b"\r\n\r\n") {
+            match stream.read(&mut buffer) {
+                Ok(0) | Err(_) => return,
+                Ok(n) => request.extend_from_slice(&buffer[..n]),
+            }
+        }
+        thread::sleep(delay);
+        let _ = write!(
+            stream,
+            "HTTP/1.1 {status}\r\nContent-Type: application/json\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{body}",
+            body.len()
+        );
+    });
+    format!("http://{address}/v1/forecast")
+}
+
+async fn fetch(
+    status: &str,
+    body: &'static str,
+    delay: Duration,
+) -> Result<WeatherProviderResponse, WeatherError> {
+    let provider =
+        OpenMeteoProvider::with_base_url(&serve(status, body, delay), Duration::from_secs(5));
+    provider
+        .get_current_weather(&LOCATION, &WeatherUnits::default())
+        .await
+}
+
+#[tokio::test]
+async fn test_parses_forecast() {
+    let response = fetch("200 OK", FORECAST, Duration::ZERO)
+        .await
+        .expect("fixture forecast should parse");
+
+    assert_eq!(response.weather_code, 3);
+    assert_eq!(response.temperature, 21.4);
+    assert_eq!(response.apparent_temperature, 20.1);
+    assert_eq!(response.humidity, 48.0);
+    assert_eq!(response.cloud_cover, 87.0);
+    assert_eq!(response.pressure, 1008.2);
+    assert_eq!(response.wind_direction, 250.0);
+    assert_eq!(response.visibility, Some(24140.0));
+    assert_eq!(response.is_day, 1);
+    assert_eq!(response.timestamp, "2024-06-01T14:00+02:00");
+}
+
+#[tokio::test]
+async fn test_timeout_is_classified() {
+    let provider = OpenMeteoProvider::with_base_url(
+        &serve("200 OK", FORECAST, Duration::from_secs(3)),
+        Duration::from_millis(200),
+    );
+    let error = provider
+        .get_current_weather(&LOCATION, &WeatherUnits::default())
+        .await
+        .expect_err("slow server should time out");
+
+    assert!(matches!(
+        error,
+        WeatherError::Network(NetworkError::Timeout { .. })
+    ));
+    assert_eq!(error.exit_code(), 75);
+}
+
+#[tokio::test]
+async fn test_client_error_status_is_classified() {
+    let error = fetch(
+        "400 Bad Request",
+        r#"{"error": true, "reason": "Latitude must be in range of -90 to 90°."}"#,
+        Duration::ZERO,
+    )
+    .await
+    .expect_err("400 should fail");
+
+    match error {
+        WeatherError::Network(NetworkError::HttpError { status, url, .. }) => {
+            assert_eq!(status, 400);
+            assert!(url.contains("latitude=52.52"));
+        }
+        other => panic!("expected HttpError, got {other:?}"),
+    }
+}
+
+#[tokio::test]
+async fn test_server_error_status_is_classified() {
+    let error = fetch("503 Service Unavailable", "{}", Duration::ZERO)
+        .await
+        .expect_err("503 should fail");
+
+    assert!(matches!(
+        error,
+        WeatherError::Network(NetworkError::HttpError { status: 503, .. })
+    ));
+    assert_eq!(error.exit_code(), 69);
+}
+
+#[tokio::test]
+async fn test_malformed_json_is_classified() {
+    let error = fetch(
+        "200 OK",
+        r#"{"current": {"time": "2024-06-01T14:00""#,
+        Duration::ZERO,
+    )
+    .await
+    .expect_err("truncated body should fail");
+
+    assert!(matches!(
+        error,
+        WeatherError::Network(NetworkError::JsonParse { .. })
+    ));
+    assert_eq!(error.exit_code(), 65);
+}
+
+#[tokio::test]
+async fn test_missing_fields_are_classified() {
+    let error = fetch(
+        "200 OK",
+        r#"{"current": {"time": "2024-06-01T14:00"}}"#,
+        Duration::ZERO,
+    )
+    .await
+    .expect_err("incomplete forecast should fail");
+
+    assert!(matches!(
+        error,
+        WeatherError::Network(NetworkError::JsonParse { .. })
+    ));
+}
@@ -1,71 +1,139 @@
 use std::sync::Arc;
 use std::time::Duration;
-use weathr::weather::{OpenMeteoProvider, WeatherClient, WeatherLocation, WeatherUnits};
+use weathr::error::{NetworkError, WeatherError};
+use weathr::weather::mock::sample_response;
+use weathr::weather::{
+    DataSource, MockProvider, ScriptedProvider, WeatherClient, WeatherLocation, WeatherUnits,
+};
+
+const LOCATION: WeatherLocation = WeatherLocation {
+    latitude: 52.52,
+    longitude: 13.41,
+    elevation: None,
+};
+
+fn timeout() -> WeatherError {
+    WeatherError::Network(NetworkError::Timeout {
+        url: "mock://weather".to_string(),
+        timeout_secs: 30,
+    })
+}
 
 #[tokio::test]
 async fn test_weather_client_integration_cache_behavior() {
-    let provider = Arc::new(OpenMeteoProvider::new());
-    let client = WeatherClient::new(provider, Duration::from_secs(60));
-
-    let location = WeatherLocation {
-        latitude: 52.52,
-        longitude: 13.41,
-        elevation: None,
-    };
-
+    let provider = Arc::new(ScriptedProvider::new().respond(sample_response()));
+    let client = WeatherClient::new(provider.clone(), Duration::from_secs(60)).without_disk_cache();
     let units = WeatherUnits::default();
 
     let weather1 = client
-        .get_current_weather(&location, &units)
+        .fetch_current_weather(&LOCATION, &units)
         .await
         .expect("First fetch should succeed");
 
     let weather2 = client
-        .get_current_weather(&location, &units)
+        .fetch_current_weather(&LOCATION, &units)
         .await
-        .expect("Second fetch should succeed");
+        .expect("Second fetch should be answered from the cache");
 
+    assert_eq!(weather1.source, DataSource::Network);
+    assert_eq!(weather2.source, DataSource::MemoryCache);
     assert_eq!(
-        weather1.timestamp, weather2.timestamp,
+        weather1.data.timestamp, weather2.data.timestamp,
         "Second fetch should return cached data"
     );
+    assert_eq!(provider.calls(), 1);
 }
 
 #[tokio::test]
 async fn test_weather_client_integration_cache_invalidation() {
-    let provider = Arc::new(OpenMeteoProvider::new());
-    let client = WeatherClient::new(provider, Duration::from_secs(60));
-
-    let location = WeatherLocation {
-        latitude: 52.52,
-        longitude: 13.41,
-        elevation: None,
-    };
-
+    let mut later = sample_response();
+    later.temperature = 21.0;
+    later.timestamp = "2024-06-01T15:00+00:00".to_string();
+    let provider = Arc::new(
+        ScriptedProvider::new()
+            .respond(sample_response())
+            .respond(later),
+    );
+    let client = WeatherClient::new(provider.clone(), Duration::from_secs(60)).without_disk_cache();
     let units = WeatherUnits::default();
 
-    let _weather1 = client
-        .get_current_weather(&location, &units)
+    let weather1 = client
+        .get_current_weather(&LOCATION, &units)
         .await
         .expect("First fetch should succeed");
 
     client.invalidate_cache().await;
 
     let weather2 = client
-        .get_current_weather(&location, &units)
+        .get_current_weather(&LOCATION, &units)
         .await
         .expect("Fetch after invalidation should succeed");
 
-    assert!(
-        weather2.temperature >= -90.0 && weather2.temperature <= 60.0,
-        "Weather data should still be valid after cache invalidation"
+    assert_eq!(weather1.temperature, 18.5);
+    assert_eq!(
+        weather2.temperature, 21.0,
+        "Invalidation should force a new request"
     );
+    assert_eq!(provider.remaining(), 0);
+}
+
+#[tokio::test]
+async fn test_weather_client_integration_error_keeps_cached_weather() {
+    let provider = Arc::new(
+        ScriptedProvider::new()
+            .respond(sample_response())
+            .fail(timeout()),
+    );
+    let client = WeatherClient::new(provider, Duration::from_secs(60)).without_disk_cache();
+    let units = WeatherUnits::default();
+
+    client
+        .get_current_weather(&LOCATION, &units)
+        .await
+        .expect("First fetch should succeed");
+
+    let error = client
+        .refresh_weather(&LOCATION, &units)
+        .await
+        .expect_err("Scripted timeout should surface");
+    assert!(matches!(
+        error,
+        WeatherError::Network(NetworkError::Timeout { .. })
+    ));
+    assert!(error.to_string().contains("timeout"));
+
+    let cached = client
+        .cached_weather(&LOCATION)
+        .await
+        .expect("Earlier weather should still be cached");
+    assert_eq!(cached.temperature, 18.5);
+}
+
+#[tokio::test]
+async fn test_weather_client_integration_slow_provider() {
+    let provider = Arc::new(MockProvider::default().with_delay(Duration::from_millis(200)));
+    let client = WeatherClient::new(provider.clone(), Duration::from_secs(60)).without_disk_cache();
+    let units = WeatherUnits::default();
+
+    let slow = tokio::time::timeout(
+        Duration::from_millis(20),
+        client.get_current_weather(&LOCATION, &units),
+    )
+    .await;
+    assert!(slow.is_err(), "Delayed response should not arrive early");
+
+    let weather = client
+        .get_current_weather(&LOCATION, &units)
+        .await
+        .expect("Delayed response should eventually arrive");
+    assert_eq!(weather.temperature, 18.5);
+    assert_eq!(provider.calls(), 2);
 }
 
 #[tokio::test]
 async fn test_weather_client_integration_realistic_weather_ranges() {
-    let provider = Arc::new(OpenMeteoProvider::new());
-    let client = WeatherClient::new(provider, Duration::from_secs(60));
+    let provider = Arc::new(MockProvider::default());
+    let client = WeatherClient::new(provider, Duration::from_secs(60)).without_disk_cache();
 
     let location = WeatherLocation {
         latitude: 0.0,